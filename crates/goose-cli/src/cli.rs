@@ -2,20 +2,33 @@ use anyhow::Result;
 use clap::{Args, Parser, Subcommand};
 
 use goose::config::{Config, ExtensionConfig};
+use goose::recipe::steps::render_with_step_outputs;
+use goose::scheduler::{
+    CatchUpPolicy, CompletionFilter, JobRetryPolicy, JobTrigger, OverlapPolicy, RetryableFailure,
+    ScheduledJobUpdate,
+};
+use goose::session::crypto::EncryptionMode;
 
 use crate::commands::bench::agent_generator;
 use crate::commands::configure::handle_configure;
 use crate::commands::info::handle_info;
 use crate::commands::mcp::run_server;
 use crate::commands::project::{handle_project_default, handle_projects_interactive};
-use crate::commands::recipe::{handle_deeplink, handle_list, handle_validate};
+use crate::commands::recipe::{
+    handle_deeplink, handle_install, handle_keygen, handle_list, handle_sign, handle_test,
+    handle_update, handle_validate, ValidateFormat,
+};
 // Import the new handlers from commands::schedule
 use crate::commands::schedule::{
-    handle_schedule_add, handle_schedule_cron_help, handle_schedule_list, handle_schedule_remove,
-    handle_schedule_run_now, handle_schedule_services_status, handle_schedule_services_stop,
-    handle_schedule_sessions,
+    handle_schedule_add, handle_schedule_cron_help, handle_schedule_history, handle_schedule_list,
+    handle_schedule_remove, handle_schedule_run_now, handle_schedule_services_status,
+    handle_schedule_services_stop, handle_schedule_sessions,
+};
+use crate::commands::session::{
+    handle_session_diff, handle_session_doctor, handle_session_encryption, handle_session_list,
+    handle_session_remove, handle_session_repair, handle_session_stats, handle_session_to_recipe,
+    DiffFormat,
 };
-use crate::commands::session::{handle_session_list, handle_session_remove};
 use crate::recipes::extract_from_cli::extract_recipe_info_from_cli;
 use crate::recipes::recipe::{explain_recipe, render_recipe_as_yaml};
 use crate::session;
@@ -25,6 +38,7 @@ use goose_bench::runners::bench_runner::BenchRunner;
 use goose_bench::runners::eval_runner::EvalRunner;
 use goose_bench::runners::metric_aggregator::MetricAggregator;
 use goose_bench::runners::model_runner::ModelRunner;
+use rand::{distributions::Alphanumeric, Rng};
 use std::io::Read;
 use std::path::PathBuf;
 
@@ -96,6 +110,9 @@ enum SessionCommand {
             long_help = "Sort sessions by date in ascending order (oldest first). Default is descending order (newest first)."
         )]
         ascending: bool,
+
+        #[arg(long, help = "Show the artifacts collected for each session")]
+        artifacts: bool,
     },
     #[command(about = "Remove sessions. Runs interactively if no ID or regex is provided.")]
     Remove {
@@ -117,6 +134,108 @@ enum SessionCommand {
         )]
         output: Option<PathBuf>,
     },
+    #[command(about = "Show where time and tokens went in a session")]
+    Stats {
+        #[command(flatten)]
+        identifier: Option<Identifier>,
+
+        #[arg(
+            short,
+            long,
+            help = "Output format (text, json)",
+            default_value = "text"
+        )]
+        format: String,
+    },
+    #[command(about = "Compare two sessions turn by turn")]
+    Diff {
+        #[arg(help = "Name of the first session")]
+        a: String,
+
+        #[arg(help = "Name of the second session")]
+        b: String,
+
+        #[arg(
+            short,
+            long,
+            value_enum,
+            help = "Output format",
+            default_value = "text"
+        )]
+        format: DiffFormat,
+
+        #[arg(
+            short,
+            long,
+            help = "Output file path (default: stdout)",
+            long_help = "Path to save the diff. If not provided, output will be sent to stdout"
+        )]
+        output: Option<PathBuf>,
+    },
+    #[command(
+        about = "Create a recipe from a session",
+        long_about = "Create a recipe from a session. Values that vary between runs, such as file names, URLs and ticket IDs, become parameters, the extensions are the ones whose tools were called and the verification commands run at the end become success checks."
+    )]
+    ToRecipe {
+        #[arg(help = "Name of the session")]
+        session_id: String,
+
+        #[arg(
+            short,
+            long,
+            help = "Output file path (default: stdout)",
+            long_help = "Path to save the recipe YAML. If not provided, the recipe will be sent to stdout"
+        )]
+        output: Option<PathBuf>,
+    },
+    #[command(about = "Repair a damaged session file")]
+    Repair {
+        #[command(flatten)]
+        identifier: Option<Identifier>,
+    },
+    #[command(about = "Encrypt all session files at rest and keep new sessions encrypted")]
+    Encrypt {
+        #[arg(
+            long,
+            help = "Derive the key from a passphrase instead of storing it in the keyring",
+            long_help = "Derive the key from a passphrase (read from GOOSE_SESSION_PASSPHRASE or prompted for) instead of storing a random key in the system keyring"
+        )]
+        passphrase: bool,
+    },
+    #[command(about = "Decrypt all session files and store new sessions in plaintext")]
+    Decrypt {},
+    #[command(about = "Check a session for conversation issues and optionally fix them")]
+    Doctor {
+        #[command(flatten)]
+        identifier: Option<Identifier>,
+
+        #[arg(
+            short,
+            long,
+            help = "Output format (text, json)",
+            default_value = "text"
+        )]
+        format: String,
+
+        #[arg(long, help = "Rewrite the session with the issues repaired")]
+        fix: bool,
+
+        #[arg(
+            long = "orphaned-tool-requests",
+            value_name = "POLICY",
+            help = "How to repair tool requests without a response (drop, synthesize)",
+            default_value = "drop"
+        )]
+        orphaned_tool_requests: String,
+
+        #[arg(
+            long = "consecutive-messages",
+            value_name = "POLICY",
+            help = "How to repair consecutive messages with the same role (merge, drop)",
+            default_value = "merge"
+        )]
+        consecutive_messages: String,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -128,14 +247,144 @@ enum SchedulerCommand {
         #[arg(
             long,
             help = "Cron expression for the schedule",
-            long_help = "Cron expression for when to run the job. Examples:\n  '0 * * * *'     - Every hour at minute 0\n  '0 */2 * * *'   - Every 2 hours\n  '@hourly'       - Every hour (shorthand)\n  '0 9 * * *'     - Every day at 9:00 AM\n  '0 9 * * 1'     - Every Monday at 9:00 AM\n  '0 0 1 * *'     - First day of every month at midnight"
+            long_help = "Cron expression for when to run the job. Examples:\n  '0 * * * *'     - Every hour at minute 0\n  '0 */2 * * *'   - Every 2 hours\n  '@hourly'       - Every hour (shorthand)\n  '0 9 * * *'     - Every day at 9:00 AM\n  '0 9 * * 1'     - Every Monday at 9:00 AM\n  '0 0 1 * *'     - First day of every month at midnight",
+            required_unless_present_any = ["watch", "webhook", "after"]
         )]
-        cron: String,
+        cron: Option<String>,
         #[arg(
             long,
             help = "Recipe source (path to file, or base64 encoded recipe string)"
         )]
         recipe_source: String,
+        #[arg(
+            long,
+            value_name = "GLOB",
+            help = "Run the job when files matching the pattern change (can be specified multiple times)",
+            long_help = "Run the job when files matching the glob pattern change. Relative patterns are resolved against --working-dir. Can be specified multiple times.",
+            action = clap::ArgAction::Append
+        )]
+        watch: Vec<String>,
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            help = "Seconds watched files must stay unchanged before the job runs",
+            default_value = "5",
+            requires = "watch"
+        )]
+        debounce: u64,
+        #[arg(
+            long,
+            help = "Run the job when its goose-server webhook is called; prints the generated secret"
+        )]
+        webhook: bool,
+        #[arg(
+            long = "webhook-param",
+            value_name = "NAME",
+            help = "Recipe parameter receiving the webhook request body",
+            requires = "webhook"
+        )]
+        webhook_param: Option<String>,
+        #[arg(
+            long,
+            value_name = "JOB_ID",
+            help = "Run the job when another scheduled job finishes"
+        )]
+        after: Option<String>,
+        #[arg(
+            long = "after-status",
+            value_name = "STATUS",
+            help = "Outcomes of --after that run the job (success, failure, any)",
+            default_value = "success",
+            requires = "after"
+        )]
+        after_status: CompletionFilter,
+        #[arg(
+            long,
+            value_name = "KEY=VALUE",
+            help = "Values for the recipe's parameters (e.g., --params repo=block/goose)",
+            long_help = "Key-value parameters passed to the recipe on every run. Can be specified multiple times.",
+            action = clap::ArgAction::Append,
+            value_parser = parse_key_val,
+        )]
+        params: Vec<(String, String)>,
+        #[arg(
+            long,
+            help = "Provider to run the job with instead of the configured one"
+        )]
+        provider: Option<String>,
+        #[arg(long, help = "Model to run the job with instead of the configured one")]
+        model: Option<String>,
+        #[arg(
+            long = "working-dir",
+            value_name = "DIR",
+            help = "Working directory recorded for the job's sessions and used to collect their artifacts"
+        )]
+        working_dir: Option<String>,
+        #[arg(
+            long = "with-builtin",
+            value_name = "NAME",
+            help = "Add builtin extensions to the ones declared by the recipe (e.g., 'developer' or multiple: 'developer,memory')",
+            value_delimiter = ','
+        )]
+        builtins: Vec<String>,
+        #[arg(
+            long,
+            value_name = "POLICY",
+            help = "What to do when the job starts while a run of it is still going (skip, queue, kill_previous)",
+            default_value = "skip"
+        )]
+        overlap: OverlapPolicy,
+        #[arg(
+            long = "catch-up",
+            value_name = "POLICY",
+            help = "Which cron runs missed while goose was not running to make up for on startup (none, latest, all)",
+            default_value = "none"
+        )]
+        catch_up: CatchUpPolicy,
+        #[arg(
+            long = "catch-up-window",
+            value_name = "SECONDS",
+            help = "Only make up for missed runs this recent",
+            requires = "catch_up"
+        )]
+        catch_up_window: Option<u64>,
+        #[arg(
+            long = "max-runtime",
+            value_name = "SECONDS",
+            help = "Kill runs going longer than this"
+        )]
+        max_runtime: Option<u64>,
+        #[arg(
+            long = "retry-attempts",
+            value_name = "N",
+            help = "Attempts per run when runs fail, the first one included"
+        )]
+        retry_attempts: Option<u32>,
+        #[arg(
+            long = "retry-backoff",
+            value_name = "SECONDS",
+            help = "Wait before the first retry, doubled after each one",
+            default_value = "60",
+            requires = "retry_attempts"
+        )]
+        retry_backoff: u64,
+        #[arg(
+            long = "retry-max-backoff",
+            value_name = "SECONDS",
+            help = "Longest wait between two attempts",
+            default_value = "3600",
+            requires = "retry_attempts"
+        )]
+        retry_max_backoff: u64,
+        #[arg(
+            long = "retry-on",
+            value_name = "FAILURE",
+            help = "Failures to retry (provider, success_check)",
+            value_delimiter = ',',
+            default_value = "provider",
+            requires = "retry_attempts"
+        )]
+        retry_on: Vec<RetryableFailure>,
     },
     #[command(about = "List all scheduled jobs")]
     List {},
@@ -154,6 +403,22 @@ enum SchedulerCommand {
         #[arg(long, help = "Maximum number of sessions to return")]
         limit: Option<u32>,
     },
+    /// Show the run history of a schedule
+    #[command(about = "Show the run history of a schedule")]
+    History {
+        /// ID of the schedule
+        id: String,
+        /// Maximum number of runs to show
+        #[arg(long, help = "Maximum number of runs to show", default_value = "20")]
+        limit: u32,
+        #[arg(
+            short,
+            long,
+            help = "Output format (text, json)",
+            default_value = "text"
+        )]
+        format: String,
+    },
     /// Run a scheduled job immediately
     #[command(about = "Run a scheduled job immediately")]
     RunNow {
@@ -234,6 +499,16 @@ enum RecipeCommand {
         /// Recipe name to get recipe file to validate
         #[arg(help = "recipe name to get recipe file or full path to the recipe file to validate")]
         recipe_name: String,
+
+        /// Output format for the validation report
+        #[arg(
+            long = "format",
+            value_name = "FORMAT",
+            value_enum,
+            help = "Output format",
+            default_value = "text"
+        )]
+        format: ValidateFormat,
     },
 
     /// Generate a deeplink for a recipe file
@@ -244,6 +519,41 @@ enum RecipeCommand {
             help = "recipe name to get recipe file or full path to the recipe file to generate deeplink"
         )]
         recipe_name: String,
+
+        /// Sign the deeplink
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Sign the deeplink with a key created by `goose recipe keygen`"
+        )]
+        key: Option<PathBuf>,
+    },
+
+    /// Sign a recipe file
+    #[command(
+        about = "Sign a recipe",
+        long_about = "Sign a recipe file with an ed25519 key, writing the signature to <recipe file>.sig. Recipes signed by a key listed in GOOSE_RECIPE_TRUSTED_KEYS are trusted to start their extensions."
+    )]
+    Sign {
+        /// Recipe name or path to the recipe file
+        #[arg(help = "recipe name to get recipe file or full path to the recipe file to sign")]
+        recipe_name: String,
+
+        /// Signing key
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Signing key created by `goose recipe keygen`"
+        )]
+        key: PathBuf,
+    },
+
+    /// Generate a key for signing recipes
+    #[command(about = "Generate a key for signing recipes")]
+    Keygen {
+        /// Where to write the private key
+        #[arg(value_name = "FILE", help = "Where to write the private key")]
+        output: PathBuf,
     },
 
     /// List available recipes
@@ -253,10 +563,11 @@ enum RecipeCommand {
         #[arg(
             long = "format",
             value_name = "FORMAT",
-            help = "Output format (text, json)",
+            value_enum,
+            help = "Output format",
             default_value = "text"
         )]
-        format: String,
+        format: ValidateFormat,
 
         /// Show verbose information including recipe descriptions
         #[arg(
@@ -265,6 +576,85 @@ enum RecipeCommand {
             help = "Show verbose information including recipe descriptions"
         )]
         verbose: bool,
+
+        /// List recipes installed from a registry
+        #[arg(long, help = "List recipes installed from a registry")]
+        installed: bool,
+    },
+
+    /// Install recipes from a registry
+    #[command(
+        about = "Install recipes from a registry",
+        long_about = "Install recipes from a registry and pin their versions in goose-recipes.lock. Without recipe names, installs the versions pinned in the lockfile."
+    )]
+    Install {
+        /// Recipes to install
+        #[arg(
+            value_name = "NAME[@VERSION]",
+            help = "Recipes to install, optionally with a version requirement such as review@^1.2"
+        )]
+        recipes: Vec<String>,
+
+        /// Registry to install from
+        #[arg(
+            long,
+            value_name = "DIR_OR_URL",
+            help = "Registry directory or URL to use instead of GOOSE_RECIPE_REGISTRY"
+        )]
+        registry: Option<String>,
+
+        /// Only use recipes that are already installed
+        #[arg(long, help = "Only use recipes that are already installed")]
+        offline: bool,
+    },
+
+    /// Update locked recipes
+    #[command(about = "Update locked recipes to the newest versions matching their requirements")]
+    Update {
+        /// Recipes to update
+        #[arg(
+            value_name = "NAME",
+            help = "Recipes to update; all locked recipes when omitted"
+        )]
+        recipes: Vec<String>,
+    },
+
+    /// Run the test spec of a recipe
+    #[command(
+        about = "Run the test cases of a recipe",
+        long_about = "Run the test cases in the recipe's test spec (<recipe>.test.yaml next to the recipe) headlessly against scripted or recorded provider responses and mocked extensions."
+    )]
+    Test {
+        /// Recipe name or path to the recipe file
+        #[arg(help = "recipe name to get recipe file or full path to the recipe file to test")]
+        recipe_name: String,
+
+        /// Test spec to run instead of the one next to the recipe
+        #[arg(long, value_name = "FILE", help = "Path to the test spec")]
+        spec: Option<PathBuf>,
+
+        /// Write a JUnit XML report
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Write a JUnit XML report to this file"
+        )]
+        junit: Option<PathBuf>,
+
+        /// Record provider responses for cases that use a recording
+        #[arg(
+            long,
+            help = "Record provider responses with the configured provider, replacing existing recordings"
+        )]
+        record: bool,
+
+        /// Only run matching cases
+        #[arg(
+            long,
+            value_name = "TEXT",
+            help = "Only run cases whose name contains TEXT"
+        )]
+        case: Option<String>,
     },
 }
 
@@ -606,8 +996,8 @@ enum Command {
         model: Option<String>,
     },
 
-    /// Recipe utilities for validation and deeplinking
-    #[command(about = "Recipe utilities for validation and deeplinking")]
+    /// Recipe utilities for validation, deeplinking and testing
+    #[command(about = "Recipe utilities for validation, deeplinking and testing")]
     Recipe {
         #[command(subcommand)]
         command: RecipeCommand,
@@ -680,6 +1070,8 @@ enum CliProviderVariant {
 #[derive(Debug)]
 pub struct InputConfig {
     pub contents: Option<String>,
+    /// Whether the first message sent from `contents` is pinned through context compaction
+    pub pin_contents: bool,
     pub extensions_override: Option<Vec<ExtensionConfig>>,
     pub additional_system_prompt: Option<String>,
 }
@@ -690,6 +1082,10 @@ pub struct RecipeInfo {
     pub sub_recipes: Option<Vec<goose::recipe::SubRecipe>>,
     pub final_output_response: Option<goose::recipe::Response>,
     pub retry_config: Option<goose::agents::types::RetryConfig>,
+    pub steps: Option<Vec<goose::recipe::RecipeStep>>,
+    /// Identifies this recipe run, so its steps can resume after a failure
+    pub run_key: String,
+    pub artifacts: Option<Vec<String>>,
 }
 
 pub async fn cli() -> Result<()> {
@@ -752,8 +1148,9 @@ pub async fn cli() -> Result<()> {
                     verbose,
                     format,
                     ascending,
+                    artifacts,
                 }) => {
-                    handle_session_list(verbose, format, ascending)?;
+                    handle_session_list(verbose, format, ascending, artifacts)?;
                     Ok(())
                 }
                 Some(SessionCommand::Remove { id, regex }) => {
@@ -777,6 +1174,92 @@ pub async fn cli() -> Result<()> {
                     crate::commands::session::handle_session_export(session_identifier, output)?;
                     Ok(())
                 }
+                Some(SessionCommand::Stats { identifier, format }) => {
+                    let session_identifier = if let Some(id) = identifier {
+                        extract_identifier(id)
+                    } else {
+                        match crate::commands::session::prompt_interactive_session_selection() {
+                            Ok(id) => id,
+                            Err(e) => {
+                                eprintln!("Error: {}", e);
+                                return Ok(());
+                            }
+                        }
+                    };
+
+                    handle_session_stats(session_identifier, &format)?;
+                    Ok(())
+                }
+                Some(SessionCommand::Diff {
+                    a,
+                    b,
+                    format,
+                    output,
+                }) => {
+                    handle_session_diff(&a, &b, format, output)?;
+                    Ok(())
+                }
+                Some(SessionCommand::ToRecipe { session_id, output }) => {
+                    handle_session_to_recipe(session::Identifier::Name(session_id), output).await?;
+                    Ok(())
+                }
+                Some(SessionCommand::Repair { identifier }) => {
+                    let session_identifier = if let Some(id) = identifier {
+                        extract_identifier(id)
+                    } else {
+                        match crate::commands::session::prompt_interactive_session_selection() {
+                            Ok(id) => id,
+                            Err(e) => {
+                                eprintln!("Error: {}", e);
+                                return Ok(());
+                            }
+                        }
+                    };
+
+                    handle_session_repair(session_identifier)?;
+                    Ok(())
+                }
+                Some(SessionCommand::Encrypt { passphrase }) => {
+                    let mode = if passphrase {
+                        EncryptionMode::Passphrase
+                    } else {
+                        EncryptionMode::Keyring
+                    };
+                    handle_session_encryption(mode)?;
+                    Ok(())
+                }
+                Some(SessionCommand::Decrypt {}) => {
+                    handle_session_encryption(EncryptionMode::Off)?;
+                    Ok(())
+                }
+                Some(SessionCommand::Doctor {
+                    identifier,
+                    format,
+                    fix,
+                    orphaned_tool_requests,
+                    consecutive_messages,
+                }) => {
+                    let session_identifier = if let Some(id) = identifier {
+                        extract_identifier(id)
+                    } else {
+                        match crate::commands::session::prompt_interactive_session_selection() {
+                            Ok(id) => id,
+                            Err(e) => {
+                                eprintln!("Error: {}", e);
+                                return Ok(());
+                            }
+                        }
+                    };
+
+                    handle_session_doctor(
+                        session_identifier,
+                        &format,
+                        fix,
+                        &orphaned_tool_requests,
+                        &consecutive_messages,
+                    )?;
+                    Ok(())
+                }
                 None => {
                     let session_start = std::time::Instant::now();
                     let session_type = if resume { "resumed" } else { "new" };
@@ -902,6 +1385,7 @@ pub async fn cli() -> Result<()> {
 
                     let input_config = InputConfig {
                         contents: Some(input),
+                        pin_contents: false,
                         extensions_override: None,
                         additional_system_prompt: system,
                     };
@@ -917,6 +1401,7 @@ pub async fn cli() -> Result<()> {
                     });
                     let input_config = InputConfig {
                         contents: Some(contents),
+                        pin_contents: false,
                         extensions_override: None,
                         additional_system_prompt: None,
                     };
@@ -925,6 +1410,7 @@ pub async fn cli() -> Result<()> {
                 (_, Some(text), _) => {
                     let input_config = InputConfig {
                         contents: Some(text),
+                        pin_contents: false,
                         extensions_override: None,
                         additional_system_prompt: system,
                     };
@@ -991,9 +1477,40 @@ pub async fn cli() -> Result<()> {
             })
             .await;
 
+            session.set_pinned_prompt(input_config.pin_contents);
+            let mut contents = input_config.contents;
+            if let Some(recipe_info) = recipe_info.as_ref() {
+                if let Some(steps) = recipe_info.steps.as_deref().filter(|s| !s.is_empty()) {
+                    let outputs = match session.run_recipe_steps(steps, &recipe_info.run_key).await
+                    {
+                        Ok(outputs) => outputs,
+                        Err(err) => {
+                            eprintln!("{}: {}", console::style("Error").red().bold(), err);
+                            std::process::exit(1);
+                        }
+                    };
+                    contents = contents
+                        .map(|prompt| render_with_step_outputs(&prompt, &outputs))
+                        .transpose()?;
+                    if contents.is_none() && !interactive {
+                        println!("{}", serde_json::to_string_pretty(&outputs)?);
+                        return Ok(());
+                    }
+                }
+            }
+
+            let artifacts = recipe_info
+                .as_ref()
+                .and_then(|r| r.artifacts.clone())
+                .filter(|patterns| !patterns.is_empty());
             if interactive {
-                let _ = session.interactive(input_config.contents).await;
-            } else if let Some(contents) = input_config.contents {
+                let _ = session.interactive(contents).await;
+                if let Some(patterns) = &artifacts {
+                    if let Err(e) = session.collect_artifacts(patterns).await {
+                        eprintln!("Failed to collect artifacts: {}", e);
+                    }
+                }
+            } else if let Some(contents) = contents {
                 let session_start = std::time::Instant::now();
                 let session_type = if recipe_info.is_some() {
                     "recipe"
@@ -1043,6 +1560,11 @@ pub async fn cli() -> Result<()> {
                     );
                 }
 
+                if let Some(patterns) = &artifacts {
+                    if let Err(e) = session.collect_artifacts(patterns).await {
+                        eprintln!("Failed to collect artifacts: {}", e);
+                    }
+                }
                 result?;
             } else {
                 eprintln!("Error: no text provided for prompt in headless mode");
@@ -1057,8 +1579,84 @@ pub async fn cli() -> Result<()> {
                     id,
                     cron,
                     recipe_source,
+                    params,
+                    provider,
+                    model,
+                    working_dir,
+                    builtins,
+                    watch,
+                    debounce,
+                    webhook,
+                    webhook_param,
+                    after,
+                    after_status,
+                    overlap,
+                    catch_up,
+                    catch_up_window,
+                    max_runtime,
+                    retry_attempts,
+                    retry_backoff,
+                    retry_max_backoff,
+                    retry_on,
                 } => {
-                    handle_schedule_add(id, cron, recipe_source).await?;
+                    let mut triggers: Vec<JobTrigger> = watch
+                        .into_iter()
+                        .map(|path| JobTrigger::FileWatch {
+                            path,
+                            debounce_seconds: debounce,
+                        })
+                        .collect();
+                    if webhook {
+                        triggers.push(JobTrigger::Webhook {
+                            secret: Some(
+                                rand::thread_rng()
+                                    .sample_iter(&Alphanumeric)
+                                    .take(32)
+                                    .map(char::from)
+                                    .collect(),
+                            ),
+                            secret_sha256: None,
+                            parameter: webhook_param,
+                        });
+                    }
+                    if let Some(job_id) = after {
+                        triggers.push(JobTrigger::JobCompleted {
+                            job_id,
+                            on: after_status,
+                        });
+                    }
+                    let extensions = builtins
+                        .into_iter()
+                        .map(|name| ExtensionConfig::Builtin {
+                            name: name.trim().to_string(),
+                            display_name: None,
+                            timeout: Some(goose::config::DEFAULT_EXTENSION_TIMEOUT),
+                            bundled: None,
+                            description: None,
+                        })
+                        .collect();
+                    let settings = ScheduledJobUpdate {
+                        parameters: Some(params.into_iter().collect()),
+                        provider,
+                        model,
+                        working_dir,
+                        extensions: Some(extensions),
+                        triggers: Some(triggers),
+                        overlap: Some(overlap),
+                        catch_up: Some(catch_up),
+                        catch_up_window_seconds: catch_up_window,
+                        max_runtime_seconds: max_runtime,
+                        retry: retry_attempts.map(|max_attempts| JobRetryPolicy {
+                            max_attempts,
+                            initial_backoff_seconds: retry_backoff,
+                            backoff_multiplier: 2.0,
+                            max_backoff_seconds: retry_max_backoff,
+                            retry_on,
+                        }),
+                        clear_retry: false,
+                    };
+                    handle_schedule_add(id, cron.unwrap_or_default(), recipe_source, settings)
+                        .await?;
                 }
                 SchedulerCommand::List {} => {
                     handle_schedule_list().await?;
@@ -1070,6 +1668,9 @@ pub async fn cli() -> Result<()> {
                     // New arm
                     handle_schedule_sessions(id, limit).await?;
                 }
+                SchedulerCommand::History { id, limit, format } => {
+                    handle_schedule_history(id, limit, &format).await?;
+                }
                 SchedulerCommand::RunNow { id } => {
                     // New arm
                     handle_schedule_run_now(id).await?;
@@ -1116,14 +1717,46 @@ pub async fn cli() -> Result<()> {
         }
         Some(Command::Recipe { command }) => {
             match command {
-                RecipeCommand::Validate { recipe_name } => {
-                    handle_validate(&recipe_name)?;
+                RecipeCommand::Validate {
+                    recipe_name,
+                    format,
+                } => {
+                    handle_validate(&recipe_name, format)?;
+                }
+                RecipeCommand::Deeplink { recipe_name, key } => {
+                    handle_deeplink(&recipe_name, key.as_deref())?;
+                }
+                RecipeCommand::Sign { recipe_name, key } => {
+                    handle_sign(&recipe_name, &key)?;
                 }
-                RecipeCommand::Deeplink { recipe_name } => {
-                    handle_deeplink(&recipe_name)?;
+                RecipeCommand::Keygen { output } => {
+                    handle_keygen(&output)?;
                 }
-                RecipeCommand::List { format, verbose } => {
-                    handle_list(&format, verbose)?;
+                RecipeCommand::List {
+                    format,
+                    verbose,
+                    installed,
+                } => {
+                    handle_list(&format, verbose, installed)?;
+                }
+                RecipeCommand::Install {
+                    recipes,
+                    registry,
+                    offline,
+                } => {
+                    handle_install(recipes, registry, offline).await?;
+                }
+                RecipeCommand::Update { recipes } => {
+                    handle_update(recipes).await?;
+                }
+                RecipeCommand::Test {
+                    recipe_name,
+                    spec,
+                    junit,
+                    record,
+                    case,
+                } => {
+                    handle_test(&recipe_name, spec, junit, record, case).await?;
                 }
             }
             return Ok(());
@@ -1,31 +1,76 @@
+use std::path::{Path, PathBuf};
+
 use anyhow::Result;
 use console::style;
 
 use crate::recipes::github_recipe::RecipeSource;
 use crate::recipes::recipe::load_recipe_for_validation;
-use crate::recipes::search_recipe::list_available_recipes;
+use crate::recipes::search_recipe::{list_available_recipes, retrieve_recipe_file};
+use crate::recipes::test_runner::RecipeTestRun;
+use crate::recipes::test_spec::{default_spec_path, junit_xml, RecipeTestSpec};
+use goose::recipe::lint::{lint_recipe, Severity};
+use goose::recipe::registry::{
+    sha256_hex, LockedRecipe, Lockfile, RecipeStore, Registry, VersionReq,
+    GOOSE_RECIPE_REGISTRY_CONFIG_KEY, LOCKFILE_NAME,
+};
+use goose::recipe::signing::{write_signature, SigningKey, GOOSE_RECIPE_TRUSTED_KEYS_CONFIG_KEY};
 use goose::recipe_deeplink;
 
-/// Validates a recipe file
+/// How `goose recipe validate` prints its report
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum ValidateFormat {
+    Text,
+    Json,
+}
+
+/// Validates a recipe file and the recipes it runs
 ///
 /// # Arguments
 ///
-/// * `file_path` - Path to the recipe file to validate
+/// * `recipe_name` - Name of or path to the recipe file to validate
+/// * `format` - Output format, `json` for CI
 ///
 /// # Returns
 ///
 /// Result indicating success or failure
-pub fn handle_validate(recipe_name: &str) -> Result<()> {
-    // Load and validate the recipe file
-    match load_recipe_for_validation(recipe_name) {
-        Ok(_) => {
-            println!("{} recipe file is valid", style("✓").green().bold());
-            Ok(())
-        }
+pub fn handle_validate(recipe_name: &str, format: ValidateFormat) -> Result<()> {
+    let recipe_file = match retrieve_recipe_file(recipe_name) {
+        Ok(recipe_file) => recipe_file,
         Err(err) => {
             println!("{} {}", style("✗").red().bold(), err);
-            Err(err)
+            return Err(err);
         }
+    };
+    let report = lint_recipe(recipe_file, &goose_mcp::BUILTIN_EXTENSIONS);
+
+    if matches!(format, ValidateFormat::Json) {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if report.issues.is_empty() {
+        println!("{} recipe file is valid", style("✓").green().bold());
+    } else {
+        for issue in &report.issues {
+            let marker = match issue.severity {
+                Severity::Error => style("✗").red().bold(),
+                Severity::Warning => style("!").yellow().bold(),
+            };
+            println!(
+                "{} [{}] {}: {}",
+                marker, issue.check, issue.file, issue.message
+            );
+        }
+    }
+
+    if report.valid {
+        Ok(())
+    } else {
+        let errors: Vec<&str> = report
+            .errors()
+            .map(|issue| issue.message.as_str())
+            .collect();
+        Err(anyhow::anyhow!(
+            "Recipe validation failed:\n  {}",
+            errors.join("\n  ")
+        ))
     }
 }
 
@@ -34,14 +79,19 @@ pub fn handle_validate(recipe_name: &str) -> Result<()> {
 /// # Arguments
 ///
 /// * `file_path` - Path to the recipe file
+/// * `key` - Signing key file to sign the deeplink with
 ///
 /// # Returns
 ///
 /// Result indicating success or failure
-pub fn handle_deeplink(recipe_name: &str) -> Result<String> {
+pub fn handle_deeplink(recipe_name: &str, key: Option<&Path>) -> Result<String> {
+    let signing_key = key.map(read_signing_key).transpose()?;
     // Load the recipe file first to validate it
     match load_recipe_for_validation(recipe_name) {
-        Ok(recipe) => match recipe_deeplink::encode(&recipe) {
+        Ok(recipe) => match signing_key.as_ref().map_or_else(
+            || recipe_deeplink::encode(&recipe),
+            |key| recipe_deeplink::encode_signed(&recipe, key),
+        ) {
             Ok(encoded) => {
                 println!(
                     "{} Generated deeplink for: {}",
@@ -68,17 +118,88 @@ pub fn handle_deeplink(recipe_name: &str) -> Result<String> {
     }
 }
 
+fn read_signing_key(path: &Path) -> Result<SigningKey> {
+    let encoded = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read signing key {}: {}", path.display(), e))?;
+    SigningKey::from_pkcs8_base64(&encoded)
+}
+
+/// Generates a key pair for signing recipes
+///
+/// # Arguments
+///
+/// * `output` - Where to write the private key
+///
+/// # Returns
+///
+/// Result indicating success or failure
+pub fn handle_keygen(output: &Path) -> Result<()> {
+    if output.exists() {
+        return Err(anyhow::anyhow!(
+            "{} already exists; refusing to overwrite a signing key",
+            output.display()
+        ));
+    }
+    let (key, encoded) = SigningKey::generate()?;
+    std::fs::write(output, encoded)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(output, std::fs::Permissions::from_mode(0o600))?;
+    }
+
+    println!(
+        "{} Private key written to {}",
+        style("✓").green().bold(),
+        output.display()
+    );
+    println!("Public key: {}", key.public_key());
+    println!(
+        "Add the public key to {} to trust recipes signed with this key",
+        GOOSE_RECIPE_TRUSTED_KEYS_CONFIG_KEY
+    );
+    Ok(())
+}
+
+/// Signs a recipe file, writing the signature next to it
+///
+/// # Arguments
+///
+/// * `recipe_name` - Name or path of the recipe to sign
+/// * `key` - Signing key file created by `goose recipe keygen`
+///
+/// # Returns
+///
+/// Result indicating success or failure
+pub fn handle_sign(recipe_name: &str, key: &Path) -> Result<()> {
+    let signing_key = read_signing_key(key)?;
+    let recipe_path = retrieve_recipe_file(recipe_name)?.file_path;
+    let content = std::fs::read(&recipe_path)?;
+    let signature_path = write_signature(&recipe_path, &signing_key.sign(&content))?;
+    println!(
+        "{} Signed {} ({})",
+        style("✓").green().bold(),
+        recipe_path.display(),
+        signature_path.display()
+    );
+    Ok(())
+}
+
 /// Lists all available recipes from local paths and GitHub repositories
 ///
 /// # Arguments
 ///
 /// * `format` - Output format ("text" or "json")
 /// * `verbose` - Whether to show detailed information
+/// * `installed` - List recipes installed from a registry instead
 ///
 /// # Returns
 ///
 /// Result indicating success or failure
-pub fn handle_list(format: &str, verbose: bool) -> Result<()> {
+pub fn handle_list(format: &str, verbose: bool, installed: bool) -> Result<()> {
+    if installed {
+        return list_installed_recipes(format, verbose);
+    }
     let recipes = match list_available_recipes() {
         Ok(recipes) => recipes,
         Err(e) => {
@@ -129,6 +250,318 @@ pub fn handle_list(format: &str, verbose: bool) -> Result<()> {
     Ok(())
 }
 
+fn list_installed_recipes(format: &str, verbose: bool) -> Result<()> {
+    let installed = RecipeStore::default_location()?.list()?;
+    if format == "json" {
+        println!("{}", serde_json::to_string(&installed)?);
+        return Ok(());
+    }
+    if installed.is_empty() {
+        println!("No recipes installed");
+        return Ok(());
+    }
+
+    let lockfile = match project_lockfile_path() {
+        Ok(path) => Lockfile::load(&path)?,
+        Err(_) => Lockfile::default(),
+    };
+    println!("Installed recipes:");
+    for recipe in installed {
+        let locked = lockfile
+            .recipes
+            .get(&recipe.name)
+            .is_some_and(|locked| locked.version == recipe.version);
+        let marker = if locked { " (locked)" } else { "" };
+        println!("{} {}{}", recipe.name, recipe.version, marker);
+        if verbose {
+            println!("    Path: {}", recipe.path.display());
+        }
+    }
+    Ok(())
+}
+
+/// The lockfile of the project in the working directory, or where a new one is created
+fn project_lockfile_path() -> Result<PathBuf> {
+    let current_dir = std::env::current_dir()?;
+    Ok(Lockfile::find(&current_dir).unwrap_or_else(|| current_dir.join(LOCKFILE_NAME)))
+}
+
+/// Split `name@requirement`, e.g. `code-review@^1.2`
+fn parse_recipe_requirement(spec: &str) -> Result<(String, Option<VersionReq>)> {
+    match spec.split_once('@') {
+        Some((name, requirement)) => {
+            let requirement = VersionReq::parse(requirement)
+                .map_err(|e| anyhow::anyhow!("Invalid version requirement in {}: {}", spec, e))?;
+            Ok((name.to_string(), Some(requirement)))
+        }
+        None => Ok((spec.to_string(), None)),
+    }
+}
+
+/// Installs recipes from a registry and pins them in the project lockfile
+///
+/// # Arguments
+///
+/// * `recipes` - Recipes as `name` or `name@requirement`; when empty, the versions pinned in
+///   the lockfile are installed
+/// * `registry` - Registry directory or URL to use instead of `GOOSE_RECIPE_REGISTRY`
+/// * `offline` - Only use recipes that are already installed
+///
+/// # Returns
+///
+/// Result indicating success or failure
+pub async fn handle_install(
+    recipes: Vec<String>,
+    registry: Option<String>,
+    offline: bool,
+) -> Result<()> {
+    let lockfile_path = project_lockfile_path()?;
+    let mut lockfile = Lockfile::load(&lockfile_path)?;
+    let store = RecipeStore::default_location()?;
+
+    if recipes.is_empty() {
+        if lockfile.recipes.is_empty() {
+            println!("No recipes in {}", lockfile_path.display());
+            return Ok(());
+        }
+        for (name, locked) in &lockfile.recipes {
+            install_locked_recipe(&store, name, locked, offline).await?;
+            println!("{} {} {}", style("✓").green().bold(), name, locked.version);
+        }
+        return Ok(());
+    }
+
+    let registry = match registry {
+        Some(location) => Some(Registry::from_location(&location)?),
+        None if offline => Registry::configured().ok(),
+        None => Some(Registry::configured()?),
+    };
+    for spec in recipes {
+        let (name, requirement) = parse_recipe_requirement(&spec)?;
+        let locked = if offline {
+            let requirement = requirement.unwrap_or(VersionReq::STAR);
+            let installed = store.newest_matching(&name, &requirement)?.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No installed version of {} matches {}; install it without --offline first",
+                    name,
+                    requirement
+                )
+            })?;
+            LockedRecipe {
+                requirement: VersionReq::parse(&format!("^{}", installed.version))?,
+                sha256: sha256_hex(&std::fs::read(&installed.path)?),
+                version: installed.version,
+                registry: match (&registry, lockfile.recipes.get(&name)) {
+                    (Some(registry), _) => registry.location(),
+                    (None, Some(previous)) => previous.registry.clone(),
+                    (None, None) => {
+                        return Err(anyhow::anyhow!(
+                            "No registry to record for {}; pass --registry or set {}",
+                            name,
+                            GOOSE_RECIPE_REGISTRY_CONFIG_KEY
+                        ))
+                    }
+                },
+            }
+        } else {
+            let registry = registry.as_ref().expect("registry is set when online");
+            let index = registry.index().await?;
+            let version =
+                index.resolve(&name, requirement.as_ref().unwrap_or(&VersionReq::STAR))?;
+            store.install(registry, &name, version).await?;
+            LockedRecipe {
+                requirement: match requirement {
+                    Some(requirement) => requirement,
+                    None => VersionReq::parse(&format!("^{}", version.version))?,
+                },
+                version: version.version.clone(),
+                sha256: version.sha256.clone(),
+                registry: registry.location(),
+            }
+        };
+        println!(
+            "{} Installed {} {}",
+            style("✓").green().bold(),
+            name,
+            locked.version
+        );
+        lockfile.recipes.insert(name, locked);
+    }
+    lockfile.save(&lockfile_path)?;
+    println!("Updated {}", lockfile_path.display());
+    Ok(())
+}
+
+/// Make sure the exact version pinned in the lockfile is installed
+async fn install_locked_recipe(
+    store: &RecipeStore,
+    name: &str,
+    locked: &LockedRecipe,
+    offline: bool,
+) -> Result<()> {
+    if let Some(path) = store.recipe_path(name, &locked.version) {
+        if sha256_hex(&std::fs::read(&path)?) == locked.sha256 {
+            return Ok(());
+        }
+    }
+    if offline {
+        return Err(anyhow::anyhow!(
+            "{} {} is not installed; run `goose recipe install` without --offline",
+            name,
+            locked.version
+        ));
+    }
+
+    let registry = Registry::from_location(&locked.registry)?;
+    let index = registry.index().await?;
+    let version = index.resolve(name, &VersionReq::parse(&format!("={}", locked.version))?)?;
+    if version.sha256 != locked.sha256 {
+        return Err(anyhow::anyhow!(
+            "{} {} in {} does not match the checksum in the lockfile",
+            name,
+            locked.version,
+            registry.location()
+        ));
+    }
+    store.install(&registry, name, version).await?;
+    Ok(())
+}
+
+/// Updates locked recipes to the newest versions matching their requirements
+///
+/// # Arguments
+///
+/// * `recipes` - Names of the recipes to update; all locked recipes when empty
+///
+/// # Returns
+///
+/// Result indicating success or failure
+pub async fn handle_update(recipes: Vec<String>) -> Result<()> {
+    let lockfile_path = project_lockfile_path()?;
+    let mut lockfile = Lockfile::load(&lockfile_path)?;
+    let store = RecipeStore::default_location()?;
+
+    let names: Vec<String> = if recipes.is_empty() {
+        lockfile.recipes.keys().cloned().collect()
+    } else {
+        recipes
+    };
+    for name in names {
+        let locked = lockfile.recipes.get_mut(&name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "{} is not in {}; install it first",
+                name,
+                lockfile_path.display()
+            )
+        })?;
+        let registry = Registry::from_location(&locked.registry)?;
+        let index = registry.index().await?;
+        let version = index.resolve(&name, &locked.requirement)?;
+        store.install(&registry, &name, version).await?;
+        if version.version == locked.version {
+            println!("{} {} is up to date", name, locked.version);
+            continue;
+        }
+        println!(
+            "{} Updated {} {} -> {}",
+            style("✓").green().bold(),
+            name,
+            locked.version,
+            version.version
+        );
+        locked.version = version.version.clone();
+        locked.sha256 = version.sha256.clone();
+    }
+    lockfile.save(&lockfile_path)?;
+    Ok(())
+}
+
+/// Runs the test spec of a recipe and reports the results
+///
+/// # Arguments
+///
+/// * `recipe_name` - Name or path of the recipe under test
+/// * `spec` - Test spec to run instead of the one next to the recipe
+/// * `junit` - Where to write a JUnit XML report
+/// * `record` - Record provider responses for cases that use a recording
+/// * `filter` - Only run cases whose name contains this text
+///
+/// # Returns
+///
+/// Result indicating whether all cases passed
+pub async fn handle_test(
+    recipe_name: &str,
+    spec: Option<PathBuf>,
+    junit: Option<PathBuf>,
+    record: bool,
+    filter: Option<String>,
+) -> Result<()> {
+    let recipe_path = retrieve_recipe_file(recipe_name)?.file_path;
+    let spec_path = spec.unwrap_or_else(|| default_spec_path(&recipe_path));
+    let spec = RecipeTestSpec::load(&spec_path)?;
+    // Cases run in their own working directory, so relative paths would no longer resolve
+    let spec_path = std::fs::canonicalize(&spec_path)?;
+    let recipe_path = match &spec.recipe {
+        Some(recipe) => spec_path
+            .parent()
+            .map(|dir| dir.join(recipe))
+            .unwrap_or_else(|| PathBuf::from(recipe)),
+        None => std::fs::canonicalize(&recipe_path)?,
+    };
+
+    // Tests run unattended, so tool calls must not wait for approval
+    std::env::set_var("GOOSE_MODE", "auto");
+
+    let results = RecipeTestRun {
+        recipe_path: &recipe_path,
+        spec_path: &spec_path,
+        record,
+        filter: filter.as_deref(),
+    }
+    .run(&spec)
+    .await?;
+
+    for result in &results {
+        if result.passed() {
+            println!(
+                "{} {} ({:.1}s)",
+                style("✓").green().bold(),
+                result.name,
+                result.duration.as_secs_f64()
+            );
+            continue;
+        }
+        println!(
+            "{} {} ({:.1}s)",
+            style("✗").red().bold(),
+            result.name,
+            result.duration.as_secs_f64()
+        );
+        if let Some(error) = &result.error {
+            println!("    {}", style(error).red());
+        }
+        for failure in &result.failures {
+            println!("    {}", failure);
+        }
+    }
+
+    if let Some(junit) = junit {
+        let suite_name = recipe_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| recipe_name.to_string());
+        std::fs::write(&junit, junit_xml(&suite_name, &results))?;
+        println!("JUnit report written to {}", junit.display());
+    }
+
+    let failed = results.iter().filter(|result| !result.passed()).count();
+    println!("\n{} passed, {} failed", results.len() - failed, failed);
+    if failed > 0 {
+        return Err(anyhow::anyhow!("{} recipe test case(s) failed", failed));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -187,7 +620,7 @@ response:
         let recipe_path =
             create_test_recipe_file(&temp_dir, "test_recipe.yaml", VALID_RECIPE_CONTENT);
 
-        let result = handle_deeplink(&recipe_path);
+        let result = handle_deeplink(&recipe_path, None);
         assert!(result.is_ok());
         let url = result.unwrap();
         assert!(url.starts_with("goose://recipe?config="));
@@ -200,7 +633,7 @@ response:
         let temp_dir = TempDir::new().expect("Failed to create temp directory");
         let recipe_path =
             create_test_recipe_file(&temp_dir, "test_recipe.yaml", INVALID_RECIPE_CONTENT);
-        let result = handle_deeplink(&recipe_path);
+        let result = handle_deeplink(&recipe_path, None);
         assert!(result.is_err());
     }
 
@@ -210,7 +643,7 @@ response:
         let recipe_path =
             create_test_recipe_file(&temp_dir, "test_recipe.yaml", VALID_RECIPE_CONTENT);
 
-        let result = handle_validate(&recipe_path);
+        let result = handle_validate(&recipe_path, ValidateFormat::Text);
         assert!(result.is_ok());
     }
 
@@ -219,7 +652,7 @@ response:
         let temp_dir = TempDir::new().expect("Failed to create temp directory");
         let recipe_path =
             create_test_recipe_file(&temp_dir, "test_recipe.yaml", INVALID_RECIPE_CONTENT);
-        let result = handle_validate(&recipe_path);
+        let result = handle_validate(&recipe_path, ValidateFormat::Text);
         assert!(result.is_err());
     }
 
@@ -232,7 +665,7 @@ response:
             RECIPE_WITH_INVALID_JSON_SCHEMA,
         );
 
-        let result = handle_validate(&recipe_path);
+        let result = handle_validate(&recipe_path, ValidateFormat::Text);
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
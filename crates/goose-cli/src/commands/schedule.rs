@@ -1,10 +1,11 @@
 use anyhow::{bail, Context, Result};
 use base64::engine::{general_purpose::STANDARD as BASE64_STANDARD, Engine};
 use goose::scheduler::{
-    get_default_scheduled_recipes_dir, get_default_scheduler_storage_path, ScheduledJob,
-    SchedulerError,
+    get_default_scheduled_recipes_dir, get_default_scheduler_storage_path, CatchUpPolicy,
+    JobTrigger, OverlapPolicy, ScheduledJob, ScheduledJobUpdate, SchedulerError,
 };
 use goose::scheduler_factory::SchedulerFactory;
+use goose::scheduler_history::RunStatus;
 use goose::temporal_scheduler::TemporalScheduler;
 use std::path::Path;
 
@@ -79,6 +80,7 @@ pub async fn handle_schedule_add(
     id: String,
     cron: String,
     recipe_source_arg: String, // This is expected to be a file path by the Scheduler
+    settings: ScheduledJobUpdate,
 ) -> Result<()> {
     println!(
         "[CLI Debug] Scheduling job ID: {}, Cron: {}, Recipe Source Path: {}",
@@ -86,7 +88,9 @@ pub async fn handle_schedule_add(
     );
 
     // Validate cron expression and provide helpful feedback
-    validate_cron_expression(&cron)?;
+    if !cron.is_empty() {
+        validate_cron_expression(&cron)?;
+    }
 
     // The Scheduler's add_scheduled_job will handle copying the recipe from recipe_source_arg
     // to its internal storage and validating the path.
@@ -100,8 +104,25 @@ pub async fn handle_schedule_add(
         current_session_id: None,
         process_start_time: None,
         execution_mode: Some("background".to_string()), // Default to background for CLI
+        parameters: settings.parameters.unwrap_or_default(),
+        provider: settings.provider,
+        model: settings.model,
+        working_dir: settings.working_dir,
+        extensions: settings.extensions.unwrap_or_default(),
+        triggers: settings.triggers.unwrap_or_default(),
+        overlap: settings.overlap.unwrap_or_default(),
+        catch_up: settings.catch_up.unwrap_or_default(),
+        catch_up_window_seconds: settings.catch_up_window_seconds,
+        max_runtime_seconds: settings.max_runtime_seconds,
+        retry: settings.retry,
     };
 
+    let webhook = job
+        .triggers
+        .iter()
+        .find(|trigger| matches!(trigger, JobTrigger::Webhook { .. }))
+        .cloned();
+
     let scheduler_storage_path =
         get_default_scheduler_storage_path().context("Failed to get scheduler storage path")?;
     let scheduler = SchedulerFactory::create(scheduler_storage_path)
@@ -124,6 +145,16 @@ pub async fn handle_schedule_add(
                 "Scheduled job '{}' added. Recipe expected at {:?}",
                 id, final_recipe_path
             );
+            if let Some(JobTrigger::Webhook {
+                secret: Some(secret),
+                ..
+            }) = webhook
+            {
+                println!(
+                    "Webhook: POST /schedule/{}/webhook on goose-server with header 'X-Goose-Webhook-Secret: {}'",
+                    id, secret
+                );
+            }
             Ok(())
         }
         Err(e) => {
@@ -139,6 +170,9 @@ pub async fn handle_schedule_add(
                         recipe_source_arg
                     );
                 }
+                SchedulerError::InvalidJobSettings(msg) => {
+                    bail!("Error: Invalid job settings: {}", msg);
+                }
                 _ => Err(anyhow::Error::new(e))
                     .context(format!("Failed to add job '{}' to scheduler", id)),
             }
@@ -176,6 +210,64 @@ pub async fn handle_schedule_list() -> Result<()> {
                 job.last_run
                     .map_or_else(|| "Never".to_string(), |dt| dt.to_rfc3339())
             );
+            let mut parameters: Vec<_> = job.parameters.iter().collect();
+            parameters.sort();
+            for (key, value) in parameters {
+                println!("  Parameter: {}={}", key, value);
+            }
+            if let Some(provider) = &job.provider {
+                println!("  Provider: {}", provider);
+            }
+            if let Some(model) = &job.model {
+                println!("  Model: {}", model);
+            }
+            if let Some(working_dir) = &job.working_dir {
+                println!("  Working Dir: {}", working_dir);
+            }
+            for extension in &job.extensions {
+                println!("  Extension: {}", extension.name());
+            }
+            for trigger in &job.triggers {
+                match trigger {
+                    JobTrigger::FileWatch {
+                        path,
+                        debounce_seconds,
+                    } => println!(
+                        "  Trigger: files matching {} ({}s debounce)",
+                        path, debounce_seconds
+                    ),
+                    JobTrigger::Webhook { parameter, .. } => match parameter {
+                        Some(parameter) => println!("  Trigger: webhook (body in {})", parameter),
+                        None => println!("  Trigger: webhook"),
+                    },
+                    JobTrigger::JobCompleted { job_id, on } => {
+                        println!("  Trigger: job {} finished ({:?})", job_id, on)
+                    }
+                }
+            }
+            if job.overlap != OverlapPolicy::Skip {
+                println!("  Overlap: {:?}", job.overlap);
+            }
+            if job.catch_up != CatchUpPolicy::None {
+                match job.catch_up_window_seconds {
+                    Some(window) => {
+                        println!("  Catch Up: {:?} (within {}s)", job.catch_up, window)
+                    }
+                    None => println!("  Catch Up: {:?}", job.catch_up),
+                }
+            }
+            if let Some(max_runtime) = job.max_runtime_seconds {
+                println!("  Max Runtime: {}s", max_runtime);
+            }
+            if let Some(retry) = &job.retry {
+                println!(
+                    "  Retry: up to {} attempts on {:?} failures ({}s backoff, at most {}s)",
+                    retry.max_attempts,
+                    retry.retry_on,
+                    retry.initial_backoff_seconds,
+                    retry.max_backoff_seconds
+                );
+            }
         }
     }
     Ok(())
@@ -226,6 +318,12 @@ pub async fn handle_schedule_sessions(id: String, limit: Option<u32>) -> Result<
                         metadata.message_count,
                         metadata.schedule_id.as_deref().unwrap_or("N/A")
                     );
+                    for artifact in &metadata.artifacts {
+                        println!(
+                            "      Artifact: {} ({} bytes)",
+                            artifact.path, artifact.size
+                        );
+                    }
                 }
             }
         }
@@ -236,6 +334,69 @@ pub async fn handle_schedule_sessions(id: String, limit: Option<u32>) -> Result<
     Ok(())
 }
 
+pub async fn handle_schedule_history(id: String, limit: u32, format: &str) -> Result<()> {
+    let scheduler_storage_path =
+        get_default_scheduler_storage_path().context("Failed to get scheduler storage path")?;
+    let scheduler = SchedulerFactory::create(scheduler_storage_path)
+        .await
+        .context("Failed to initialize scheduler")?;
+
+    let runs = match scheduler.run_history(&id, limit as usize).await {
+        Ok(runs) => runs,
+        Err(SchedulerError::JobNotFound(job_id)) => {
+            bail!("Error: Job with ID '{}' not found.", job_id);
+        }
+        Err(e) => bail!("Failed to get run history for schedule '{}': {:?}", id, e),
+    };
+
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&runs)?);
+        return Ok(());
+    }
+    if runs.is_empty() {
+        println!("No runs recorded for schedule ID '{}'.", id);
+        return Ok(());
+    }
+    println!("Runs of schedule ID '{}':", id);
+    for run in runs {
+        let status = match run.status {
+            RunStatus::Success => "✅ SUCCESS",
+            RunStatus::Failed => "❌ FAILED",
+            RunStatus::Killed => "🛑 KILLED",
+            RunStatus::TimedOut => "⏱️  TIMED OUT",
+        };
+        println!(
+            "- {} ({:?}) {} after {}s",
+            run.started_at.to_rfc3339(),
+            run.trigger,
+            status,
+            run.duration_seconds()
+        );
+        if run.attempt > 1 {
+            println!("  Attempt: {}", run.attempt);
+        }
+        if let Some(session_id) = &run.session_id {
+            println!("  Session: {}", session_id);
+        }
+        if let Some(error) = &run.error {
+            println!("  Error: {}", error);
+        }
+        if let Some(total_tokens) = run.total_tokens {
+            match run.cost_usd {
+                Some(cost) => println!("  Tokens: {} (${:.4})", total_tokens, cost),
+                None => println!("  Tokens: {}", total_tokens),
+            }
+        }
+        if let Some(excerpt) = &run.output_excerpt {
+            println!("  Output: {}", excerpt.replace('\n', " "));
+        }
+        for artifact in &run.artifacts {
+            println!("  Artifact: {}", artifact);
+        }
+    }
+    Ok(())
+}
+
 pub async fn handle_schedule_run_now(id: String) -> Result<()> {
     let scheduler_storage_path =
         get_default_scheduler_storage_path().context("Failed to get scheduler storage path")?;
@@ -1,8 +1,14 @@
 use crate::session::message_to_markdown;
 use anyhow::{Context, Result};
 use cliclack::{confirm, multiselect, select};
+use goose::conversation::{
+    ConsecutiveMessageRepair, IssueSeverity, OrphanedRequestRepair, RepairPolicy,
+};
+use goose::recipe::from_session::used_extensions;
+use goose::session::crypto::{self, EncryptionMode};
+use goose::session::diff::{diff_sessions, SessionDiff, TurnDiff, TurnSummary};
 use goose::session::info::{get_valid_sorted_sessions, SessionInfo, SortOrder};
-use goose::session::{self, Identifier};
+use goose::session::{self, Identifier, MessageStats, SessionStats};
 use goose::utils::safe_truncate;
 use regex::Regex;
 use std::fs;
@@ -22,7 +28,7 @@ pub fn remove_sessions(sessions: Vec<SessionInfo>) -> Result<()> {
 
     if should_delete {
         for session in sessions {
-            fs::remove_file(session.path.clone())
+            session::delete_session(Path::new(&session.path))
                 .with_context(|| format!("Failed to remove session file '{}'", session.path))?;
             println!("Session `{}` removed.", session.id);
         }
@@ -115,7 +121,12 @@ pub fn handle_session_remove(id: Option<String>, regex_string: Option<String>) -
     remove_sessions(matched_sessions)
 }
 
-pub fn handle_session_list(verbose: bool, format: String, ascending: bool) -> Result<()> {
+pub fn handle_session_list(
+    verbose: bool,
+    format: String,
+    ascending: bool,
+    artifacts: bool,
+) -> Result<()> {
     let sort_order = if ascending {
         SortOrder::Ascending
     } else {
@@ -159,6 +170,11 @@ pub fn handle_session_list(verbose: bool, format: String, ascending: bool) -> Re
                     } else {
                         println!("{}", output);
                     }
+                    if artifacts {
+                        for artifact in &metadata.artifacts {
+                            println!("    Artifact: {} ({} bytes)", artifact.path, artifact.size);
+                        }
+                    }
                 }
             }
         }
@@ -210,6 +226,542 @@ pub fn handle_session_export(identifier: Identifier, output_path: Option<PathBuf
     Ok(())
 }
 
+/// Generate a parameterized recipe from a saved session, writing it to `output_path` or
+/// printing it as YAML. The session is read from its file, so no extension is started.
+pub async fn handle_session_to_recipe(
+    identifier: Identifier,
+    output_path: Option<PathBuf>,
+) -> Result<()> {
+    let session_file_path = goose::session::get_path(identifier)
+        .map_err(|e| anyhow::anyhow!("Invalid session identifier: {}", e))?;
+    if !session_file_path.exists() {
+        return Err(anyhow::anyhow!(
+            "Session file not found (expected path: {})",
+            session_file_path.display()
+        ));
+    }
+    let messages = goose::session::read_messages(&session_file_path)
+        .map_err(|e| anyhow::anyhow!("Failed to read session messages: {}", e))?;
+    if messages.is_empty() {
+        return Err(anyhow::anyhow!(
+            "The session has no messages to create a recipe from"
+        ));
+    }
+
+    let config = goose::config::Config::global();
+    let provider_name: String = config
+        .get_param("GOOSE_PROVIDER")
+        .context("No provider configured. Run 'goose configure' first")?;
+    let model_name: String = config
+        .get_param("GOOSE_MODEL")
+        .context("No model configured. Run 'goose configure' first")?;
+    let model_config = goose::model::ModelConfig::new(&model_name)?;
+    let agent = goose::agents::Agent::new();
+    agent
+        .update_provider(goose::providers::create(&provider_name, model_config)?)
+        .await?;
+
+    eprintln!("{}", console::style("Generating Recipe").green());
+    let mut recipe = agent.create_recipe(messages.clone()).await?;
+    // Keep only the extensions the session actually used
+    recipe.extensions = recipe
+        .extensions
+        .map(|extensions| used_extensions(messages.messages(), &extensions));
+
+    let yaml = serde_yaml::to_string(&recipe)?;
+    if let Some(output) = output_path {
+        fs::write(&output, yaml)
+            .with_context(|| format!("Failed to write to output file: {}", output.display()))?;
+        eprintln!(
+            "{}",
+            console::style(format!("Saved recipe to {}", output.display())).green()
+        );
+    } else {
+        print!("{}", yaml);
+    }
+
+    Ok(())
+}
+
+/// Show a breakdown of provider calls, token usage and tool time for a session
+pub fn handle_session_stats(identifier: Identifier, format: &str) -> Result<()> {
+    let session_file_path = match goose::session::get_path(identifier) {
+        Ok(path) => path,
+        Err(e) => {
+            return Err(anyhow::anyhow!("Invalid session identifier: {}", e));
+        }
+    };
+
+    if !session_file_path.exists() {
+        return Err(anyhow::anyhow!(
+            "Session file not found (expected path: {})",
+            session_file_path.display()
+        ));
+    }
+
+    let records = session::stats::read_message_stats(&session_file_path)?;
+    let stats = SessionStats::from_records(&records);
+
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+
+    let session_name = session_file_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("Unnamed Session");
+    println!("Session: {}", session_name);
+
+    if stats.provider_calls == 0 {
+        println!("No per-message stats recorded for this session");
+        return Ok(());
+    }
+
+    println!(
+        "Provider calls: {}  Input tokens: {}  Output tokens: {}",
+        stats.provider_calls, stats.input_tokens, stats.output_tokens
+    );
+    println!(
+        "Provider time: {}  Tool time: {}",
+        format_duration_ms(stats.provider_ms),
+        format_duration_ms(stats.tool_ms)
+    );
+
+    println!("\nBy model:");
+    for (model, bucket) in &stats.by_model {
+        println!(
+            "  {:<30} {:>5} calls {:>9} in {:>9} out {:>10}",
+            model,
+            bucket.calls,
+            bucket.input_tokens,
+            bucket.output_tokens,
+            format_duration_ms(bucket.duration_ms)
+        );
+    }
+
+    if !stats.by_tool.is_empty() {
+        println!("\nBy tool:");
+        let mut tools: Vec<_> = stats.by_tool.iter().collect();
+        tools.sort_by(|a, b| b.1.duration_ms.cmp(&a.1.duration_ms));
+        for (tool, bucket) in tools {
+            println!(
+                "  {:<40} {:>5} calls {:>10}",
+                tool,
+                bucket.calls,
+                format_duration_ms(bucket.duration_ms)
+            );
+        }
+    }
+
+    println!("\nTurns:");
+    for (i, turn) in stats.turns.iter().enumerate() {
+        let created = chrono::DateTime::from_timestamp(turn.created, 0)
+            .map(|t| {
+                t.with_timezone(&chrono::Local)
+                    .format("%H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_else(|| "-".to_string());
+        let tools = turn
+            .tool_calls
+            .iter()
+            .map(|t| format!("{} ({})", t.name, format_duration_ms(t.duration_ms)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!(
+            "  {:>3}. {} {:<24} {:>8} in {:>7} out {:>9}  {}",
+            i + 1,
+            created,
+            turn.model,
+            turn.input_tokens.unwrap_or(0),
+            turn.output_tokens.unwrap_or(0),
+            format_duration_ms(turn.latency_ms),
+            tools
+        );
+    }
+
+    Ok(())
+}
+
+/// Repair a session file that was damaged by a crash or by concurrent writers
+pub fn handle_session_repair(identifier: Identifier) -> Result<()> {
+    let session_file_path = match goose::session::get_path(identifier) {
+        Ok(path) => path,
+        Err(e) => {
+            return Err(anyhow::anyhow!("Invalid session identifier: {}", e));
+        }
+    };
+
+    if !session_file_path.exists() {
+        return Err(anyhow::anyhow!(
+            "Session file not found (expected path: {})",
+            session_file_path.display()
+        ));
+    }
+
+    let issues = goose::session::repair_session(&session_file_path)?;
+    if issues.is_empty() {
+        println!("No conversation issues found.");
+    } else {
+        println!("Repaired session with {} fixes:", issues.len());
+        for issue in issues {
+            println!("  - {}", issue);
+        }
+    }
+    println!(
+        "Original saved to {}",
+        session_file_path.with_extension("repair.bak").display()
+    );
+
+    Ok(())
+}
+
+/// Check a session for conversation issues, and rewrite it with them repaired when `fix` is set
+pub fn handle_session_doctor(
+    identifier: Identifier,
+    format: &str,
+    fix: bool,
+    orphaned_tool_requests: &str,
+    consecutive_messages: &str,
+) -> Result<()> {
+    let policy = RepairPolicy {
+        orphaned_tool_requests: match orphaned_tool_requests {
+            "drop" => OrphanedRequestRepair::Drop,
+            "synthesize" => OrphanedRequestRepair::SynthesizeErrorResponse,
+            other => {
+                return Err(anyhow::anyhow!(
+                    "Unknown orphaned tool request policy '{}' (expected drop or synthesize)",
+                    other
+                ))
+            }
+        },
+        consecutive_messages: match consecutive_messages {
+            "merge" => ConsecutiveMessageRepair::Merge,
+            "drop" => ConsecutiveMessageRepair::Drop,
+            other => {
+                return Err(anyhow::anyhow!(
+                    "Unknown consecutive message policy '{}' (expected merge or drop)",
+                    other
+                ))
+            }
+        },
+        ..Default::default()
+    };
+
+    let session_file_path = goose::session::get_path(identifier)
+        .map_err(|e| anyhow::anyhow!("Invalid session identifier: {}", e))?;
+    if !session_file_path.exists() {
+        return Err(anyhow::anyhow!(
+            "Session file not found (expected path: {})",
+            session_file_path.display()
+        ));
+    }
+
+    let issues = if fix {
+        goose::session::repair_session_with_policy(&session_file_path, &policy)?
+    } else {
+        goose::session::diagnose_session(&session_file_path, &policy)?
+    };
+
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&issues)?);
+        return Ok(());
+    }
+
+    if issues.is_empty() {
+        println!("No conversation issues found.");
+        return Ok(());
+    }
+
+    println!(
+        "{} {} issues:",
+        if fix { "Repaired" } else { "Found" },
+        issues.len()
+    );
+    for issue in &issues {
+        let severity = match issue.severity {
+            IssueSeverity::Error => console::style("error").red(),
+            IssueSeverity::Warning => console::style("warning").yellow(),
+        };
+        let position = issue
+            .index
+            .map(|index| format!("message {}", index))
+            .unwrap_or_else(|| "conversation".to_string());
+        println!("  {:<7} {:<12} {}", severity, position, issue);
+    }
+    if fix {
+        println!(
+            "Original saved to {}",
+            session_file_path.with_extension("repair.bak").display()
+        );
+    } else {
+        println!("Run again with --fix to repair the session.");
+    }
+
+    Ok(())
+}
+
+/// Switch session encryption on or off and migrate existing sessions to match
+pub fn handle_session_encryption(mode: EncryptionMode) -> Result<()> {
+    if mode == EncryptionMode::Passphrase && std::env::var(crypto::PASSPHRASE_SECRET).is_err() {
+        let passphrase = cliclack::password("Enter a passphrase for session encryption")
+            .mask('▪')
+            .interact()?;
+        let confirmation = cliclack::password("Confirm the passphrase")
+            .mask('▪')
+            .interact()?;
+        if passphrase != confirmation {
+            return Err(anyhow::anyhow!("Passphrases do not match"));
+        }
+        if passphrase.trim().is_empty() {
+            return Err(anyhow::anyhow!("Passphrase cannot be empty"));
+        }
+        crypto::unlock_with_passphrase(&passphrase)?;
+    }
+
+    let migrated = goose::session::migrate_session_encryption(mode)?;
+    match mode {
+        EncryptionMode::Off => println!("Decrypted {} sessions.", migrated),
+        EncryptionMode::Keyring => println!(
+            "Encrypted {} sessions with a key stored in the system keyring.",
+            migrated
+        ),
+        EncryptionMode::Passphrase => {
+            println!("Encrypted {} sessions with your passphrase.", migrated);
+            println!(
+                "Set {} in the environment so goose can read and write sessions.",
+                crypto::PASSPHRASE_SECRET
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// How `goose session diff` renders its result
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum DiffFormat {
+    Text,
+    Json,
+    Html,
+}
+
+/// Compare two sessions turn by turn and render the result as text, JSON or HTML
+pub fn handle_session_diff(
+    a: &str,
+    b: &str,
+    format: DiffFormat,
+    output_path: Option<PathBuf>,
+) -> Result<()> {
+    let load = |name: &str| -> Result<(goose::conversation::Conversation, Vec<MessageStats>)> {
+        let path = goose::session::get_path(Identifier::Name(name.to_string()))
+            .map_err(|e| anyhow::anyhow!("Invalid session identifier '{}': {}", name, e))?;
+        if !path.exists() {
+            return Err(anyhow::anyhow!("Session '{}' not found", name));
+        }
+        let stats = session::stats::read_message_stats(&path)?;
+        let messages = goose::session::read_messages(&path)?;
+        Ok((messages, stats))
+    };
+
+    let (a_messages, a_stats) = load(a)?;
+    let (b_messages, b_stats) = load(b)?;
+    let diff = diff_sessions(a, &a_messages, &a_stats, b, &b_messages, &b_stats);
+
+    let rendered = match format {
+        DiffFormat::Json => serde_json::to_string_pretty(&diff)?,
+        DiffFormat::Html => render_diff_html(&diff),
+        DiffFormat::Text => render_diff_text(&diff, output_path.is_none()),
+    };
+
+    if let Some(output) = output_path {
+        fs::write(&output, rendered)
+            .with_context(|| format!("Failed to write to output file: {}", output.display()))?;
+        println!("Session diff written to {}", output.display());
+    } else {
+        println!("{}", rendered);
+    }
+
+    Ok(())
+}
+
+fn format_tool_calls(turn: Option<&TurnSummary>) -> String {
+    turn.map(|t| {
+        t.tool_calls
+            .iter()
+            .map(|c| format!("{}({})", c.name, c.arguments))
+            .collect::<Vec<_>>()
+            .join("\n")
+    })
+    .unwrap_or_default()
+}
+
+fn format_outputs(turn: Option<&TurnSummary>) -> String {
+    turn.map(|t| {
+        t.tool_outputs
+            .iter()
+            .map(|o| safe_truncate(o, 200))
+            .collect::<Vec<_>>()
+            .join("\n")
+    })
+    .unwrap_or_default()
+}
+
+fn format_answer(turn: Option<&TurnSummary>) -> String {
+    turn.and_then(|t| t.final_answer.clone())
+        .unwrap_or_default()
+}
+
+fn format_tokens(turn: Option<&TurnSummary>) -> String {
+    turn.map(|t| format!("{} in / {} out", t.input_tokens, t.output_tokens))
+        .unwrap_or_default()
+}
+
+type DiffSection = (&'static str, bool, fn(Option<&TurnSummary>) -> String);
+
+fn diff_sections(turn: &TurnDiff) -> [DiffSection; 4] {
+    [
+        ("Tool calls", turn.tool_calls_differ, format_tool_calls),
+        ("Tool outputs", turn.outputs_differ, format_outputs),
+        ("Final answer", turn.answer_differs, format_answer),
+        ("Tokens", turn.tokens_differ, format_tokens),
+    ]
+}
+
+fn render_diff_text(diff: &SessionDiff, color: bool) -> String {
+    let style = |text: String, differs: bool| {
+        if color && differs {
+            console::style(text).yellow().to_string()
+        } else {
+            text
+        }
+    };
+
+    let mut out = String::new();
+    out.push_str(&format!("--- a: {}\n+++ b: {}\n", diff.a, diff.b));
+    out.push_str(&format!(
+        "Turns: {} vs {}  Tool calls: {} vs {}  Tokens: {}/{} vs {}/{}\n",
+        diff.a_totals.turns,
+        diff.b_totals.turns,
+        diff.a_totals.tool_calls,
+        diff.b_totals.tool_calls,
+        diff.a_totals.input_tokens,
+        diff.a_totals.output_tokens,
+        diff.b_totals.input_tokens,
+        diff.b_totals.output_tokens,
+    ));
+
+    for turn in &diff.turns {
+        let prompt = turn
+            .a
+            .as_ref()
+            .or(turn.b.as_ref())
+            .map(|t| safe_truncate(&t.prompt, 80))
+            .unwrap_or_default();
+        let marker = if turn.has_differences() { "*" } else { " " };
+        out.push_str(&format!(
+            "\n{} Turn {}: {}\n",
+            marker,
+            turn.index + 1,
+            prompt
+        ));
+        if turn.prompt_differs {
+            out.push_str(&style("  prompts differ\n".to_string(), true));
+        }
+
+        for (label, differs, render) in diff_sections(turn) {
+            let a = render(turn.a.as_ref());
+            let b = render(turn.b.as_ref());
+            if !differs && label != "Tokens" {
+                continue;
+            }
+            out.push_str(&format!("  {}:\n", label));
+            for line in a.lines() {
+                out.push_str(&style(format!("    - {}\n", line), differs));
+            }
+            for line in b.lines() {
+                out.push_str(&style(format!("    + {}\n", line), differs));
+            }
+        }
+    }
+
+    out
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_diff_html(diff: &SessionDiff) -> String {
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\">");
+    out.push_str(&format!(
+        "<title>Session diff: {} vs {}</title>",
+        html_escape(&diff.a),
+        html_escape(&diff.b)
+    ));
+    out.push_str(
+        "<style>body{font-family:sans-serif}table{border-collapse:collapse;width:100%}\
+         td,th{border:1px solid #ccc;padding:4px;vertical-align:top}\
+         pre{white-space:pre-wrap;margin:0}.differs{background:#fff4c2}</style></head><body>",
+    );
+    out.push_str(&format!(
+        "<h1>{} vs {}</h1><p>Turns: {} vs {}, tool calls: {} vs {}, tokens: {}/{} vs {}/{}</p>",
+        html_escape(&diff.a),
+        html_escape(&diff.b),
+        diff.a_totals.turns,
+        diff.b_totals.turns,
+        diff.a_totals.tool_calls,
+        diff.b_totals.tool_calls,
+        diff.a_totals.input_tokens,
+        diff.a_totals.output_tokens,
+        diff.b_totals.input_tokens,
+        diff.b_totals.output_tokens,
+    ));
+
+    for turn in &diff.turns {
+        let prompt = turn
+            .a
+            .as_ref()
+            .or(turn.b.as_ref())
+            .map(|t| t.prompt.clone())
+            .unwrap_or_default();
+        out.push_str(&format!(
+            "<h2>Turn {}</h2><p>{}</p><table><tr><th></th><th>{}</th><th>{}</th></tr>",
+            turn.index + 1,
+            html_escape(&prompt),
+            html_escape(&diff.a),
+            html_escape(&diff.b)
+        ));
+        for (label, differs, render) in diff_sections(turn) {
+            let class = if differs { " class=\"differs\"" } else { "" };
+            out.push_str(&format!(
+                "<tr{}><th>{}</th><td><pre>{}</pre></td><td><pre>{}</pre></td></tr>",
+                class,
+                label,
+                html_escape(&render(turn.a.as_ref())),
+                html_escape(&render(turn.b.as_ref()))
+            ));
+        }
+        out.push_str("</table>");
+    }
+
+    out.push_str("</body></html>\n");
+    out
+}
+
+fn format_duration_ms(ms: u64) -> String {
+    if ms >= 1000 {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    } else {
+        format!("{}ms", ms)
+    }
+}
+
 /// Convert a list of messages to markdown format for session export
 ///
 /// This function handles the formatting of a complete session including headers,
@@ -308,7 +860,7 @@ pub fn prompt_interactive_session_selection() -> Result<session::Identifier> {
     }
 
     // Build the selection prompt
-    let mut selector = select("Select a session to export:");
+    let mut selector = select("Select a session:");
 
     // Map to display text
     let display_map: std::collections::HashMap<String, SessionInfo> = sessions
@@ -335,13 +887,13 @@ pub fn prompt_interactive_session_selection() -> Result<session::Identifier> {
 
     // Add a cancel option
     let cancel_value = String::from("cancel");
-    selector = selector.item(cancel_value, "Cancel", "Cancel export");
+    selector = selector.item(cancel_value, "Cancel", "Cancel selection");
 
     // Get user selection
     let selected_display_text: String = selector.interact()?;
 
     if selected_display_text == "cancel" {
-        return Err(anyhow::anyhow!("Export canceled"));
+        return Err(anyhow::anyhow!("Selection canceled"));
     }
 
     // Retrieve the selected session
@@ -20,6 +20,7 @@ pub fn extract_recipe_info_from_cli(
         eprintln!("{}: {}", console::style("Error").red().bold(), err);
         std::process::exit(1);
     });
+    let steps_run_key = run_key(&recipe_name, &params);
     print_recipe_info(&recipe, params);
     let mut all_sub_recipes = recipe.sub_recipes.clone().unwrap_or_default();
     if !additional_sub_recipes.is_empty() {
@@ -47,8 +48,10 @@ pub fn extract_recipe_info_from_cli(
             }
         }
     }
+    let initial_prompt = recipe.initial_prompt();
     let input_config = InputConfig {
-        contents: recipe.prompt.filter(|s| !s.trim().is_empty()),
+        pin_contents: initial_prompt.as_ref().is_some_and(|(_, pinned)| *pinned),
+        contents: initial_prompt.map(|(text, _)| text),
         extensions_override: recipe.extensions,
         additional_system_prompt: recipe.instructions,
     };
@@ -58,15 +61,29 @@ pub fn extract_recipe_info_from_cli(
             goose_provider: s.goose_provider,
             goose_model: s.goose_model,
             temperature: s.temperature,
+            compaction_strategy: s.compaction_strategy,
         }),
         sub_recipes: Some(all_sub_recipes),
         final_output_response: recipe.response,
         retry_config: recipe.retry,
+        steps: recipe.steps,
+        run_key: steps_run_key,
+        artifacts: recipe.artifacts,
     };
 
     Ok((input_config, recipe_info))
 }
 
+fn run_key(recipe_name: &str, params: &[(String, String)]) -> String {
+    let mut params = params.to_vec();
+    params.sort();
+    let params: Vec<String> = params
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect();
+    format!("{}?{}", recipe_name, params.join("&"))
+}
+
 fn extract_recipe_name(recipe_identifier: &str) -> String {
     // If it's a path (contains / or \), extract the file stem
     if recipe_identifier.contains('/') || recipe_identifier.contains('\\') {
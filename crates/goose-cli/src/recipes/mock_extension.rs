@@ -0,0 +1,118 @@
+//! Extension client with canned tool responses, used by `goose recipe test`
+//!
+//! Each tool declared in a test case returns its configured text, so recipes can be tested
+//! without starting the real extension.
+
+use mcp_client::client::{Error, McpClientTrait};
+use rmcp::model::{
+    CallToolResult, Content, GetPromptResult, InitializeResult, ListPromptsResult,
+    ListResourcesResult, ListToolsResult, ReadResourceResult, ServerNotification, Tool,
+};
+use rmcp::object;
+use serde_json::Value;
+use tokio::sync::mpsc::{self, Receiver};
+use tokio_util::sync::CancellationToken;
+
+use crate::recipes::test_spec::MockTool;
+
+pub struct MockExtensionClient {
+    tools: Vec<MockTool>,
+}
+
+impl MockExtensionClient {
+    pub fn new(tools: Vec<MockTool>) -> Self {
+        Self { tools }
+    }
+}
+
+#[async_trait::async_trait]
+impl McpClientTrait for MockExtensionClient {
+    fn get_info(&self) -> Option<&InitializeResult> {
+        None
+    }
+
+    async fn list_resources(
+        &self,
+        _next_cursor: Option<String>,
+        _cancel_token: CancellationToken,
+    ) -> Result<ListResourcesResult, Error> {
+        Ok(ListResourcesResult {
+            resources: vec![],
+            next_cursor: None,
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        _uri: &str,
+        _cancel_token: CancellationToken,
+    ) -> Result<ReadResourceResult, Error> {
+        Err(Error::UnexpectedResponse)
+    }
+
+    async fn list_tools(
+        &self,
+        _next_cursor: Option<String>,
+        _cancel_token: CancellationToken,
+    ) -> Result<ListToolsResult, Error> {
+        let tools = self
+            .tools
+            .iter()
+            .map(|tool| {
+                let input_schema = match &tool.input_schema {
+                    Some(Value::Object(schema)) => schema.clone(),
+                    _ => object!({"type": "object", "properties": {}}),
+                };
+                Tool::new(tool.name.clone(), tool.description.clone(), input_schema)
+            })
+            .collect();
+
+        Ok(ListToolsResult {
+            tools,
+            next_cursor: None,
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        name: &str,
+        _arguments: Value,
+        _cancel_token: CancellationToken,
+    ) -> Result<CallToolResult, Error> {
+        let tool = self
+            .tools
+            .iter()
+            .find(|tool| tool.name == name)
+            .ok_or(Error::UnexpectedResponse)?;
+
+        Ok(CallToolResult {
+            content: Some(vec![Content::text(tool.response.clone())]),
+            is_error: tool.is_error.then_some(true),
+            structured_content: None,
+        })
+    }
+
+    async fn list_prompts(
+        &self,
+        _next_cursor: Option<String>,
+        _cancel_token: CancellationToken,
+    ) -> Result<ListPromptsResult, Error> {
+        Ok(ListPromptsResult {
+            prompts: vec![],
+            next_cursor: None,
+        })
+    }
+
+    async fn get_prompt(
+        &self,
+        _name: &str,
+        _arguments: Value,
+        _cancel_token: CancellationToken,
+    ) -> Result<GetPromptResult, Error> {
+        Err(Error::UnexpectedResponse)
+    }
+
+    async fn subscribe(&self) -> Receiver<ServerNotification> {
+        mpsc::channel(1).1
+    }
+}
@@ -1,6 +1,9 @@
 pub mod extract_from_cli;
 pub mod github_recipe;
+pub mod mock_extension;
 pub mod print_recipe;
 pub mod recipe;
 pub mod search_recipe;
 pub mod secret_discovery;
+pub mod test_runner;
+pub mod test_spec;
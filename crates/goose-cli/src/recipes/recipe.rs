@@ -7,10 +7,12 @@ use crate::recipes::secret_discovery::{discover_recipe_secrets, SecretRequiremen
 use anyhow::Result;
 use goose::config::Config;
 use goose::recipe::build_recipe::{
-    apply_values_to_parameters, build_recipe_from_template, validate_recipe_parameters, RecipeError,
+    apply_values_to_parameters, build_recipe_from_template, render_recipe_chain_for_preview,
+    validate_chain_parameters, RecipeError,
 };
+use goose::recipe::compose::load_recipe_chain;
 use goose::recipe::read_recipe_file_content::RecipeFile;
-use goose::recipe::template_recipe::render_recipe_for_preview;
+use goose::recipe::signing::check_recipe_file;
 use goose::recipe::Recipe;
 use serde_json::Value;
 use std::collections::HashMap;
@@ -37,8 +39,12 @@ fn load_recipe_file_with_dir(recipe_name: &str) -> Result<(RecipeFile, String)>
 
 pub fn load_recipe(recipe_name: &str, params: Vec<(String, String)>) -> Result<Recipe> {
     let recipe_file = retrieve_recipe_file(recipe_name)?;
+    let recipe_path = recipe_file.file_path.clone();
     match build_recipe_from_template(recipe_file, params, Some(create_user_prompt_callback())) {
         Ok(recipe) => {
+            if let Some(warning) = check_recipe_file(&recipe_path, &recipe)? {
+                eprintln!("Warning: {}", warning);
+            }
             let secret_requirements = discover_recipe_secrets(&recipe);
             if let Err(e) = collect_missing_secrets(&secret_requirements) {
                 eprintln!(
@@ -52,6 +58,10 @@ pub fn load_recipe(recipe_name: &str, params: Vec<(String, String)>) -> Result<R
             "Please provide the following parameters in the command line: {}",
             missing_parameters_command_line(parameters)
         )),
+        Err(RecipeError::InvalidParams { errors }) => Err(anyhow::anyhow!(
+            "Invalid values for recipe parameters:\n  {}\nPlease fix them with --params key=value",
+            errors.join("\n  ")
+        )),
         Err(e) => Err(anyhow::anyhow!(e.to_string())),
     }
 }
@@ -134,14 +144,10 @@ pub fn render_recipe_as_yaml(recipe_name: &str, params: Vec<(String, String)>) -
 }
 
 pub fn load_recipe_for_validation(recipe_name: &str) -> Result<Recipe> {
-    let (recipe_file, recipe_dir_str) = load_recipe_file_with_dir(recipe_name)?;
-    let recipe_file_content = &recipe_file.content;
-    validate_recipe_parameters(recipe_file_content, &recipe_dir_str)?;
-    let recipe = render_recipe_for_preview(
-        recipe_file_content,
-        recipe_dir_str.to_string(),
-        &HashMap::new(),
-    )?;
+    let recipe_file = retrieve_recipe_file(recipe_name)?;
+    let chain = load_recipe_chain(recipe_file)?;
+    validate_chain_parameters(&chain)?;
+    let recipe = render_recipe_chain_for_preview(&chain, &HashMap::new())?;
 
     if let Some(response) = &recipe.response {
         if let Some(json_schema) = &response.json_schema {
@@ -154,8 +160,8 @@ pub fn load_recipe_for_validation(recipe_name: &str) -> Result<Recipe> {
 
 pub fn explain_recipe(recipe_name: &str, params: Vec<(String, String)>) -> Result<()> {
     let (recipe_file, recipe_dir_str) = load_recipe_file_with_dir(recipe_name)?;
-    let recipe_file_content = &recipe_file.content;
-    let recipe_parameters = validate_recipe_parameters(recipe_file_content, &recipe_dir_str)?;
+    let chain = load_recipe_chain(recipe_file)?;
+    let recipe_parameters = validate_chain_parameters(&chain)?;
 
     let (params_for_template, missing_params) = apply_values_to_parameters(
         &params,
@@ -163,11 +169,7 @@ pub fn explain_recipe(recipe_name: &str, params: Vec<(String, String)>) -> Resul
         &recipe_dir_str,
         None::<fn(&str, &str) -> Result<String>>,
     )?;
-    let recipe = render_recipe_for_preview(
-        recipe_file_content,
-        recipe_dir_str.to_string(),
-        &params_for_template,
-    )?;
+    let recipe = render_recipe_chain_for_preview(&chain, &params_for_template)?;
     print_recipe_explanation(&recipe);
     print_required_parameters_for_template(params_for_template, missing_params);
 
@@ -1,8 +1,9 @@
 use anyhow::{anyhow, Result};
 use goose::config::Config;
+use goose::recipe::compose::recipe_path_dirs;
 use goose::recipe::read_recipe_file_content::{read_recipe_file, RecipeFile};
+use goose::recipe::registry::resolve_locked_recipe;
 use goose::recipe::template_recipe::parse_recipe_content;
-use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -13,8 +14,6 @@ use super::github_recipe::{
     GOOSE_RECIPE_GITHUB_REPO_CONFIG_KEY,
 };
 
-const GOOSE_RECIPE_PATH_ENV_VAR: &str = "GOOSE_RECIPE_PATH";
-
 pub fn retrieve_recipe_file(recipe_name: &str) -> Result<RecipeFile> {
     if RECIPE_FILE_EXTENSIONS
         .iter()
@@ -30,6 +29,9 @@ pub fn retrieve_recipe_file(recipe_name: &str) -> Result<RecipeFile> {
         ));
     }
     retrieve_recipe_from_local_path(recipe_name).or_else(|e| {
+        if let Some(path) = resolve_locked_recipe(recipe_name, Path::new("."))? {
+            return read_recipe_file(path);
+        }
         if let Some(recipe_repo_full_name) = configured_github_recipe_repo() {
             retrieve_recipe_from_github(recipe_name, &recipe_repo_full_name)
         } else {
@@ -66,14 +68,7 @@ fn read_recipe_in_dir(dir: &Path, recipe_name: &str) -> Result<RecipeFile> {
 
 fn retrieve_recipe_from_local_path(recipe_name: &str) -> Result<RecipeFile> {
     let mut search_dirs = vec![PathBuf::from(".")];
-    if let Ok(recipe_path_env) = env::var(GOOSE_RECIPE_PATH_ENV_VAR) {
-        let path_separator = if cfg!(windows) { ';' } else { ':' };
-        let recipe_path_env_dirs: Vec<PathBuf> = recipe_path_env
-            .split(path_separator)
-            .map(PathBuf::from)
-            .collect();
-        search_dirs.extend(recipe_path_env_dirs);
-    }
+    search_dirs.extend(recipe_path_dirs());
     for dir in &search_dirs {
         if let Ok(result) = read_recipe_in_dir(dir, recipe_name) {
             return Ok(result);
@@ -124,14 +119,7 @@ fn discover_local_recipes() -> Result<Vec<RecipeInfo>> {
     let mut search_dirs = vec![PathBuf::from(".")];
 
     // Add GOOSE_RECIPE_PATH directories
-    if let Ok(recipe_path_env) = env::var(GOOSE_RECIPE_PATH_ENV_VAR) {
-        let path_separator = if cfg!(windows) { ';' } else { ':' };
-        let recipe_path_env_dirs: Vec<PathBuf> = recipe_path_env
-            .split(path_separator)
-            .map(PathBuf::from)
-            .collect();
-        search_dirs.extend(recipe_path_env_dirs);
-    }
+    search_dirs.extend(recipe_path_dirs());
 
     for dir in search_dirs {
         if let Ok(dir_recipes) = scan_directory_for_recipes(&dir) {
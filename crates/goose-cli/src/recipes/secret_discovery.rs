@@ -170,6 +170,11 @@ mod tests {
             response: None,
             sub_recipes: None,
             retry: None,
+            pinned: None,
+            steps: None,
+            extends: None,
+            include: None,
+            artifacts: None,
         }
     }
 
@@ -214,6 +219,11 @@ mod tests {
             response: None,
             sub_recipes: None,
             retry: None,
+            pinned: None,
+            steps: None,
+            extends: None,
+            include: None,
+            artifacts: None,
         };
 
         let secrets = discover_recipe_secrets(&recipe);
@@ -257,6 +267,11 @@ mod tests {
             response: None,
             sub_recipes: None,
             retry: None,
+            pinned: None,
+            steps: None,
+            extends: None,
+            include: None,
+            artifacts: None,
         };
 
         let secrets = discover_recipe_secrets(&recipe);
@@ -309,6 +324,11 @@ mod tests {
             parameters: None,
             response: None,
             retry: None,
+            pinned: None,
+            steps: None,
+            extends: None,
+            include: None,
+            artifacts: None,
         };
 
         let secrets = discover_recipe_secrets(&recipe);
@@ -0,0 +1,270 @@
+//! Runs the cases of a recipe test spec headlessly
+//!
+//! Cases run one after another because each one changes the process working directory to its
+//! own copy of the fixture. Recipe `steps` and `retry` are not run; use assertions instead.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use futures::StreamExt;
+use goose::agents::retry::{evaluate_success_checks, CheckContext};
+use goose::agents::{Agent, AgentEvent, RetryConfig};
+use goose::config::Config;
+use goose::conversation::message::Message;
+use goose::conversation::Conversation;
+use goose::model::ModelConfig;
+use goose::providers::base::Provider;
+use goose::providers::create;
+use goose::providers::testprovider::{ScriptedProvider, TestProvider};
+use goose::recipe::build_recipe::build_recipe_from_template;
+use goose::recipe::read_recipe_file_content::read_recipe_file;
+use goose::recipe::Recipe;
+
+use crate::recipes::mock_extension::MockExtensionClient;
+use crate::recipes::test_spec::{CaseResult, RecipeTestSpec, TestCase, TestProviderSpec};
+
+pub struct RecipeTestRun<'a> {
+    pub recipe_path: &'a Path,
+    pub spec_path: &'a Path,
+    /// Record provider responses for cases that use a recording, replacing existing ones
+    pub record: bool,
+    /// Only run cases whose name contains this text
+    pub filter: Option<&'a str>,
+}
+
+enum CaseProvider {
+    Scripted(Arc<ScriptedProvider>),
+    Replaying(Arc<TestProvider>),
+    Recording(Arc<TestProvider>),
+}
+
+impl CaseProvider {
+    fn provider(&self) -> Arc<dyn Provider> {
+        match self {
+            CaseProvider::Scripted(provider) => provider.clone(),
+            CaseProvider::Replaying(provider) | CaseProvider::Recording(provider) => {
+                provider.clone()
+            }
+        }
+    }
+}
+
+impl RecipeTestRun<'_> {
+    pub async fn run(&self, spec: &RecipeTestSpec) -> Result<Vec<CaseResult>> {
+        let original_dir = std::env::current_dir()?;
+        let mut results = Vec::new();
+        for case in &spec.cases {
+            if self
+                .filter
+                .is_some_and(|filter| !case.name.contains(filter))
+            {
+                continue;
+            }
+
+            let started = Instant::now();
+            let outcome = self.run_case(case, spec.timeout()).await;
+            if let Err(e) = std::env::set_current_dir(&original_dir) {
+                tracing::error!("Failed to restore working directory: {}", e);
+            }
+            let (failures, error) = match outcome {
+                Ok(failures) => (failures, None),
+                Err(e) => (Vec::new(), Some(e.to_string())),
+            };
+            results.push(CaseResult {
+                name: case.name.clone(),
+                duration: started.elapsed(),
+                failures,
+                error,
+            });
+        }
+        Ok(results)
+    }
+
+    fn spec_dir(&self) -> &Path {
+        self.spec_path.parent().unwrap_or(Path::new("."))
+    }
+
+    async fn run_case(&self, case: &TestCase, timeout: Duration) -> Result<Vec<String>> {
+        let recipe = self.render_recipe(case)?;
+        let prompt = case
+            .prompt
+            .clone()
+            .or_else(|| recipe.initial_prompt().map(|(text, _)| text))
+            .ok_or_else(|| anyhow!("The recipe has no prompt; set `prompt` on the case"))?;
+
+        let work_dir = tempfile::tempdir()?;
+        if let Some(fixture) = &case.fixture {
+            copy_dir(&self.spec_dir().join(fixture), work_dir.path())?;
+        }
+        std::env::set_current_dir(work_dir.path())?;
+
+        let case_provider = self.case_provider(case, &recipe)?;
+        let agent = Agent::new();
+        for extension in recipe.extensions.iter().flatten() {
+            if case
+                .extensions
+                .iter()
+                .any(|mock| mock.name == extension.name())
+            {
+                continue;
+            }
+            agent
+                .add_extension(extension.clone())
+                .await
+                .map_err(|e| anyhow!("Failed to add extension {}: {}", extension.name(), e))?;
+        }
+        {
+            let mut extension_manager = agent.extension_manager.write().await;
+            for mock in &case.extensions {
+                extension_manager.add_client(
+                    mock.name.clone(),
+                    Box::new(MockExtensionClient::new(mock.tools.clone())),
+                );
+            }
+        }
+        agent.update_provider(case_provider.provider()).await?;
+        if let Some(instructions) = &recipe.instructions {
+            agent.extend_system_prompt(instructions.clone()).await;
+        }
+        if let Some(response) = &recipe.response {
+            agent.add_final_output_tool(response.clone()).await;
+        }
+
+        let messages = tokio::time::timeout(timeout, run_agent(&agent, prompt))
+            .await
+            .map_err(|_| anyhow!("Timed out after {:?}", timeout))??;
+
+        let context = CheckContext::new(messages.clone(), None, Some(case_provider.provider()));
+        let mut failures = case
+            .assert
+            .evaluate(&messages, context.final_output.as_deref());
+        let retry_config = RetryConfig {
+            max_retries: 0,
+            checks: Vec::new(),
+            on_failure: None,
+            timeout_seconds: None,
+            on_failure_timeout_seconds: None,
+        };
+        failures
+            .extend(evaluate_success_checks(&case.assert.checks, &retry_config, &context).await?);
+
+        drop(context);
+        drop(agent);
+        match case_provider {
+            CaseProvider::Scripted(provider) if provider.remaining() > 0 => {
+                failures.push(format!(
+                    "{} scripted responses were not used",
+                    provider.remaining()
+                ));
+            }
+            CaseProvider::Recording(provider) => Arc::try_unwrap(provider)
+                .map_err(|_| anyhow!("Failed to save recording, provider is still in use"))?
+                .finish_recording()?,
+            _ => {}
+        }
+        Ok(failures)
+    }
+
+    fn render_recipe(&self, case: &TestCase) -> Result<Recipe> {
+        let recipe_file = read_recipe_file(self.recipe_path)?;
+        let params = case
+            .params
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        build_recipe_from_template(
+            recipe_file,
+            params,
+            None::<fn(&str, &str) -> Result<String>>,
+        )
+        .map_err(|e| anyhow!("Failed to render recipe: {}", e))
+    }
+
+    fn case_provider(&self, case: &TestCase, recipe: &Recipe) -> Result<CaseProvider> {
+        match &case.provider {
+            TestProviderSpec::Script(turns) => {
+                let responses = turns
+                    .iter()
+                    .enumerate()
+                    .map(|(turn, scripted)| scripted.to_message(turn))
+                    .collect();
+                Ok(CaseProvider::Scripted(Arc::new(ScriptedProvider::new(
+                    responses,
+                ))))
+            }
+            TestProviderSpec::Recording(path) => {
+                let path = self.spec_dir().join(path);
+                if self.record {
+                    if let Some(parent) = path.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    let _ = std::fs::remove_file(&path);
+                    let inner = configured_provider(recipe)?;
+                    return Ok(CaseProvider::Recording(Arc::new(
+                        TestProvider::new_recording(inner, path.to_string_lossy()),
+                    )));
+                }
+                if !path.exists() {
+                    return Err(anyhow!(
+                        "Recording {} does not exist; run with --record to create it",
+                        path.display()
+                    ));
+                }
+                Ok(CaseProvider::Replaying(Arc::new(
+                    TestProvider::new_replaying(path.to_string_lossy())?,
+                )))
+            }
+        }
+    }
+}
+
+/// The provider a normal run of the recipe would use
+fn configured_provider(recipe: &Recipe) -> Result<Arc<dyn Provider>> {
+    let config = Config::global();
+    let settings = recipe.settings.as_ref();
+    let provider_name = match settings.and_then(|s| s.goose_provider.clone()) {
+        Some(name) => name,
+        None => config.get_param::<String>("GOOSE_PROVIDER")?,
+    };
+    let model_name = match settings.and_then(|s| s.goose_model.clone()) {
+        Some(name) => name,
+        None => config.get_param::<String>("GOOSE_MODEL")?,
+    };
+    let model_config =
+        ModelConfig::new(&model_name)?.with_temperature(settings.and_then(|s| s.temperature));
+    create(&provider_name, model_config)
+}
+
+async fn run_agent(agent: &Agent, prompt: String) -> Result<Vec<Message>> {
+    let mut messages = Conversation::new_unvalidated(vec![Message::user().with_text(prompt)]);
+    let mut stream = agent.reply(messages.clone(), None, None).await?;
+    while let Some(event) = stream.next().await {
+        match event? {
+            AgentEvent::Message(message) => messages.push(message),
+            AgentEvent::HistoryReplaced(history) => {
+                messages = Conversation::new_unvalidated(history);
+            }
+            AgentEvent::McpNotification(_) | AgentEvent::ModelChange { .. } => {}
+        }
+    }
+    Ok(messages.messages().clone())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    if !from.is_dir() {
+        return Err(anyhow!("Fixture {} is not a directory", from.display()));
+    }
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target: PathBuf = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            std::fs::create_dir_all(&target)?;
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,458 @@
+//! Test specs for recipes, run by `goose recipe test`
+//!
+//! A spec lives next to its recipe as `<recipe>.test.yaml` and lists cases. Each case renders
+//! the recipe with a set of parameters, runs it in a copy of a fixture directory against a
+//! scripted or recorded provider and mocked extensions, and then checks the tool calls made,
+//! the final output and the files produced.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use goose::agents::types::SuccessCheck;
+use goose::conversation::message::{Message, MessageContent};
+use mcp_core::ToolCall;
+use serde::Deserialize;
+use serde_json::Value;
+
+pub const TEST_SPEC_SUFFIX: &str = ".test.yaml";
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RecipeTestSpec {
+    /// Recipe under test, relative to the spec. Defaults to the recipe the spec is named after.
+    pub recipe: Option<String>,
+    /// Time limit for each case (default: 300 seconds)
+    pub timeout_seconds: Option<u64>,
+    pub cases: Vec<TestCase>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TestCase {
+    pub name: String,
+    /// Recipe parameters
+    #[serde(default)]
+    pub params: BTreeMap<String, String>,
+    /// Prompt to use instead of the recipe's prompt
+    pub prompt: Option<String>,
+    /// Directory copied into the working directory of the case, relative to the spec
+    pub fixture: Option<String>,
+    pub provider: TestProviderSpec,
+    /// Extensions replaced by canned tool responses; the recipe's extension of the same name
+    /// is not started
+    #[serde(default)]
+    pub extensions: Vec<MockExtension>,
+    #[serde(default)]
+    pub assert: TestAssertions,
+}
+
+/// Where the model responses of a case come from
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum TestProviderSpec {
+    /// Responses recorded from a real provider, relative to the spec. Record them by running
+    /// with `--record`.
+    Recording(String),
+    /// Responses returned in order, one per model call
+    Script(Vec<ScriptedTurn>),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptedTurn {
+    pub text: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Vec<ScriptedToolCall>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptedToolCall {
+    /// Full tool name, e.g. `weather__get_weather`
+    pub name: String,
+    #[serde(default = "empty_object")]
+    pub arguments: Value,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MockExtension {
+    pub name: String,
+    pub tools: Vec<MockTool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MockTool {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub input_schema: Option<Value>,
+    /// Text returned by every call of the tool
+    pub response: String,
+    /// Return the response as a tool error
+    #[serde(default)]
+    pub is_error: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TestAssertions {
+    /// Tool calls that must be made in this order; other calls may happen in between
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCallAssertion>,
+    /// Tools that must not be called
+    #[serde(default)]
+    pub forbidden_tool_calls: Vec<String>,
+    pub output: Option<OutputAssertion>,
+    /// Success checks run in the working directory of the case, e.g. for files produced
+    #[serde(default)]
+    pub checks: Vec<SuccessCheck>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ToolCallAssertion {
+    pub name: String,
+    /// Arguments the call must include; other arguments are ignored
+    pub arguments: Option<Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutputAssertion {
+    #[serde(default)]
+    pub contains: Vec<String>,
+    /// Regular expression the final output must match
+    pub matches: Option<String>,
+}
+
+/// Outcome of one test case
+#[derive(Debug)]
+pub struct CaseResult {
+    pub name: String,
+    pub duration: Duration,
+    /// Assertions that did not hold
+    pub failures: Vec<String>,
+    /// Set when the case could not run to completion
+    pub error: Option<String>,
+}
+
+impl CaseResult {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty() && self.error.is_none()
+    }
+}
+
+fn empty_object() -> Value {
+    Value::Object(Default::default())
+}
+
+impl RecipeTestSpec {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read test spec {}: {}", path.display(), e))?;
+        let spec: Self = serde_yaml::from_str(&content)
+            .map_err(|e| anyhow!("Invalid test spec {}: {}", path.display(), e))?;
+        if spec.cases.is_empty() {
+            return Err(anyhow!("Test spec {} has no cases", path.display()));
+        }
+        Ok(spec)
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_seconds.unwrap_or(300))
+    }
+}
+
+/// The spec conventionally stored next to a recipe: `recipe.yaml` -> `recipe.test.yaml`
+pub fn default_spec_path(recipe_path: &Path) -> PathBuf {
+    let stem = recipe_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    recipe_path.with_file_name(format!("{}{}", stem, TEST_SPEC_SUFFIX))
+}
+
+impl ScriptedTurn {
+    pub fn to_message(&self, turn: usize) -> Message {
+        let mut message = Message::assistant();
+        if let Some(text) = &self.text {
+            message = message.with_text(text);
+        }
+        for (i, call) in self.tool_calls.iter().enumerate() {
+            message = message.with_tool_request(
+                format!("scripted_{}_{}", turn, i),
+                Ok(ToolCall::new(&call.name, call.arguments.clone())),
+            );
+        }
+        message
+    }
+}
+
+impl TestAssertions {
+    /// Check the tool calls and final output; `checks` are run separately since they may need
+    /// the file system, network or a provider
+    pub fn evaluate(&self, messages: &[Message], final_output: Option<&str>) -> Vec<String> {
+        let mut failures = Vec::new();
+        let calls = tool_calls(messages);
+
+        let mut remaining = calls.iter();
+        for expected in &self.tool_calls {
+            let found = remaining.any(|call| {
+                call.name == expected.name
+                    && expected
+                        .arguments
+                        .as_ref()
+                        .is_none_or(|arguments| json_contains(&call.arguments, arguments))
+            });
+            if !found {
+                failures.push(match &expected.arguments {
+                    Some(arguments) => format!(
+                        "expected a call to {} with arguments {}",
+                        expected.name, arguments
+                    ),
+                    None => format!("expected a call to {}", expected.name),
+                });
+                break;
+            }
+        }
+
+        for forbidden in &self.forbidden_tool_calls {
+            if calls.iter().any(|call| &call.name == forbidden) {
+                failures.push(format!("{} was called but should not have been", forbidden));
+            }
+        }
+
+        if let Some(output) = &self.output {
+            let text = final_output.unwrap_or_default();
+            for expected in &output.contains {
+                if !text.contains(expected.as_str()) {
+                    failures.push(format!("final output does not contain {:?}", expected));
+                }
+            }
+            if let Some(pattern) = &output.matches {
+                match regex::Regex::new(pattern) {
+                    Ok(regex) if regex.is_match(text) => {}
+                    Ok(_) => failures.push(format!(
+                        "final output does not match the pattern {:?}",
+                        pattern
+                    )),
+                    Err(e) => failures.push(format!("invalid output pattern {:?}: {}", pattern, e)),
+                }
+            }
+        }
+
+        failures
+    }
+}
+
+/// Tool calls the model made, in order
+pub fn tool_calls(messages: &[Message]) -> Vec<ToolCall> {
+    messages
+        .iter()
+        .flat_map(|message| &message.content)
+        .filter_map(|content| match content {
+            MessageContent::ToolRequest(request) => request.tool_call.as_ref().ok().cloned(),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether `actual` has everything in `expected`; objects may have extra keys
+fn json_contains(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => expected.iter().all(|(key, value)| {
+            actual
+                .get(key)
+                .is_some_and(|actual| json_contains(actual, value))
+        }),
+        _ => actual == expected,
+    }
+}
+
+/// Render results as a JUnit XML report, one test suite per spec
+pub fn junit_xml(suite_name: &str, results: &[CaseResult]) -> String {
+    let failures = results
+        .iter()
+        .filter(|result| result.error.is_none() && !result.failures.is_empty())
+        .count();
+    let errors = results
+        .iter()
+        .filter(|result| result.error.is_some())
+        .count();
+    let time: f64 = results
+        .iter()
+        .map(|result| result.duration.as_secs_f64())
+        .sum();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">\n",
+        results.len(),
+        failures,
+        errors,
+        time
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">\n",
+        xml_escape(suite_name),
+        results.len(),
+        failures,
+        errors,
+        time
+    ));
+    for result in results {
+        xml.push_str(&format!(
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+            xml_escape(&result.name),
+            xml_escape(suite_name),
+            result.duration.as_secs_f64()
+        ));
+        if let Some(error) = &result.error {
+            xml.push_str(&format!(
+                ">\n      <error message=\"{}\">{}</error>\n    </testcase>\n",
+                xml_escape(error.lines().next().unwrap_or_default()),
+                xml_escape(error)
+            ));
+        } else if !result.failures.is_empty() {
+            xml.push_str(&format!(
+                ">\n      <failure message=\"{}\">{}</failure>\n    </testcase>\n",
+                xml_escape(&result.failures[0]),
+                xml_escape(&result.failures.join("\n"))
+            ));
+        } else {
+            xml.push_str("/>\n");
+        }
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+fn xml_escape(text: &str) -> String {
+    text.chars()
+        .filter(|c| matches!(c, '\t' | '\n' | '\r') || !c.is_control())
+        .fold(String::with_capacity(text.len()), |mut escaped, c| {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&apos;"),
+                _ => escaped.push(c),
+            }
+            escaped
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SPEC: &str = r#"
+cases:
+  - name: reports the weather
+    params:
+      city: Berlin
+    fixture: fixtures/empty
+    provider:
+      script:
+        - text: Checking the weather
+          tool_calls:
+            - name: weather__get_weather
+              arguments: {location: Berlin, units: metric}
+        - text: It is cloudy in Berlin
+    extensions:
+      - name: weather
+        tools:
+          - name: get_weather
+            response: cloudy
+    assert:
+      tool_calls:
+        - name: weather__get_weather
+          arguments: {location: Berlin}
+      forbidden_tool_calls: [developer__shell]
+      output:
+        contains: [cloudy]
+        matches: "(?i)berlin"
+      checks:
+        - type: file_exists
+          path: report.md
+"#;
+
+    fn run_script(spec: &RecipeTestSpec) -> Vec<Message> {
+        let TestProviderSpec::Script(turns) = &spec.cases[0].provider else {
+            panic!("expected a scripted provider");
+        };
+        turns
+            .iter()
+            .enumerate()
+            .map(|(turn, scripted)| scripted.to_message(turn))
+            .collect()
+    }
+
+    #[test]
+    fn test_assertions_on_scripted_run() {
+        let spec: RecipeTestSpec = serde_yaml::from_str(SPEC).unwrap();
+        let case = &spec.cases[0];
+        assert_eq!(case.params.get("city").map(String::as_str), Some("Berlin"));
+        assert_eq!(case.assert.checks.len(), 1);
+
+        let messages = run_script(&spec);
+        let failures = case
+            .assert
+            .evaluate(&messages, Some("It is cloudy in Berlin"));
+        assert!(failures.is_empty(), "{:?}", failures);
+
+        let failures = case.assert.evaluate(&messages[1..], Some("It is sunny"));
+        assert_eq!(failures.len(), 3, "{:?}", failures);
+        assert!(failures[0].contains("expected a call to weather__get_weather"));
+    }
+
+    #[test]
+    fn test_json_contains() {
+        let actual = json!({"path": "a.txt", "options": {"force": true, "depth": 2}});
+        assert!(json_contains(&actual, &json!({"options": {"force": true}})));
+        assert!(!json_contains(
+            &actual,
+            &json!({"options": {"force": false}})
+        ));
+        assert!(!json_contains(&actual, &json!({"missing": 1})));
+    }
+
+    #[test]
+    fn test_junit_xml() {
+        let results = vec![
+            CaseResult {
+                name: "passes".to_string(),
+                duration: Duration::from_millis(1500),
+                failures: vec![],
+                error: None,
+            },
+            CaseResult {
+                name: "fails <badly>".to_string(),
+                duration: Duration::from_millis(10),
+                failures: vec!["final output does not contain \"ok\"".to_string()],
+                error: None,
+            },
+        ];
+
+        let xml = junit_xml("weather & co", &results);
+        assert!(xml.contains("<testsuite name=\"weather &amp; co\" tests=\"2\" failures=\"1\""));
+        assert!(xml
+            .contains("<testcase name=\"passes\" classname=\"weather &amp; co\" time=\"1.500\"/>"));
+        assert!(xml.contains("name=\"fails &lt;badly&gt;\""));
+        assert!(xml.contains("<failure message=\"final output does not contain &quot;ok&quot;\">"));
+    }
+
+    #[test]
+    fn test_default_spec_path() {
+        assert_eq!(
+            default_spec_path(Path::new("recipes/weather.yaml")),
+            PathBuf::from("recipes/weather.test.yaml")
+        );
+    }
+}
@@ -170,6 +170,7 @@ pub struct SessionSettings {
     pub goose_model: Option<String>,
     pub goose_provider: Option<String>,
     pub temperature: Option<f32>,
+    pub compaction_strategy: Option<String>,
 }
 
 pub async fn build_session(session_config: SessionBuilderConfig) -> Session {
@@ -218,6 +219,17 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> Session {
         agent.add_final_output_tool(final_output_response).await;
     }
 
+    if let Some(strategy) = session_config
+        .settings
+        .as_ref()
+        .and_then(|s| s.compaction_strategy.clone())
+    {
+        if let Err(e) = agent.set_compaction_strategy(Some(strategy)).await {
+            output::render_error(&format!("Invalid compaction strategy: {}", e));
+            process::exit(1);
+        }
+    }
+
     let new_provider = match create(&provider_name, model_config) {
         Ok(provider) => provider,
         Err(e) => {
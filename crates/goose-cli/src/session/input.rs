@@ -21,6 +21,10 @@ pub enum InputResult {
     Clear,
     Recipe(Option<String>),
     Summarize,
+    Pin,
+    Unpin,
+    /// Show the token budget of the next request; `true` lists every message
+    Context(bool),
 }
 
 #[derive(Debug)]
@@ -121,6 +125,9 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
     const CMD_CLEAR: &str = "/clear";
     const CMD_RECIPE: &str = "/recipe";
     const CMD_SUMMARIZE: &str = "/summarize";
+    const CMD_PIN: &str = "/pin";
+    const CMD_UNPIN: &str = "/unpin";
+    const CMD_CONTEXT: &str = "/context";
 
     match input {
         "/exit" | "/quit" => Some(InputResult::Exit),
@@ -181,6 +188,10 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
         s if s == CMD_CLEAR => Some(InputResult::Clear),
         s if s.starts_with(CMD_RECIPE) => parse_recipe_command(s),
         s if s == CMD_SUMMARIZE => Some(InputResult::Summarize),
+        s if s == CMD_PIN => Some(InputResult::Pin),
+        s if s == CMD_UNPIN => Some(InputResult::Unpin),
+        s if s == CMD_CONTEXT => Some(InputResult::Context(false)),
+        s if s.split_whitespace().eq([CMD_CONTEXT, "all"]) => Some(InputResult::Context(true)),
         _ => None,
     }
 }
@@ -291,6 +302,10 @@ fn print_help() {
 /recipe [filepath] - Generate a recipe from the current conversation and save it to the specified filepath (must end with .yaml).
                        If no filepath is provided, it will be saved to ./recipe.yaml.
 /summarize - Summarize the current conversation to reduce context length while preserving key information.
+/pin - Pin your latest message so it is kept word for word when the conversation is summarized or truncated.
+/unpin - Unpin all pinned messages.
+/context [all] - Show how many tokens each part of the next request uses, with advice on what to disable.
+                 Lists the largest messages, or every message with 'all'.
 /? or /help - Display this help message
 /clear - Clears the current chat history
 
@@ -536,4 +551,30 @@ mod tests {
         let result = handle_slash_command("  /summarize  ");
         assert!(matches!(result, Some(InputResult::Summarize)));
     }
+
+    #[test]
+    fn test_pin_commands() {
+        assert!(matches!(
+            handle_slash_command("/pin"),
+            Some(InputResult::Pin)
+        ));
+        assert!(matches!(
+            handle_slash_command("  /unpin "),
+            Some(InputResult::Unpin)
+        ));
+        assert!(handle_slash_command("/pinned").is_none());
+    }
+
+    #[test]
+    fn test_context_command() {
+        assert!(matches!(
+            handle_slash_command("/context"),
+            Some(InputResult::Context(false))
+        ));
+        assert!(matches!(
+            handle_slash_command("/context all"),
+            Some(InputResult::Context(true))
+        ));
+        assert!(handle_slash_command("/context foo").is_none());
+    }
 }
@@ -32,7 +32,10 @@ use goose::agents::types::RetryConfig;
 use goose::agents::{Agent, SessionConfig};
 use goose::config::Config;
 use goose::providers::pricing::initialize_pricing_cache;
+use goose::recipe::steps::StepRunState;
+use goose::recipe::RecipeStep;
 use goose::session;
+use goose::session::artifacts::{artifacts_dir, record_artifacts};
 use input::InputResult;
 use mcp_core::handler::ToolError;
 use rmcp::model::PromptMessage;
@@ -66,6 +69,7 @@ pub struct Session {
     max_turns: Option<u32>,
     edit_mode: Option<EditMode>,
     retry_config: Option<RetryConfig>,
+    pinned_prompt: bool, // Whether the prompt the session starts with is pinned
 }
 
 // Cache structure for completion data
@@ -152,9 +156,32 @@ impl Session {
             max_turns,
             edit_mode,
             retry_config,
+            pinned_prompt: false,
         }
     }
 
+    /// Pin the prompt passed to `interactive` or `headless`, e.g. when a recipe asks for it
+    pub fn set_pinned_prompt(&mut self, pinned: bool) {
+        self.pinned_prompt = pinned;
+    }
+
+    /// Run the steps of a recipe before its prompt and return the output of each step.
+    ///
+    /// Progress is kept under `run_key`, so running the same recipe again after a failure
+    /// resumes from the steps that didn't complete.
+    pub async fn run_recipe_steps(
+        &self,
+        steps: &[RecipeStep],
+        run_key: &str,
+    ) -> Result<HashMap<String, Value>> {
+        let state_path = StepRunState::path_for(run_key)?;
+        println!(
+            "{}",
+            console::style(format!("Running {} recipe steps...", steps.len())).dim()
+        );
+        self.agent.run_recipe_steps(steps, &state_path, None).await
+    }
+
     /// Helper function to summarize context messages
     async fn summarize_context_messages(
         messages: &mut Conversation,
@@ -418,7 +445,9 @@ impl Session {
     pub async fn interactive(&mut self, prompt: Option<String>) -> Result<()> {
         // Process initial message if provided
         if let Some(prompt) = prompt {
-            let msg = Message::user().with_text(&prompt);
+            let msg = Message::user()
+                .with_text(&prompt)
+                .with_pinned(self.pinned_prompt);
             self.process_message(msg, CancellationToken::default())
                 .await?;
         }
@@ -695,6 +724,65 @@ impl Session {
 
                     continue;
                 }
+                InputResult::Pin => {
+                    save_history(&mut editor);
+
+                    let mut messages = self.messages.messages().clone();
+                    match messages
+                        .iter_mut()
+                        .rev()
+                        .find(|m| m.role == rmcp::model::Role::User && !m.is_tool_response())
+                    {
+                        Some(message) => {
+                            message.pinned = true;
+                            self.update_messages(messages)?;
+                            println!(
+                                "{}",
+                                console::style(
+                                    "Pinned your latest message. It will be kept word for word when the conversation is compacted."
+                                )
+                                .green()
+                            );
+                        }
+                        None => {
+                            println!(
+                                "{}",
+                                console::style("There is no message to pin yet.").yellow()
+                            );
+                        }
+                    }
+                    continue;
+                }
+                InputResult::Unpin => {
+                    save_history(&mut editor);
+
+                    let mut messages = self.messages.messages().clone();
+                    let mut unpinned = 0;
+                    for message in messages.iter_mut().filter(|m| m.pinned) {
+                        message.pinned = false;
+                        unpinned += 1;
+                    }
+                    if unpinned > 0 {
+                        self.update_messages(messages)?;
+                    }
+                    println!(
+                        "{}",
+                        console::style(format!("Unpinned {} message(s).", unpinned)).green()
+                    );
+                    continue;
+                }
+                InputResult::Context(all_messages) => {
+                    save_history(&mut editor);
+
+                    match self.agent.context_budget(self.messages.messages()).await {
+                        Ok(budget) => output::display_context_budget(&budget, all_messages),
+                        Err(e) => output::render_error(&format!(
+                            "Failed to compute context budget: {}",
+                            e
+                        )),
+                    }
+                    continue;
+                }
                 InputResult::Summarize => {
                     save_history(&mut editor);
 
@@ -853,7 +941,9 @@ impl Session {
 
     /// Process a single message and exit
     pub async fn headless(&mut self, prompt: String) -> Result<()> {
-        let message = Message::user().with_text(&prompt);
+        let message = Message::user()
+            .with_text(&prompt)
+            .with_pinned(self.pinned_prompt);
         self.process_message(message, CancellationToken::default())
             .await?;
         Ok(())
@@ -1381,6 +1471,27 @@ impl Session {
         self.session_file.clone()
     }
 
+    /// Collect the files matching a recipe's `artifacts` into the session
+    pub async fn collect_artifacts(&self, patterns: &[String]) -> Result<()> {
+        let Some(session_file) = self.session_file.as_ref().filter(|f| f.exists()) else {
+            return Ok(());
+        };
+        let working_dir = std::env::current_dir()?;
+        let artifacts = record_artifacts(session_file, patterns, &working_dir).await?;
+        if !artifacts.is_empty() {
+            println!(
+                "{}",
+                console::style(format!(
+                    "Collected {} artifact(s) in {}",
+                    artifacts.len(),
+                    artifacts_dir(session_file).display()
+                ))
+                .dim()
+            );
+        }
+        Ok(())
+    }
+
     /// Update the completion cache with fresh data
     /// This should be called before the interactive session starts
     pub async fn update_completion_cache(&mut self) -> Result<()> {
@@ -1622,6 +1733,17 @@ impl Session {
     fn push_message(&mut self, message: Message) {
         self.messages.push(message);
     }
+
+    /// Replace the conversation after editing existing messages and rewrite the session file,
+    /// since persisting normally only appends new messages
+    fn update_messages(&mut self, messages: Vec<Message>) -> Result<()> {
+        self.messages = Conversation::new_unvalidated(messages);
+        if let Some(session_file) = self.session_file.as_ref().filter(|f| f.exists()) {
+            let metadata = session::read_metadata(session_file)?;
+            session::save_messages_with_metadata(session_file, &metadata, &self.messages)?;
+        }
+        Ok(())
+    }
 }
 
 fn get_reasoner() -> Result<Arc<dyn Provider>, anyhow::Error> {
@@ -2,6 +2,7 @@ use anstream::println;
 use bat::WrappingMode;
 use console::{style, Color};
 use goose::config::Config;
+use goose::context_mgmt::budget::ContextBudget;
 use goose::conversation::message::{Message, MessageContent, ToolRequest, ToolResponse};
 use goose::providers::pricing::get_model_pricing;
 use goose::providers::pricing::parse_model_id;
@@ -748,6 +749,78 @@ pub fn display_context_usage(total_tokens: usize, context_limit: usize) {
     );
 }
 
+/// Number of messages listed by /context unless every message is requested
+const CONTEXT_BUDGET_TOP_MESSAGES: usize = 10;
+
+/// Display the token breakdown of the next request and advice on reducing it
+pub fn display_context_budget(budget: &ContextBudget, all_messages: bool) {
+    display_context_usage(budget.total_tokens, budget.context_limit);
+
+    let row = |name: &str, tokens: usize| {
+        println!("  {:<48} {:>8}", name, tokens);
+    };
+
+    println!("\n{}", style("System prompt").bold());
+    for section in &budget.system_prompt {
+        row(&section.name, section.tokens);
+    }
+
+    if !budget.hints.is_empty() {
+        println!("\n{}", style("Hints").bold());
+        for hint in &budget.hints {
+            row(&hint.name, hint.tokens);
+        }
+    }
+
+    println!("\n{}", style("Tools").bold());
+    for cost in &budget.tools {
+        row(
+            &format!("{} ({} tools)", cost.extension, cost.tool_count),
+            cost.tokens,
+        );
+    }
+
+    println!(
+        "\n{} {}",
+        style("Messages").bold(),
+        style(format!(
+            "({} messages, {} tokens)",
+            budget.messages.len(),
+            budget.history_tokens()
+        ))
+        .dim()
+    );
+    let mut messages: Vec<_> = budget.messages.iter().collect();
+    if !all_messages {
+        messages.sort_by(|a, b| b.tokens.cmp(&a.tokens));
+        messages.truncate(CONTEXT_BUDGET_TOP_MESSAGES);
+        messages.sort_by_key(|message| message.index);
+    }
+    for message in messages {
+        let pinned = if message.pinned { " [pinned]" } else { "" };
+        let label = format!(
+            "#{} {}{}: {}",
+            message.index, message.role, pinned, message.preview
+        );
+        let label: String = label.chars().take(48).collect();
+        row(&label, message.tokens);
+    }
+    if !all_messages && budget.messages.len() > CONTEXT_BUDGET_TOP_MESSAGES {
+        println!(
+            "  {}",
+            style("Showing the largest messages; use /context all to list every one.").dim()
+        );
+    }
+
+    if !budget.advice.is_empty() {
+        println!("\n{}", style("Advice").bold());
+        for advice in &budget.advice {
+            println!("  - {}", style(advice).yellow());
+        }
+    }
+    println!();
+}
+
 fn normalize_model_name(model: &str) -> String {
     let mut result = model.to_string();
 
@@ -7,6 +7,16 @@ pub static APP_STRATEGY: Lazy<AppStrategyArgs> = Lazy::new(|| AppStrategyArgs {
     app_name: "goose".to_string(),
 });
 
+/// Names `goose mcp` accepts for the builtin extensions served from this crate
+pub const BUILTIN_EXTENSIONS: [&str; 6] = [
+    "developer",
+    "computercontroller",
+    "googledrive",
+    "google_drive",
+    "memory",
+    "tutorial",
+];
+
 pub mod computercontroller;
 mod developer;
 pub mod google_drive;
@@ -371,8 +371,14 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
         super::routes::agent::add_sub_recipes,
         super::routes::reply::confirm_permission,
         super::routes::context::manage_context,
+        super::routes::context::validate_context,
+        super::routes::context::list_strategies,
+        super::routes::context::context_budget,
         super::routes::session::list_sessions,
         super::routes::session::get_session_history,
+        super::routes::session::set_message_pinned,
+        super::routes::session::list_session_artifacts,
+        super::routes::session::get_session_artifact,
         super::routes::schedule::create_schedule,
         super::routes::schedule::list_schedules,
         super::routes::schedule::delete_schedule,
@@ -383,9 +389,12 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
         super::routes::schedule::kill_running_job,
         super::routes::schedule::inspect_running_job,
         super::routes::schedule::sessions_handler,
+        super::routes::schedule::run_history_handler,
+        super::routes::schedule::webhook_handler,
         super::routes::recipe::create_recipe,
         super::routes::recipe::encode_recipe,
-        super::routes::recipe::decode_recipe
+        super::routes::recipe::decode_recipe,
+        super::routes::recipe::validate_recipe_params
     ),
     components(schemas(
         super::routes::config_management::UpsertConfigQuery,
@@ -400,8 +409,25 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
         super::routes::reply::PermissionConfirmationRequest,
         super::routes::context::ContextManageRequest,
         super::routes::context::ContextManageResponse,
+        super::routes::context::ContextValidateRequest,
+        super::routes::context::ContextValidateResponse,
+        goose::context_mgmt::strategy::CompactionStrategyMetadata,
+        super::routes::context::ContextBudgetRequest,
+        goose::context_mgmt::budget::ContextBudget,
+        goose::context_mgmt::budget::TokenCost,
+        goose::context_mgmt::budget::ExtensionToolsCost,
+        goose::context_mgmt::budget::MessageCost,
+        goose::conversation::ConversationIssue,
+        goose::conversation::IssueKind,
+        goose::conversation::IssueSeverity,
+        goose::conversation::RepairAction,
+        goose::conversation::RepairPolicy,
+        goose::conversation::OrphanedRequestRepair,
+        goose::conversation::ConsecutiveMessageRepair,
         super::routes::session::SessionListResponse,
         super::routes::session::SessionHistoryResponse,
+        super::routes::session::SetMessagePinnedRequest,
+        super::routes::session::SessionArtifactsResponse,
         Message,
         MessageContent,
         ContentSchema,
@@ -436,11 +462,22 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
         ModelInfo,
         SessionInfo,
         SessionMetadata,
+        goose::session::SessionArtifact,
         super::routes::schedule::CreateScheduleRequest,
         super::routes::schedule::UpdateScheduleRequest,
         super::routes::schedule::KillJobResponse,
         super::routes::schedule::InspectJobResponse,
         goose::scheduler::ScheduledJob,
+        goose::scheduler::ScheduledJobUpdate,
+        goose::scheduler::JobTrigger,
+        goose::scheduler::CompletionFilter,
+        goose::scheduler::OverlapPolicy,
+        goose::scheduler::CatchUpPolicy,
+        goose::scheduler::JobRetryPolicy,
+        goose::scheduler::RetryableFailure,
+        goose::scheduler_history::JobRun,
+        goose::scheduler_history::RunTrigger,
+        goose::scheduler_history::RunStatus,
         super::routes::schedule::RunNowResponse,
         super::routes::schedule::ListSchedulesResponse,
         super::routes::schedule::SessionsQuery,
@@ -452,6 +489,8 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
         super::routes::recipe::EncodeRecipeResponse,
         super::routes::recipe::DecodeRecipeRequest,
         super::routes::recipe::DecodeRecipeResponse,
+        super::routes::recipe::ValidateRecipeParamsRequest,
+        super::routes::recipe::ValidateRecipeParamsResponse,
         goose::recipe::Recipe,
         goose::recipe::Author,
         goose::recipe::Settings,
@@ -460,6 +499,8 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
         goose::recipe::RecipeParameterRequirement,
         goose::recipe::Response,
         goose::recipe::SubRecipe,
+        goose::recipe::PinnedSection,
+        goose::recipe::RecipeStep,
         goose::agents::types::RetryConfig,
         goose::agents::types::SuccessCheck,
         super::routes::agent::AddSubRecipesRequest,
@@ -76,6 +76,8 @@ struct UpdateProviderRequest {
 #[derive(Deserialize)]
 struct SessionConfigRequest {
     response: Option<Response>,
+    /// Compaction strategy from the recipe's settings
+    compaction_strategy: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -330,16 +332,28 @@ async fn update_session_config(
         })
     })?;
 
+    if payload.response.is_none() && payload.compaction_strategy.is_none() {
+        return Ok(Json("Nothing provided to update.".to_string()));
+    }
+
+    if let Some(strategy) = payload.compaction_strategy {
+        agent
+            .set_compaction_strategy(Some(strategy))
+            .await
+            .map_err(|e| {
+                Json(ErrorResponse {
+                    error: format!("Invalid compaction strategy: {}", e),
+                })
+            })?;
+        tracing::info!("Set compaction strategy from recipe settings");
+    }
+
     if let Some(response) = payload.response {
         agent.add_final_output_tool(response).await;
-
         tracing::info!("Added final output tool with response config");
-        Ok(Json(
-            "Session config updated with final output tool".to_string(),
-        ))
-    } else {
-        Ok(Json("Nothing provided to update.".to_string()))
     }
+
+    Ok(Json("Session config updated".to_string()))
 }
 
 pub fn routes(state: Arc<AppState>) -> Router {
@@ -3,10 +3,16 @@ use crate::state::AppState;
 use axum::{
     extract::State,
     http::{HeaderMap, StatusCode},
-    routing::post,
+    routing::{get, post},
     Json, Router,
 };
-use goose::conversation::{message::Message, Conversation};
+use goose::context_mgmt::budget::ContextBudget;
+use goose::context_mgmt::get_messages_token_counts_async;
+use goose::context_mgmt::strategy::{self, CompactionStrategyMetadata};
+use goose::conversation::{
+    message::Message, repair_conversation, Conversation, ConversationIssue, RepairPolicy,
+};
+use goose::token_counter::create_async_token_counter;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use utoipa::ToSchema;
@@ -17,7 +23,8 @@ use utoipa::ToSchema;
 pub struct ContextManageRequest {
     /// Collection of messages to be managed
     pub messages: Vec<Message>,
-    /// Operation to perform: "truncation" or "summarize"
+    /// Operation to perform: "truncation", "summarize", or the name of a compaction strategy
+    /// from /context/strategies
     pub manage_action: String,
 }
 
@@ -29,6 +36,10 @@ pub struct ContextManageResponse {
     pub messages: Vec<Message>,
     /// Token counts for each processed message
     pub token_counts: Vec<usize>,
+    /// Total tokens of the messages before the operation
+    pub tokens_before: usize,
+    /// Total tokens of the messages after the operation
+    pub tokens_after: usize,
 }
 
 #[utoipa::path(
@@ -37,6 +48,7 @@ pub struct ContextManageResponse {
     request_body = ContextManageRequest,
     responses(
         (status = 200, description = "Context managed successfully", body = ContextManageResponse),
+        (status = 400, description = "Unknown manage action"),
         (status = 401, description = "Unauthorized - Invalid or missing API key"),
         (status = 412, description = "Precondition failed - Agent not available"),
         (status = 500, description = "Internal server error")
@@ -58,24 +70,145 @@ async fn manage_context(
         .await
         .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
 
-    let mut processed_messages = Conversation::new_unvalidated(vec![]);
-    let mut token_counts: Vec<usize> = vec![];
-
-    if request.manage_action == "truncation" {
-        (processed_messages, token_counts) = agent
-            .truncate_context(&request.messages)
-            .await
-            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    } else if request.manage_action == "summarize" {
-        (processed_messages, token_counts) = agent
-            .summarize_context(&request.messages)
-            .await
-            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let token_counter = create_async_token_counter()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let tokens_before: usize = get_messages_token_counts_async(&token_counter, &request.messages)
+        .iter()
+        .sum();
+
+    let (processed_messages, token_counts) = match request.manage_action.as_str() {
+        "truncation" => agent.truncate_context(&request.messages).await,
+        "summarize" => agent.summarize_context(&request.messages).await,
+        name => {
+            strategy::create(name).map_err(|_| StatusCode::BAD_REQUEST)?;
+            agent.compact_context(&request.messages, name, None).await
+        }
     }
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     Ok(Json(ContextManageResponse {
         messages: processed_messages.messages().clone(),
+        tokens_after: token_counts.iter().sum(),
         token_counts,
+        tokens_before,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/context/strategies",
+    responses(
+        (status = 200, description = "Available compaction strategies", body = Vec<CompactionStrategyMetadata>),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Context Management"
+)]
+async fn list_strategies(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<CompactionStrategyMetadata>>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    Ok(Json(strategy::strategies()))
+}
+
+/// Request payload for the context budget
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextBudgetRequest {
+    /// Messages that would be sent with the next request
+    pub messages: Vec<Message>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/context/budget",
+    request_body = ContextBudgetRequest,
+    responses(
+        (status = 200, description = "Token breakdown of the next request", body = ContextBudget),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 412, description = "Precondition failed - Agent not available"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Context Management"
+)]
+async fn context_budget(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<ContextBudgetRequest>,
+) -> Result<Json<ContextBudget>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let agent = state
+        .get_agent()
+        .await
+        .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+
+    let budget = agent.context_budget(&request.messages).await.map_err(|e| {
+        tracing::error!("Failed to compute context budget: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(budget))
+}
+
+/// Request payload for conversation validation
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextValidateRequest {
+    /// Messages to validate
+    pub messages: Vec<Message>,
+    /// How to repair the issues found; defaults to dropping orphaned tool requests and
+    /// merging consecutive messages
+    #[serde(default)]
+    pub policy: RepairPolicy,
+}
+
+/// Response from conversation validation
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextValidateResponse {
+    /// Issues found, with the index of the message each one was found in
+    pub issues: Vec<ConversationIssue>,
+    /// Messages after applying the repair policy
+    pub messages: Vec<Message>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/context/validate",
+    request_body = ContextValidateRequest,
+    responses(
+        (status = 200, description = "Conversation validated successfully", body = ContextValidateResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Context Management"
+)]
+async fn validate_context(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<ContextValidateRequest>,
+) -> Result<Json<ContextValidateResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let (repaired, issues) = repair_conversation(
+        Conversation::new_unvalidated(request.messages),
+        &request.policy,
+    );
+
+    Ok(Json(ContextValidateResponse {
+        issues,
+        messages: repaired.messages().clone(),
     }))
 }
 
@@ -83,5 +216,8 @@ async fn manage_context(
 pub fn routes(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/context/manage", post(manage_context))
+        .route("/context/validate", post(validate_context))
+        .route("/context/budget", post(context_budget))
+        .route("/context/strategies", get(list_strategies))
         .with_state(state)
 }
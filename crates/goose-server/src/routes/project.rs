@@ -269,12 +269,13 @@ async fn add_session_to_project(
     let session_path =
         goose::session::get_path(goose::session::Identifier::Name(session_id.clone()))
             .map_err(|_| StatusCode::NOT_FOUND)?;
-    let mut metadata =
-        goose::session::read_metadata(&session_path).map_err(|_| StatusCode::NOT_FOUND)?;
-    metadata.project_id = Some(project_id);
 
-    tokio::task::spawn(async move {
-        if let Err(e) = goose::session::update_metadata(&session_path, &metadata).await {
+    tokio::task::spawn_blocking(move || {
+        let updated = goose::session::update_metadata_with(&session_path, |metadata| {
+            metadata.project_id = Some(project_id);
+            Ok(())
+        });
+        if let Err(e) = updated {
             tracing::error!("Failed to update session metadata: {}", e);
         }
     });
@@ -321,19 +322,19 @@ async fn remove_session_from_project(
     let session_path =
         goose::session::get_path(goose::session::Identifier::Name(session_id.clone()))
             .map_err(|_| StatusCode::NOT_FOUND)?;
-    let mut metadata =
-        goose::session::read_metadata(&session_path).map_err(|_| StatusCode::NOT_FOUND)?;
-
-    // Only update if this session was actually in this project
-    if metadata.project_id.as_deref() == Some(&project_id) {
-        metadata.project_id = None;
 
-        tokio::task::spawn(async move {
-            if let Err(e) = goose::session::update_metadata(&session_path, &metadata).await {
-                tracing::error!("Failed to update session metadata: {}", e);
+    tokio::task::spawn_blocking(move || {
+        let updated = goose::session::update_metadata_with(&session_path, |metadata| {
+            // Only update if this session was actually in this project
+            if metadata.project_id.as_deref() == Some(&project_id) {
+                metadata.project_id = None;
             }
+            Ok(())
         });
-    }
+        if let Err(e) = updated {
+            tracing::error!("Failed to update session metadata: {}", e);
+        }
+    });
 
     Ok(StatusCode::NO_CONTENT)
 }
@@ -1,8 +1,12 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use axum::{extract::State, http::StatusCode, routing::post, Json, Router};
 use goose::conversation::{message::Message, Conversation};
-use goose::recipe::Recipe;
+use goose::recipe::build_recipe::apply_values_to_parameters;
+use goose::recipe::parameter_values::validate_parameter_values;
+use goose::recipe::signing::{check_trust, SignaturePolicy};
+use goose::recipe::{Recipe, BUILT_IN_RECIPE_DIR_PARAM};
 use goose::recipe_deeplink;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
@@ -54,6 +58,23 @@ pub struct DecodeRecipeRequest {
 #[derive(Debug, Serialize, ToSchema)]
 pub struct DecodeRecipeResponse {
     recipe: Recipe,
+    /// Set when the recipe is not signed by a trusted key but is allowed by the signature policy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    warning: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ValidateRecipeParamsRequest {
+    recipe: Recipe,
+    #[serde(default)]
+    params: HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ValidateRecipeParamsResponse {
+    /// Parameter values after defaults and coercion, as they would be passed to the recipe
+    values: HashMap<String, String>,
+    errors: Vec<String>,
 }
 
 #[utoipa::path(
@@ -148,27 +169,89 @@ async fn encode_recipe(
     request_body = DecodeRecipeRequest,
     responses(
         (status = 200, description = "Recipe decoded successfully", body = DecodeRecipeResponse),
-        (status = 400, description = "Bad request")
+        (status = 400, description = "Bad request, or the recipe has steps, which only run from the goose CLI"),
+        (status = 403, description = "Recipe blocked by the signature policy")
     ),
     tag = "Recipe Management"
 )]
 async fn decode_recipe(
     Json(request): Json<DecodeRecipeRequest>,
 ) -> Result<Json<DecodeRecipeResponse>, StatusCode> {
-    match recipe_deeplink::decode(&request.deeplink) {
-        Ok(recipe) => Ok(Json(DecodeRecipeResponse { recipe })),
+    let (recipe, verification) = match recipe_deeplink::decode_verified(&request.deeplink) {
+        Ok(decoded) => decoded,
         Err(err) => {
             tracing::error!("Failed to decode deeplink: {}", err);
-            Err(StatusCode::BAD_REQUEST)
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    };
+    if recipe.steps.as_ref().is_some_and(|steps| !steps.is_empty()) {
+        tracing::error!("Refusing deeplink recipe: steps only run from the goose CLI");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    match check_trust(&verification, &recipe, SignaturePolicy::configured()) {
+        Ok(warning) => Ok(Json(DecodeRecipeResponse { recipe, warning })),
+        Err(err) => {
+            tracing::error!("Refusing deeplink recipe: {}", err);
+            Err(StatusCode::FORBIDDEN)
         }
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/recipes/validate_params",
+    request_body = ValidateRecipeParamsRequest,
+    responses(
+        (status = 200, description = "Parameter values are valid", body = ValidateRecipeParamsResponse),
+        (status = 400, description = "Missing or invalid parameter values", body = ValidateRecipeParamsResponse)
+    ),
+    tag = "Recipe Management"
+)]
+/// Check parameter values against the types and constraints declared by a recipe
+async fn validate_recipe_params(
+    Json(request): Json<ValidateRecipeParamsRequest>,
+) -> Result<Json<ValidateRecipeParamsResponse>, (StatusCode, Json<ValidateRecipeParamsResponse>)> {
+    let parameters = request.recipe.parameters.unwrap_or_default();
+    let params: Vec<(String, String)> = request.params.into_iter().collect();
+    let (mut values, missing_params) = apply_values_to_parameters(
+        &params,
+        Some(parameters.clone()),
+        "",
+        None::<fn(&str, &str) -> anyhow::Result<String>>,
+    )
+    .map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ValidateRecipeParamsResponse {
+                values: HashMap::new(),
+                errors: vec![e.to_string()],
+            }),
+        )
+    })?;
+    values.remove(BUILT_IN_RECIPE_DIR_PARAM);
+
+    let mut errors: Vec<String> = missing_params
+        .into_iter()
+        .map(|key| format!("{}: a value is required", key))
+        .collect();
+    if let Err(invalid) = validate_parameter_values(&parameters, &mut values) {
+        errors.extend(invalid);
+    }
+
+    let response = ValidateRecipeParamsResponse { values, errors };
+    if response.errors.is_empty() {
+        Ok(Json(response))
+    } else {
+        Err((StatusCode::BAD_REQUEST, Json(response)))
+    }
+}
+
 pub fn routes(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/recipes/create", post(create_recipe))
         .route("/recipes/encode", post(encode_recipe))
         .route("/recipes/decode", post(decode_recipe))
+        .route("/recipes/validate_params", post(validate_recipe_params))
         .with_state(state)
 }
 
@@ -206,4 +289,48 @@ mod tests {
         assert!(!encoded_again.is_empty());
         assert_eq!(encoded, encoded_again);
     }
+
+    #[tokio::test]
+    async fn test_validate_recipe_params() {
+        let recipe = Recipe::from_content(
+            r#"
+title: Sized
+description: Takes a size and a count
+instructions: Make {{ count }} {{ size }} things
+parameters:
+  - key: size
+    input_type: select
+    requirement: required
+    description: How big
+    options: [small, large]
+  - key: count
+    input_type: number
+    requirement: optional
+    description: How many
+    default: "3"
+    min: 1
+    max: 10
+"#,
+        )
+        .unwrap();
+
+        let request = ValidateRecipeParamsRequest {
+            recipe: recipe.clone(),
+            params: HashMap::from([("size".to_string(), "large".to_string())]),
+        };
+        let response = validate_recipe_params(Json(request)).await.unwrap().0;
+        assert_eq!(response.values["count"], "3");
+        assert!(!response.values.contains_key(BUILT_IN_RECIPE_DIR_PARAM));
+
+        let request = ValidateRecipeParamsRequest {
+            recipe,
+            params: HashMap::from([
+                ("size".to_string(), "medium".to_string()),
+                ("count".to_string(), "20".to_string()),
+            ]),
+        };
+        let (status, response) = validate_recipe_params(Json(request)).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(response.0.errors.len(), 2);
+    }
 }
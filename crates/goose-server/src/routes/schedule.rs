@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use axum::{
@@ -12,20 +13,52 @@ use chrono::NaiveDateTime;
 
 use crate::routes::utils::verify_secret_key;
 use crate::state::AppState;
-use goose::scheduler::ScheduledJob;
+use goose::agents::ExtensionConfig;
+use goose::scheduler::{
+    CatchUpPolicy, JobRetryPolicy, JobTrigger, OverlapPolicy, ScheduledJob, ScheduledJobUpdate,
+};
+use goose::scheduler_history::JobRun;
+use goose::session::SessionArtifact;
 
 #[derive(Deserialize, Serialize, utoipa::ToSchema)]
 pub struct CreateScheduleRequest {
     id: String,
     recipe_source: String,
+    /// May be empty when the job has triggers
+    #[serde(default)]
     cron: String,
     #[serde(default)]
     execution_mode: Option<String>, // "foreground" or "background"
+    #[serde(default)]
+    parameters: HashMap<String, String>,
+    #[serde(default)]
+    provider: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    working_dir: Option<String>,
+    #[serde(default)]
+    extensions: Vec<ExtensionConfig>,
+    #[serde(default)]
+    triggers: Vec<JobTrigger>,
+    #[serde(default)]
+    overlap: OverlapPolicy,
+    #[serde(default)]
+    catch_up: CatchUpPolicy,
+    #[serde(default)]
+    catch_up_window_seconds: Option<u64>,
+    #[serde(default)]
+    max_runtime_seconds: Option<u64>,
+    #[serde(default)]
+    retry: Option<JobRetryPolicy>,
 }
 
 #[derive(Deserialize, Serialize, utoipa::ToSchema)]
 pub struct UpdateScheduleRequest {
-    cron: String,
+    #[serde(default)]
+    cron: Option<String>,
+    #[serde(flatten)]
+    settings: ScheduledJobUpdate,
 }
 
 #[derive(Serialize, utoipa::ToSchema)]
@@ -81,6 +114,7 @@ pub struct SessionDisplayInfo {
     accumulated_total_tokens: Option<i32>,
     accumulated_input_tokens: Option<i32>,
     accumulated_output_tokens: Option<i32>,
+    artifacts: Vec<SessionArtifact>,
 }
 
 fn parse_session_name_to_iso(session_name: &str) -> String {
@@ -95,7 +129,7 @@ fn parse_session_name_to_iso(session_name: &str) -> String {
     request_body = CreateScheduleRequest,
     responses(
         (status = 200, description = "Scheduled job created successfully", body = ScheduledJob),
-        (status = 400, description = "Invalid cron expression or recipe file"),
+        (status = 400, description = "Invalid cron expression, recipe file or job settings"),
         (status = 409, description = "Job ID already exists"),
         (status = 500, description = "Internal server error")
     ),
@@ -127,6 +161,17 @@ async fn create_schedule(
         current_session_id: None,
         process_start_time: None,
         execution_mode: req.execution_mode.or(Some("background".to_string())), // Default to background
+        parameters: req.parameters,
+        provider: req.provider,
+        model: req.model,
+        working_dir: req.working_dir,
+        extensions: req.extensions,
+        triggers: req.triggers,
+        overlap: req.overlap,
+        catch_up: req.catch_up,
+        catch_up_window_seconds: req.catch_up_window_seconds,
+        max_runtime_seconds: req.max_runtime_seconds,
+        retry: req.retry,
     };
     scheduler
         .add_scheduled_job(job.clone())
@@ -137,6 +182,7 @@ async fn create_schedule(
                 goose::scheduler::SchedulerError::JobNotFound(_) => StatusCode::NOT_FOUND,
                 goose::scheduler::SchedulerError::CronParseError(_) => StatusCode::BAD_REQUEST,
                 goose::scheduler::SchedulerError::RecipeLoadError(_) => StatusCode::BAD_REQUEST,
+                goose::scheduler::SchedulerError::InvalidJobSettings(_) => StatusCode::BAD_REQUEST,
                 goose::scheduler::SchedulerError::JobIdExists(_) => StatusCode::CONFLICT,
                 _ => StatusCode::INTERNAL_SERVER_ERROR,
             }
@@ -303,6 +349,7 @@ async fn sessions_handler(
                     accumulated_total_tokens: metadata.accumulated_total_tokens,
                     accumulated_input_tokens: metadata.accumulated_input_tokens,
                     accumulated_output_tokens: metadata.accumulated_output_tokens,
+                    artifacts: metadata.artifacts,
                 })
                 .collect();
             Ok(Json(display_infos))
@@ -317,6 +364,93 @@ async fn sessions_handler(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/schedule/{id}/webhook",
+    params(
+        ("id" = String, Path, description = "ID of the schedule to run"),
+        ("X-Goose-Webhook-Secret" = String, Header, description = "Secret of the schedule's webhook trigger")
+    ),
+    request_body(content = String, description = "Passed to the recipe parameter named by the trigger"),
+    responses(
+        (status = 202, description = "The run was started"),
+        (status = 404, description = "No schedule with a webhook trigger and this secret"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "schedule"
+)]
+#[axum::debug_handler]
+async fn webhook_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    body: String,
+) -> Result<StatusCode, StatusCode> {
+    // Called by other services, so authenticated with the job's own secret
+    let secret = headers
+        .get("X-Goose-Webhook-Secret")
+        .and_then(|value| value.to_str().ok())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    let scheduler = state
+        .scheduler()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    scheduler
+        .trigger_webhook(&id, secret, body)
+        .await
+        .map_err(|e| {
+            eprintln!("Error triggering webhook of schedule '{}': {:?}", id, e);
+            match e {
+                goose::scheduler::SchedulerError::JobNotFound(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            }
+        })?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+#[utoipa::path(
+    get,
+    path = "/schedule/{id}/history",
+    params(
+        ("id" = String, Path, description = "ID of the schedule"),
+        SessionsQuery
+    ),
+    responses(
+        (status = 200, description = "The newest runs of the schedule, newest first", body = Vec<JobRun>),
+        (status = 404, description = "Scheduled job not found"),
+        (status = 500, description = "Internal server error"),
+        (status = 501, description = "The scheduler in use does not keep run history")
+    ),
+    tag = "schedule"
+)]
+#[axum::debug_handler]
+async fn run_history_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Query(query_params): Query<SessionsQuery>,
+) -> Result<Json<Vec<JobRun>>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+    let scheduler = state
+        .scheduler()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    scheduler
+        .run_history(&id, query_params.limit as usize)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            eprintln!("Error fetching run history for schedule '{}': {:?}", id, e);
+            match e {
+                goose::scheduler::SchedulerError::JobNotFound(_) => StatusCode::NOT_FOUND,
+                goose::scheduler::SchedulerError::Unsupported(_) => StatusCode::NOT_IMPLEMENTED,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            }
+        })
+}
+
 #[utoipa::path(
     post,
     path = "/schedule/{id}/pause",
@@ -417,18 +551,29 @@ async fn update_schedule(
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    scheduler
-        .update_schedule(&id, req.cron)
-        .await
-        .map_err(|e| {
-            eprintln!("Error updating schedule '{}': {:?}", id, e);
-            match e {
-                goose::scheduler::SchedulerError::JobNotFound(_) => StatusCode::NOT_FOUND,
-                goose::scheduler::SchedulerError::AnyhowError(_) => StatusCode::BAD_REQUEST,
-                goose::scheduler::SchedulerError::CronParseError(_) => StatusCode::BAD_REQUEST,
-                _ => StatusCode::INTERNAL_SERVER_ERROR,
-            }
-        })?;
+    let update_error = |e: goose::scheduler::SchedulerError| {
+        eprintln!("Error updating schedule '{}': {:?}", id, e);
+        match e {
+            goose::scheduler::SchedulerError::JobNotFound(_) => StatusCode::NOT_FOUND,
+            goose::scheduler::SchedulerError::AnyhowError(_) => StatusCode::BAD_REQUEST,
+            goose::scheduler::SchedulerError::CronParseError(_) => StatusCode::BAD_REQUEST,
+            goose::scheduler::SchedulerError::RecipeLoadError(_) => StatusCode::BAD_REQUEST,
+            goose::scheduler::SchedulerError::InvalidJobSettings(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    };
+    if let Some(cron) = req.cron {
+        scheduler
+            .update_schedule(&id, cron)
+            .await
+            .map_err(update_error)?;
+    }
+    if !req.settings.is_empty() {
+        scheduler
+            .update_job(&id, req.settings)
+            .await
+            .map_err(update_error)?;
+    }
 
     // Return the updated schedule
     let jobs = scheduler.list_scheduled_jobs().await.map_err(|e| {
@@ -541,5 +686,7 @@ pub fn routes(state: Arc<AppState>) -> Router {
         .route("/schedule/{id}/kill", post(kill_running_job))
         .route("/schedule/{id}/inspect", get(inspect_running_job))
         .route("/schedule/{id}/sessions", get(sessions_handler)) // Corrected
+        .route("/schedule/{id}/history", get(run_history_handler))
+        .route("/schedule/{id}/webhook", post(webhook_handler))
         .with_state(state)
 }
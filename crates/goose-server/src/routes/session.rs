@@ -6,14 +6,16 @@ use std::sync::Arc;
 use crate::state::AppState;
 use axum::{
     extract::{Path, State},
-    http::{HeaderMap, StatusCode},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
     routing::{get, put},
     Json, Router,
 };
 use goose::conversation::message::Message;
 use goose::session;
+use goose::session::artifacts::artifact_path;
 use goose::session::info::{get_valid_sorted_sessions, SessionInfo, SortOrder};
-use goose::session::SessionMetadata;
+use goose::session::{SessionArtifact, SessionMetadata};
 use serde::{Deserialize, Serialize};
 use tracing::{error, info};
 use utoipa::ToSchema;
@@ -36,6 +38,13 @@ pub struct SessionHistoryResponse {
     messages: Vec<Message>,
 }
 
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionArtifactsResponse {
+    /// Output files collected after the recipe run of the session
+    artifacts: Vec<SessionArtifact>,
+}
+
 #[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateSessionMetadataRequest {
@@ -45,6 +54,13 @@ pub struct UpdateSessionMetadataRequest {
 
 const MAX_DESCRIPTION_LENGTH: usize = 200;
 
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetMessagePinnedRequest {
+    /// Whether the message is kept verbatim through context compaction
+    pinned: bool,
+}
+
 #[derive(Serialize, ToSchema, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionInsights {
@@ -345,18 +361,136 @@ async fn update_session_metadata(
     let session_path = session::get_path(session::Identifier::Name(session_id.clone()))
         .map_err(|_| StatusCode::BAD_REQUEST)?;
 
-    // Read current metadata
-    let mut metadata = session::read_metadata(&session_path).map_err(|_| StatusCode::NOT_FOUND)?;
+    // Update the description, holding the session lock from the read to the write
+    session::update_metadata_with(&session_path, |metadata| {
+        metadata.description = request.description;
+        Ok(())
+    })
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    // Update description
-    metadata.description = request.description;
+    Ok(StatusCode::OK)
+}
 
-    // Save updated metadata
-    session::update_metadata(&session_path, &metadata)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+#[utoipa::path(
+    put,
+    path = "/sessions/{session_id}/messages/{message_index}/pinned",
+    request_body = SetMessagePinnedRequest,
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session"),
+        ("message_index" = usize, Path, description = "Index of the message in the session history")
+    ),
+    responses(
+        (status = 200, description = "Message pinned or unpinned successfully", body = Message),
+        (status = 400, description = "Bad request - Invalid session ID"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session or message not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+// Pin or unpin a message so compaction keeps it verbatim
+async fn set_message_pinned(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((session_id, message_index)): Path<(String, usize)>,
+    Json(request): Json<SetMessagePinnedRequest>,
+) -> Result<Json<Message>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
 
-    Ok(StatusCode::OK)
+    let session_path = session::get_path(session::Identifier::Name(session_id.clone()))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !session_path.exists() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let message = session::set_message_pinned(&session_path, message_index, request.pinned)
+        .map_err(|e| {
+            error!(
+                "Failed to update pinned message in session {}: {}",
+                session_id, e
+            );
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(message))
+}
+
+#[utoipa::path(
+    get,
+    path = "/sessions/{session_id}/artifacts",
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session")
+    ),
+    responses(
+        (status = 200, description = "Artifacts collected for the session", body = SessionArtifactsResponse),
+        (status = 400, description = "Bad request - Invalid session ID"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+// List the output files a recipe run collected for a session
+async fn list_session_artifacts(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> Result<Json<SessionArtifactsResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let session_path = session::get_path(session::Identifier::Name(session_id))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let metadata = session::read_metadata(&session_path).map_err(|_| StatusCode::NOT_FOUND)?;
+
+    Ok(Json(SessionArtifactsResponse {
+        artifacts: metadata.artifacts,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/sessions/{session_id}/artifacts/{path}",
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session"),
+        ("path" = String, Path, description = "Path of the artifact relative to the working directory of the run")
+    ),
+    responses(
+        (status = 200, description = "Content of the artifact", content_type = "application/octet-stream"),
+        (status = 400, description = "Bad request - Invalid session ID"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Artifact not found")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+// Download one of the output files collected for a session
+async fn get_session_artifact(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((session_id, artifact)): Path<(String, String)>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let session_path = session::get_path(session::Identifier::Name(session_id))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let path = artifact_path(&session_path, &artifact).map_err(|_| StatusCode::NOT_FOUND)?;
+    let content = tokio::fs::read(&path).await.map_err(|e| {
+        error!("Failed to read artifact {}: {}", path.display(), e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/octet-stream")],
+        content,
+    ))
 }
 
 // Configure routes for this module
@@ -370,6 +504,18 @@ pub fn routes(state: Arc<AppState>) -> Router {
             "/sessions/{session_id}/metadata",
             put(update_session_metadata),
         )
+        .route(
+            "/sessions/{session_id}/artifacts",
+            get(list_session_artifacts),
+        )
+        .route(
+            "/sessions/{session_id}/artifacts/{*path}",
+            get(get_session_artifact),
+        )
+        .route(
+            "/sessions/{session_id}/messages/{message_index}/pinned",
+            put(set_message_pinned),
+        )
         .with_state(state)
 }
 
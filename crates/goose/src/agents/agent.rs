@@ -2,8 +2,10 @@ use std::collections::{HashMap, HashSet};
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Instant;
 
 use anyhow::{anyhow, Result};
+use chrono::Utc;
 use futures::stream::BoxStream;
 use futures::{stream, FutureExt, Stream, StreamExt, TryStreamExt};
 use uuid::Uuid;
@@ -14,13 +16,14 @@ use crate::agents::final_output_tool::{FINAL_OUTPUT_CONTINUATION_MESSAGE, FINAL_
 use crate::agents::platform_tools::{
     PLATFORM_LIST_RESOURCES_TOOL_NAME, PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME,
     PLATFORM_MANAGE_SCHEDULE_TOOL_NAME, PLATFORM_READ_RESOURCE_TOOL_NAME,
-    PLATFORM_SEARCH_AVAILABLE_EXTENSIONS_TOOL_NAME,
+    PLATFORM_SEARCH_AVAILABLE_EXTENSIONS_TOOL_NAME, PLATFORM_SEARCH_PROJECT_TOOL_NAME,
 };
+use crate::agents::project_index::{self, ProjectIndex};
 use crate::agents::prompt_manager::PromptManager;
 use crate::agents::recipe_tools::dynamic_task_tools::{
     create_dynamic_task, create_dynamic_task_tool, DYNAMIC_TASK_TOOL_NAME_PREFIX,
 };
-use crate::agents::retry::{RetryManager, RetryResult};
+use crate::agents::retry::{CheckContext, RetryManager, RetryResult};
 use crate::agents::router_tool_selector::RouterToolSelectionStrategy;
 use crate::agents::router_tools::{ROUTER_LLM_SEARCH_TOOL_NAME, ROUTER_VECTOR_SEARCH_TOOL_NAME};
 use crate::agents::sub_recipe_manager::SubRecipeManager;
@@ -30,18 +33,20 @@ use crate::agents::subagent_execution_tool::subagent_execute_task_tool::{
 use crate::agents::subagent_execution_tool::tasks_manager::TasksManager;
 use crate::agents::tool_route_manager::ToolRouteManager;
 use crate::agents::tool_router_index_manager::ToolRouterIndexManager;
-use crate::agents::types::SessionConfig;
 use crate::agents::types::{FrontendTool, ToolResultReceiver};
+use crate::agents::types::{RetryConfig, SessionConfig};
 use crate::config::{Config, ExtensionConfigManager, PermissionManager};
 use crate::context_mgmt::auto_compact;
 use crate::conversation::{debug_conversation_fix, fix_conversation, Conversation};
 use crate::permission::permission_judge::{check_tool_permissions, PermissionCheckResult};
 use crate::permission::PermissionConfirmation;
-use crate::providers::base::Provider;
+use crate::providers::base::{Provider, ProviderUsage};
 use crate::providers::errors::ProviderError;
+use crate::recipe::from_session::{parameterize, propose_success_checks, ProposedParameter};
 use crate::recipe::{Author, Recipe, Response, Settings, SubRecipe};
 use crate::scheduler_trait::SchedulerTrait;
 use crate::session;
+use crate::session::stats::{MessageStats, ToolCallStats};
 use crate::tool_monitor::{ToolCall, ToolMonitor};
 use crate::utils::is_token_cancelled;
 use mcp_core::{ToolError, ToolResult};
@@ -59,6 +64,8 @@ use crate::agents::subagent_task_config::TaskConfig;
 use crate::conversation::message::{Message, ToolRequest};
 
 const DEFAULT_MAX_TURNS: u32 = 1000;
+/// Retries given to recipes created from a session when success checks were proposed
+const GENERATED_RECIPE_MAX_RETRIES: u32 = 2;
 
 /// Context needed for the reply function
 pub struct ReplyContext {
@@ -97,6 +104,10 @@ pub struct Agent {
     pub(super) tool_route_manager: ToolRouteManager,
     pub(super) scheduler_service: Mutex<Option<Arc<dyn SchedulerTrait>>>,
     pub(super) retry_manager: RetryManager,
+    pub(super) compaction_strategy: Mutex<Option<String>>,
+    pub(super) project_index: Mutex<Option<Arc<ProjectIndex>>>,
+    /// Provider error that ended the last reply
+    pub(super) provider_error: Mutex<Option<String>>,
 }
 
 #[derive(Clone, Debug)]
@@ -173,6 +184,9 @@ impl Agent {
             tool_route_manager: ToolRouteManager::new(),
             scheduler_service: Mutex::new(None),
             retry_manager,
+            compaction_strategy: Mutex::new(None),
+            project_index: Mutex::new(None),
+            provider_error: Mutex::new(None),
         }
     }
 
@@ -196,16 +210,44 @@ impl Agent {
         self.retry_manager.get_attempts().await
     }
 
+    /// The provider error that ended the last reply, if it ended on one
+    pub async fn last_provider_error(&self) -> Option<String> {
+        self.provider_error.lock().await.clone()
+    }
+
     /// Handle retry logic for the agent reply loop
     async fn handle_retry_logic(
         &self,
         messages: &mut Conversation,
         session: &Option<SessionConfig>,
         initial_messages: &[Message],
+        latest_messages: &[Message],
     ) -> Result<bool> {
+        let final_output = self
+            .final_output_tool
+            .lock()
+            .await
+            .as_ref()
+            .and_then(|tool| tool.final_output.clone());
+        let check_context = CheckContext::new(
+            messages
+                .messages()
+                .iter()
+                .chain(latest_messages)
+                .cloned()
+                .collect(),
+            final_output,
+            self.provider().await.ok(),
+        );
         let result = self
             .retry_manager
-            .handle_retry_logic(messages, session, initial_messages, &self.final_output_tool)
+            .handle_retry_logic(
+                messages,
+                session,
+                initial_messages,
+                &self.final_output_tool,
+                &check_context,
+            )
             .await?;
 
         match result {
@@ -384,6 +426,11 @@ impl Agent {
             return (request_id, Ok(ToolCallResult::from(result)));
         }
 
+        if tool_call.name == PLATFORM_SEARCH_PROJECT_TOOL_NAME {
+            let result = self.handle_project_search(tool_call.arguments).await;
+            return (request_id, Ok(ToolCallResult::from(result)));
+        }
+
         if tool_call.name == PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME {
             let extension_name = tool_call
                 .arguments
@@ -687,6 +734,10 @@ impl Agent {
             // Dynamic task tool
             prefixed_tools.push(create_dynamic_task_tool());
 
+            if project_index::is_enabled() {
+                prefixed_tools.push(platform_tools::search_project_tool());
+            }
+
             // Add resource tools if supported
             if extension_manager.supports_resources() {
                 prefixed_tools.extend([
@@ -792,7 +843,8 @@ impl Agent {
                 (compact_result.tokens_before, compact_result.tokens_after)
             {
                 format!(
-                    "Auto-compacted context: {} → {} tokens ({:.0}% reduction)\n\n",
+                    "Auto-compacted context with {} strategy: {} → {} tokens ({:.0}% reduction)\n\n",
+                    compact_result.strategy.as_deref().unwrap_or("default"),
                     before,
                     after,
                     (1.0 - (after as f64 / before as f64)) * 100.0
@@ -865,6 +917,7 @@ impl Agent {
         } = context;
         let reply_span = tracing::Span::current();
         self.reset_retry_attempts().await;
+        *self.provider_error.lock().await = None;
 
         if let Some(content) = messages
             .last()
@@ -907,6 +960,14 @@ impl Agent {
                     break;
                 }
 
+                let turn_created = Utc::now().timestamp();
+                let mut turn_usage: Option<ProviderUsage> = None;
+                let mut turn_message_id: Option<String> = None;
+                let mut tool_call_stats: Vec<ToolCallStats> = Vec::new();
+
+                // Only time spent waiting on the provider counts as its latency, not running
+                // tools or waiting for the user to approve them
+                let request_started = Instant::now();
                 let mut stream = Self::stream_response_from_provider(
                     self.provider().await?,
                     &system_prompt,
@@ -914,12 +975,19 @@ impl Agent {
                     &tools,
                     &toolshim_tools,
                 ).await?;
+                let mut provider_time = request_started.elapsed();
 
                 let mut added_message = false;
                 let mut messages_to_add = Vec::new();
                 let mut tools_updated = false;
 
-                while let Some(next) = stream.next().await {
+                loop {
+                    let waiting_started = Instant::now();
+                    let Some(next) = stream.next().await else {
+                        break;
+                    };
+                    provider_time += waiting_started.elapsed();
+
                     if is_token_cancelled(&cancel_token) {
                         break;
                     }
@@ -954,8 +1022,14 @@ impl Agent {
                                         .await?;
                                 }
                             }
+                            if usage.is_some() {
+                                turn_usage = usage;
+                            }
 
                             if let Some(response) = response {
+                                if response.id.is_some() {
+                                    turn_message_id = response.id.clone();
+                                }
                                 let ToolCategorizeResult {
                                     frontend_requests,
                                     remaining_requests,
@@ -975,7 +1049,6 @@ impl Agent {
                                 if num_tool_requests == 0 {
                                     continue;
                                 }
-
                                 let message_tool_response = Arc::new(Mutex::new(Message::user().with_id(
                                     format!("msg_{}", Uuid::new_v4())
                                 )));
@@ -1044,6 +1117,13 @@ impl Agent {
                                         })
                                         .collect::<Vec<_>>();
 
+                                    let tool_names: HashMap<String, String> = remaining_requests
+                                        .iter()
+                                        .filter_map(|request| {
+                                            request.tool_call.as_ref().ok().map(|call| (request.id.clone(), call.name.clone()))
+                                        })
+                                        .collect();
+                                    let execution_started = Instant::now();
                                     let mut combined = stream::select_all(with_id);
                                     let mut all_install_successful = true;
 
@@ -1058,6 +1138,11 @@ impl Agent {
                                                 {
                                                     all_install_successful = false;
                                                 }
+                                                tool_call_stats.push(ToolCallStats {
+                                                    name: tool_names.get(&request_id).cloned().unwrap_or_default(),
+                                                    id: request_id.clone(),
+                                                    duration_ms: execution_started.elapsed().as_millis() as u64,
+                                                });
                                                 let mut response = message_tool_response.lock().await;
                                                 *response =
                                                     response.clone().with_tool_response(request_id, output);
@@ -1091,6 +1176,7 @@ impl Agent {
                         }
                         Err(e) => {
                             error!("Error: {}", e);
+                            *self.provider_error.lock().await = Some(e.to_string());
                             yield AgentEvent::Message(Message::assistant().with_text(
                                     format!("Ran into this error: {e}.\n\nPlease retry if you think this is a transient or recoverable error.")
                                 ));
@@ -1098,6 +1184,19 @@ impl Agent {
                         }
                     }
                 }
+                if let (Some(session_config), Some(usage)) = (&session, turn_usage) {
+                    let stats = MessageStats::new(
+                        turn_message_id,
+                        turn_created,
+                        &usage,
+                        provider_time.as_millis() as u64,
+                        tool_call_stats,
+                    );
+                    // Stats are informational, so failing to record them doesn't end the reply
+                    if let Err(e) = Self::record_message_stats(session_config, &stats) {
+                        tracing::warn!("Failed to record message stats: {}", e);
+                    }
+                }
                 if tools_updated {
                     (tools, toolshim_tools, system_prompt) = self.prepare_tools_and_prompt().await?;
                 }
@@ -1116,7 +1215,7 @@ impl Agent {
                         }
                     }
 
-                    match self.handle_retry_logic(&mut messages, &session, &initial_messages).await {
+                    match self.handle_retry_logic(&mut messages, &session, &initial_messages, &messages_to_add).await {
                         Ok(should_retry) => {
                             if should_retry {
                                 info!("Retry logic triggered, restarting agent loop");
@@ -1269,6 +1368,7 @@ impl Agent {
 
         let recipe_prompt = prompt_manager.get_recipe_prompt().await;
         let tools = extension_manager.get_prefixed_tools(None).await?;
+        let session_messages = messages.messages().clone();
 
         messages.push(Message::user().with_text(recipe_prompt));
 
@@ -1293,7 +1393,7 @@ impl Agent {
             .to_string();
 
         // try to parse json response from the LLM
-        let (instructions, activities) =
+        let (instructions, activities, proposed_parameters) =
             if let Ok(json_content) = serde_json::from_str::<Value>(&clean_content) {
                 let instructions = json_content
                     .get("instructions")
@@ -1315,7 +1415,13 @@ impl Agent {
                     })
                     .collect::<Result<_, _>>()?;
 
-                (instructions, activities)
+                // Parameters are optional, a recipe without them still works
+                let proposed_parameters: Vec<ProposedParameter> = json_content
+                    .get("parameters")
+                    .and_then(|params| serde_json::from_value(params.clone()).ok())
+                    .unwrap_or_default();
+
+                (instructions, activities, proposed_parameters)
             } else {
                 // If we can't get valid JSON, try string parsing
                 // Use split_once to get the content after "Instructions:".
@@ -1346,8 +1452,9 @@ impl Agent {
                     .filter(|line| !line.is_empty())
                     .collect();
 
-                (instructions, activities)
+                (instructions, activities, Vec::new())
             };
+        let (instructions, parameters) = parameterize(&instructions, proposed_parameters);
 
         let extensions = ExtensionConfigManager::get_all().unwrap_or_default();
         let extension_configs: Vec<_> = extensions
@@ -1355,6 +1462,7 @@ impl Agent {
             .filter(|e| e.enabled)
             .map(|e| e.config.clone())
             .collect();
+        let success_checks = propose_success_checks(session_messages.as_slice());
 
         let author = Author {
             contact: std::env::var("USER")
@@ -1374,18 +1482,30 @@ impl Agent {
             goose_provider: Some(provider_name.clone()),
             goose_model: Some(model_name.clone()),
             temperature: Some(model_config.temperature.unwrap_or(0.0)),
+            compaction_strategy: self.compaction_strategy.lock().await.clone(),
         };
 
-        let recipe = Recipe::builder()
+        let mut builder = Recipe::builder()
             .title("Custom recipe from chat")
             .description("a custom recipe instance from this chat session")
             .instructions(instructions)
             .activities(activities)
             .extensions(extension_configs)
             .settings(settings)
-            .author(author)
-            .build()
-            .expect("valid recipe");
+            .author(author);
+        if !parameters.is_empty() {
+            builder = builder.parameters(parameters);
+        }
+        if !success_checks.is_empty() {
+            builder = builder.retry(RetryConfig {
+                max_retries: GENERATED_RECIPE_MAX_RETRIES,
+                checks: success_checks,
+                on_failure: None,
+                timeout_seconds: None,
+                on_failure_timeout_seconds: None,
+            });
+        }
+        let recipe = builder.build().expect("valid recipe");
 
         Ok(recipe)
     }
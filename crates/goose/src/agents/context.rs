@@ -4,14 +4,17 @@ use crate::conversation::message::Message;
 use crate::conversation::Conversation;
 use crate::token_counter::create_async_token_counter;
 
+use crate::context_mgmt::budget::ContextBudget;
+use crate::context_mgmt::strategy;
 use crate::context_mgmt::summarize::summarize_messages_async;
-use crate::context_mgmt::truncate::{truncate_messages, OldestFirstTruncation};
+use crate::context_mgmt::truncate::truncate_messages;
 use crate::context_mgmt::{estimate_target_context_limit, get_messages_token_counts_async};
 
 use super::super::agents::Agent;
 
 impl Agent {
     /// Public API to truncate oldest messages so that the conversation's token count is within the allowed context limit.
+    /// Old tool outputs are elided first when the agent uses the tool_output_elision strategy.
     pub async fn truncate_context(
         &self,
         messages: &[Message], // last message is a user msg that led to assistant message with_context_length_exceeded
@@ -23,11 +26,12 @@ impl Agent {
         let target_context_limit = estimate_target_context_limit(provider);
         let token_counts = get_messages_token_counts_async(&token_counter, messages);
 
+        let truncation = strategy::overflow_truncation(&self.compaction_strategy().await);
         let (mut new_messages, mut new_token_counts) = truncate_messages(
             messages,
             &token_counts,
             target_context_limit,
-            &OldestFirstTruncation,
+            truncation.as_ref(),
         )?;
 
         // Only add an assistant message if we have room for it and it won't cause another overflow
@@ -48,6 +52,72 @@ impl Agent {
         Ok((new_messages, new_token_counts))
     }
 
+    /// Choose the compaction strategy for this agent's session, overriding GOOSE_COMPACTION_STRATEGY
+    pub async fn set_compaction_strategy(
+        &self,
+        strategy: Option<String>,
+    ) -> Result<(), anyhow::Error> {
+        if let Some(name) = &strategy {
+            strategy::create(name)?;
+        }
+        *self.compaction_strategy.lock().await = strategy;
+        Ok(())
+    }
+
+    /// Name of the compaction strategy this agent uses
+    pub async fn compaction_strategy(&self) -> String {
+        strategy::resolve_strategy_name(self.compaction_strategy.lock().await.as_deref())
+    }
+
+    /// Public API to compact the conversation with a named strategy from the registry, so that
+    /// its token count is within `context_limit`, or the provider's target limit if not given.
+    pub async fn compact_context(
+        &self,
+        messages: &[Message],
+        strategy_name: &str,
+        context_limit: Option<usize>,
+    ) -> Result<(Conversation, Vec<usize>), anyhow::Error> {
+        let strategy = strategy::create(strategy_name)?;
+        let provider = self.provider().await?;
+        let token_counter = create_async_token_counter()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to create token counter: {}", e))?;
+        let context_limit =
+            context_limit.unwrap_or_else(|| estimate_target_context_limit(provider.clone()));
+
+        strategy
+            .compact(provider, &token_counter, messages, context_limit)
+            .await
+    }
+
+    /// Public API to break down the token cost of the next request to the provider by system
+    /// prompt section, extension tools, hints and message, with advice on what to reduce.
+    pub async fn context_budget(
+        &self,
+        messages: &[Message],
+    ) -> Result<ContextBudget, anyhow::Error> {
+        let provider = self.provider().await?;
+        let token_counter = create_async_token_counter()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to create token counter: {}", e))?;
+        let (tools, _, system_prompt) = self.prepare_tools_and_prompt().await?;
+        let extensions_info = self
+            .extension_manager
+            .read()
+            .await
+            .get_extensions_info()
+            .await;
+
+        Ok(ContextBudget::compute(
+            &token_counter,
+            &system_prompt,
+            &extensions_info,
+            &tools,
+            messages,
+            provider.get_model_config().context_limit(),
+        ))
+    }
+
     /// Public API to summarize the conversation so that its token count is within the allowed context limit.
     pub async fn summarize_context(
         &self,
@@ -11,6 +11,7 @@ use rmcp::transport::{
     ConfigureCommandExt, SseClientTransport, StreamableHttpClientTransport, TokioChildProcess,
 };
 use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::process::Stdio;
 use std::sync::Arc;
 use std::time::Duration;
@@ -42,6 +43,7 @@ pub struct ExtensionManager {
     instructions: HashMap<String, String>,
     resource_capable_extensions: HashSet<String>,
     temp_dirs: HashMap<String, tempfile::TempDir>,
+    working_dir: Option<PathBuf>,
 }
 
 /// A flattened representation of a resource used by the agent to prepare inference
@@ -79,7 +81,7 @@ impl ResourceItem {
 
 /// Sanitizes a string by replacing invalid characters with underscores.
 /// Valid characters match [a-zA-Z0-9_-]
-fn normalize(input: String) -> String {
+pub(crate) fn normalize(input: String) -> String {
     let mut result = String::with_capacity(input.len());
     for c in input.chars() {
         result.push(match c {
@@ -113,9 +115,16 @@ impl ExtensionManager {
             instructions: HashMap::new(),
             resource_capable_extensions: HashSet::new(),
             temp_dirs: HashMap::new(),
+            working_dir: None,
         }
     }
 
+    /// Start the processes of extensions added from now on in `working_dir` instead of the
+    /// current directory
+    pub fn set_working_dir(&mut self, working_dir: Option<PathBuf>) {
+        self.working_dir = working_dir;
+    }
+
     pub fn supports_resources(&self) -> bool {
         !self.resource_capable_extensions.is_empty()
     }
@@ -183,6 +192,7 @@ impl ExtensionManager {
             Ok(all_envs)
         }
 
+        let working_dir = self.working_dir.clone();
         let client: Box<dyn McpClientTrait> = match &config {
             ExtensionConfig::Sse { uri, timeout, .. } => {
                 let transport = SseClientTransport::start(uri.to_string()).await.map_err(
@@ -279,6 +289,9 @@ impl ExtensionManager {
                 let all_envs = merge_environments(envs, env_keys, &sanitized_name).await?;
                 let command = Command::new(cmd).configure(|command| {
                     command.args(args).envs(all_envs);
+                    if let Some(dir) = &working_dir {
+                        command.current_dir(dir);
+                    }
                 });
                 let (transport, mut stderr) = TokioChildProcess::builder(command)
                     .stderr(Stdio::piped())
@@ -329,6 +342,9 @@ impl ExtensionManager {
 
                 let transport = TokioChildProcess::new(Command::new(cmd).configure(|command| {
                     command.arg("mcp").arg(name);
+                    if let Some(dir) = &working_dir {
+                        command.current_dir(dir);
+                    }
                 }))?;
                 Box::new(
                     McpClient::connect(
@@ -359,6 +375,9 @@ impl ExtensionManager {
                     });
 
                     command.arg("python").arg(file_path.to_str().unwrap());
+                    if let Some(dir) = &working_dir {
+                        command.current_dir(dir);
+                    }
                 });
                 let transport = TokioChildProcess::new(command)?;
 
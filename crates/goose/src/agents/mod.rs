@@ -5,7 +5,10 @@ pub mod extension_manager;
 pub mod final_output_tool;
 mod large_response_handler;
 pub mod platform_tools;
+pub(crate) mod project_index;
+mod project_search_tool;
 pub mod prompt_manager;
+mod recipe_steps;
 mod recipe_tools;
 mod reply_parts;
 pub mod retry;
@@ -8,6 +8,7 @@ pub const PLATFORM_SEARCH_AVAILABLE_EXTENSIONS_TOOL_NAME: &str =
     "platform__search_available_extensions";
 pub const PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME: &str = "platform__manage_extensions";
 pub const PLATFORM_MANAGE_SCHEDULE_TOOL_NAME: &str = "platform__manage_schedule";
+pub const PLATFORM_SEARCH_PROJECT_TOOL_NAME: &str = "platform__search_project";
 
 pub fn read_resource_tool() -> Tool {
     Tool::new(
@@ -154,3 +155,33 @@ pub fn manage_schedule_tool() -> Tool {
         open_world_hint: Some(false),
     })
 }
+
+pub fn search_project_tool() -> Tool {
+    Tool::new(
+        PLATFORM_SEARCH_PROJECT_TOOL_NAME.to_string(),
+        indoc! {r#"
+            Search the current working directory for code relevant to a natural language query.
+
+            Uses a semantic index of the project that is updated automatically as files change
+            and excludes anything ignored by .gooseignore or .gitignore. Results are chunks of
+            files with their path and line range. Use this to find where something is implemented
+            before reading whole files.
+        "#}
+        .to_string(),
+        object!({
+            "type": "object",
+            "required": ["query"],
+            "properties": {
+                "query": {"type": "string", "description": "What to look for, e.g. 'where session files are written'"},
+                "k": {"type": "integer", "description": "Number of chunks to return", "default": 8}
+            }
+        }),
+    )
+    .annotate(ToolAnnotations {
+        title: Some("Search project code".to_string()),
+        read_only_hint: Some(true),
+        destructive_hint: Some(false),
+        idempotent_hint: Some(false),
+        open_world_hint: Some(false),
+    })
+}
@@ -0,0 +1,515 @@
+//! Semantic index of the working directory for retrieving relevant code
+//!
+//! Files are split into overlapping windows of lines, embedded with the embedding provider and
+//! stored in the same LanceDB database as the tool index, in one table per project. A manifest
+//! of file sizes, modification times and hashes keeps updates incremental: before every search,
+//! only files that changed since the last update are embedded again and deleted files are
+//! dropped. Files excluded by `.gooseignore` (global or in the project) or `.gitignore` are
+//! never indexed.
+
+use anyhow::{Context, Result};
+use arrow::array::{FixedSizeListBuilder, Float32Builder, StringArray, UInt32Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::{RecordBatch, RecordBatchIterator};
+use etcetera::{choose_app_strategy, AppStrategy};
+use futures::TryStreamExt;
+use ignore::WalkBuilder;
+use lancedb::connect;
+use lancedb::connection::Connection;
+use lancedb::query::{ExecutableQuery, QueryBase};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+use tokio::sync::Mutex;
+
+use crate::agents::tool_vectordb::ToolVectorDB;
+use crate::config::{Config, APP_STRATEGY};
+use crate::providers::base::Provider;
+
+/// Config flag that turns on the project index and its search tool
+pub const PROJECT_INDEX_CONFIG_KEY: &str = "GOOSE_PROJECT_INDEX";
+
+const CHUNK_LINES: usize = 60;
+const CHUNK_OVERLAP: usize = 10;
+const MAX_FILE_BYTES: u64 = 512 * 1024;
+const EMBEDDING_BATCH_SIZE: usize = 64;
+
+pub fn is_enabled() -> bool {
+    Config::global()
+        .get_param::<bool>(PROJECT_INDEX_CONFIG_KEY)
+        .unwrap_or(false)
+}
+
+/// A window of lines from a file in the project
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeChunk {
+    /// Path relative to the project root
+    pub path: String,
+    /// First line of the chunk, 1-based
+    pub start_line: usize,
+    /// Last line of the chunk, inclusive
+    pub end_line: usize,
+    pub content: String,
+}
+
+/// Summary of an incremental update
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct IndexUpdate {
+    pub files_indexed: usize,
+    pub files_removed: usize,
+    pub chunks_added: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct FileEntry {
+    size: u64,
+    modified: u64,
+    hash: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    files: HashMap<String, FileEntry>,
+}
+
+pub struct ProjectIndex {
+    root: PathBuf,
+    connection: Connection,
+    table_name: String,
+    manifest_path: PathBuf,
+    embedding_provider: Arc<dyn Provider>,
+    // Held while updating so concurrent searches don't embed the same files twice
+    update_lock: Mutex<()>,
+}
+
+impl ProjectIndex {
+    pub async fn new(root: PathBuf, embedding_provider: Arc<dyn Provider>) -> Result<Self> {
+        if !embedding_provider.supports_embeddings() {
+            return Err(anyhow::anyhow!(
+                "The embedding provider does not support embeddings; set GOOSE_EMBEDDING_MODEL_PROVIDER to one that does"
+            ));
+        }
+
+        let db_path = ToolVectorDB::get_db_path()?;
+        tokio::fs::create_dir_all(&db_path)
+            .await
+            .context("Failed to create database directory")?;
+        let connection = connect(db_path.to_str().unwrap())
+            .execute()
+            .await
+            .context("Failed to connect to LanceDB")?;
+
+        let table_name = Self::table_name_for(&root);
+        let manifest_path = db_path.join(format!("{}.manifest.json", table_name));
+
+        Ok(Self {
+            root,
+            connection,
+            table_name,
+            manifest_path,
+            embedding_provider,
+            update_lock: Mutex::new(()),
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Each project gets its own table, named after a hash of its root directory
+    pub fn table_name_for(root: &Path) -> String {
+        let hash = blake3::hash(root.to_string_lossy().as_bytes()).to_hex();
+        format!("project_{}", &hash[..16])
+    }
+
+    /// Bring the index up to date with the files on disk
+    pub async fn update(&self) -> Result<IndexUpdate> {
+        let _guard = self.update_lock.lock().await;
+
+        let mut manifest = self.load_manifest();
+        let root = self.root.clone();
+        let files = tokio::task::spawn_blocking(move || list_project_files(&root))
+            .await
+            .context("Failed to list project files")?;
+
+        let mut update = IndexUpdate::default();
+        let mut changed: Vec<(String, String)> = Vec::new();
+        let mut seen = HashMap::new();
+        for (relative, path) in files {
+            let Ok(metadata) = std::fs::metadata(&path) else {
+                continue;
+            };
+            if metadata.len() > MAX_FILE_BYTES {
+                continue;
+            }
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default();
+
+            match manifest.files.get(&relative) {
+                Some(entry) if entry.size == metadata.len() && entry.modified == modified => {
+                    seen.insert(relative, entry.clone());
+                    continue;
+                }
+                _ => {}
+            }
+
+            // Skip binary and non-UTF-8 files
+            let Ok(bytes) = std::fs::read(&path) else {
+                continue;
+            };
+            if bytes.contains(&0) {
+                continue;
+            }
+            let Ok(content) = String::from_utf8(bytes) else {
+                continue;
+            };
+
+            let entry = FileEntry {
+                size: metadata.len(),
+                modified,
+                hash: blake3::hash(content.as_bytes()).to_hex().to_string(),
+            };
+            let unchanged = manifest
+                .files
+                .get(&relative)
+                .is_some_and(|previous| previous.hash == entry.hash);
+            if !unchanged {
+                changed.push((relative.clone(), content));
+            }
+            seen.insert(relative, entry);
+        }
+
+        let removed: Vec<String> = manifest
+            .files
+            .keys()
+            .filter(|path| !seen.contains_key(*path))
+            .cloned()
+            .collect();
+
+        for path in removed.iter().chain(changed.iter().map(|(path, _)| path)) {
+            self.delete_file_chunks(path).await?;
+        }
+        update.files_removed = removed.len();
+
+        let chunks: Vec<CodeChunk> = changed
+            .iter()
+            .flat_map(|(path, content)| chunk_file(path, content))
+            .collect();
+        for batch in chunks.chunks(EMBEDDING_BATCH_SIZE) {
+            self.add_chunks(batch).await?;
+        }
+        update.files_indexed = changed.len();
+        update.chunks_added = chunks.len();
+
+        manifest.files = seen;
+        self.save_manifest(&manifest)?;
+
+        if update != IndexUpdate::default() {
+            tracing::info!(
+                "Updated project index for {}: {} files indexed, {} removed, {} chunks added",
+                self.root.display(),
+                update.files_indexed,
+                update.files_removed,
+                update.chunks_added
+            );
+        }
+        Ok(update)
+    }
+
+    /// Find the chunks most relevant to `query`, updating the index first
+    pub async fn search(&self, query: &str, k: usize) -> Result<Vec<CodeChunk>> {
+        self.update().await?;
+
+        let Some(table) = self.open_table().await? else {
+            return Ok(Vec::new());
+        };
+        let query_vector = self
+            .embed(vec![query.to_string()])
+            .await?
+            .into_iter()
+            .next()
+            .context("No embedding returned for the query")?;
+
+        let batches: Vec<RecordBatch> = table
+            .vector_search(query_vector)
+            .context("Failed to create vector search")?
+            .limit(k)
+            .execute()
+            .await
+            .context("Failed to execute vector search")?
+            .try_collect()
+            .await?;
+
+        let mut chunks = Vec::new();
+        for batch in batches {
+            let paths = string_column(&batch, "path")?;
+            let contents = string_column(&batch, "content")?;
+            let start_lines = u32_column(&batch, "start_line")?;
+            let end_lines = u32_column(&batch, "end_line")?;
+            for i in 0..batch.num_rows() {
+                chunks.push(CodeChunk {
+                    path: paths.value(i).to_string(),
+                    start_line: start_lines.value(i) as usize,
+                    end_line: end_lines.value(i) as usize,
+                    content: contents.value(i).to_string(),
+                });
+            }
+        }
+        Ok(chunks)
+    }
+
+    async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        self.embedding_provider
+            .create_embeddings(texts)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to generate embeddings: {}", e))
+    }
+
+    async fn open_table(&self) -> Result<Option<lancedb::Table>> {
+        let table_names = self
+            .connection
+            .table_names()
+            .execute()
+            .await
+            .context("Failed to list tables")?;
+        if !table_names.contains(&self.table_name) {
+            return Ok(None);
+        }
+        let table = self
+            .connection
+            .open_table(&self.table_name)
+            .execute()
+            .await
+            .context("Failed to open project index table")?;
+        Ok(Some(table))
+    }
+
+    async fn delete_file_chunks(&self, path: &str) -> Result<()> {
+        if let Some(table) = self.open_table().await? {
+            table
+                .delete(&format!("path = '{}'", path.replace('\'', "''")))
+                .await
+                .context("Failed to delete stale chunks")?;
+        }
+        Ok(())
+    }
+
+    async fn add_chunks(&self, chunks: &[CodeChunk]) -> Result<()> {
+        if chunks.is_empty() {
+            return Ok(());
+        }
+
+        // Embed the path along with the content so file names count towards relevance
+        let vectors = self
+            .embed(
+                chunks
+                    .iter()
+                    .map(|chunk| format!("{}\n{}", chunk.path, chunk.content))
+                    .collect(),
+            )
+            .await?;
+        let dimension = vectors
+            .first()
+            .map(Vec::len)
+            .context("No embeddings returned")?;
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("path", DataType::Utf8, false),
+            Field::new("start_line", DataType::UInt32, false),
+            Field::new("end_line", DataType::UInt32, false),
+            Field::new("content", DataType::Utf8, false),
+            Field::new(
+                "vector",
+                DataType::FixedSizeList(
+                    Arc::new(Field::new("item", DataType::Float32, true)),
+                    dimension as i32,
+                ),
+                false,
+            ),
+        ]));
+
+        let mut vectors_builder =
+            FixedSizeListBuilder::new(Float32Builder::new(), dimension as i32);
+        for vector in &vectors {
+            if vector.len() != dimension {
+                return Err(anyhow::anyhow!(
+                    "Embedding provider returned vectors of different sizes"
+                ));
+            }
+            vectors_builder.values().append_slice(vector);
+            vectors_builder.append(true);
+        }
+
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from_iter_values(
+                    chunks.iter().map(|chunk| chunk.path.as_str()),
+                )),
+                Arc::new(UInt32Array::from_iter_values(
+                    chunks.iter().map(|chunk| chunk.start_line as u32),
+                )),
+                Arc::new(UInt32Array::from_iter_values(
+                    chunks.iter().map(|chunk| chunk.end_line as u32),
+                )),
+                Arc::new(StringArray::from_iter_values(
+                    chunks.iter().map(|chunk| chunk.content.as_str()),
+                )),
+                Arc::new(vectors_builder.finish()),
+            ],
+        )
+        .context("Failed to create record batch")?;
+        let reader = RecordBatchIterator::new(vec![Ok(batch)].into_iter(), schema);
+
+        match self.open_table().await? {
+            Some(table) => {
+                table
+                    .add(Box::new(reader))
+                    .execute()
+                    .await
+                    .context("Failed to add chunks to project index")?;
+            }
+            None => {
+                self.connection
+                    .create_table(&self.table_name, Box::new(reader))
+                    .execute()
+                    .await
+                    .context("Failed to create project index table")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn load_manifest(&self) -> Manifest {
+        std::fs::read_to_string(&self.manifest_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_manifest(&self, manifest: &Manifest) -> Result<()> {
+        let temp_path = self.manifest_path.with_extension("json.tmp");
+        std::fs::write(&temp_path, serde_json::to_string(manifest)?)?;
+        std::fs::rename(&temp_path, &self.manifest_path)?;
+        Ok(())
+    }
+}
+
+/// Every indexable file under `root` as (relative path, absolute path), honouring the global
+/// and project `.gooseignore` as well as `.gitignore`
+fn list_project_files(root: &Path) -> Vec<(String, PathBuf)> {
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .hidden(true)
+        .git_ignore(true)
+        .require_git(false)
+        .add_custom_ignore_filename(".gooseignore");
+    if let Ok(strategy) = choose_app_strategy(APP_STRATEGY.clone()) {
+        let global_ignore = strategy.in_config_dir(".gooseignore");
+        if global_ignore.is_file() {
+            builder.add_ignore(global_ignore);
+        }
+    }
+
+    builder
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .filter_map(|entry| {
+            let relative = entry.path().strip_prefix(root).ok()?;
+            Some((
+                relative.to_string_lossy().replace('\\', "/"),
+                entry.path().to_path_buf(),
+            ))
+        })
+        .collect()
+}
+
+/// Split a file into overlapping windows of lines
+fn chunk_file(path: &str, content: &str) -> Vec<CodeChunk> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < lines.len() {
+        let end = (start + CHUNK_LINES).min(lines.len());
+        let text = lines[start..end].join("\n");
+        if !text.trim().is_empty() {
+            chunks.push(CodeChunk {
+                path: path.to_string(),
+                start_line: start + 1,
+                end_line: end,
+                content: text,
+            });
+        }
+        if end == lines.len() {
+            break;
+        }
+        start = end - CHUNK_OVERLAP;
+    }
+    chunks
+}
+
+fn string_column<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a StringArray> {
+    batch
+        .column_by_name(name)
+        .with_context(|| format!("Missing {} column", name))?
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .with_context(|| format!("Invalid {} column type", name))
+}
+
+fn u32_column<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a UInt32Array> {
+    batch
+        .column_by_name(name)
+        .with_context(|| format!("Missing {} column", name))?
+        .as_any()
+        .downcast_ref::<UInt32Array>()
+        .with_context(|| format!("Invalid {} column type", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks_overlap_and_cover_the_file() {
+        let content = (1..=130)
+            .map(|i| format!("line {}", i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let chunks = chunk_file("src/lib.rs", &content);
+
+        let ranges: Vec<(usize, usize)> = chunks
+            .iter()
+            .map(|chunk| (chunk.start_line, chunk.end_line))
+            .collect();
+        assert_eq!(ranges, vec![(1, 60), (51, 110), (101, 130)]);
+        assert!(chunks[0].content.starts_with("line 1\n"));
+        assert!(chunks[2].content.ends_with("line 130"));
+        assert!(chunk_file("empty.rs", "").is_empty());
+    }
+
+    #[test]
+    fn test_project_files_respect_gooseignore() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join(".gooseignore"), "secrets/\n*.log\n")?;
+        std::fs::create_dir(dir.path().join("secrets"))?;
+        std::fs::write(dir.path().join("secrets/key.txt"), "hunter2")?;
+        std::fs::write(dir.path().join("debug.log"), "noise")?;
+        std::fs::create_dir(dir.path().join("src"))?;
+        std::fs::write(dir.path().join("src/main.rs"), "fn main() {}")?;
+
+        let files: Vec<String> = list_project_files(dir.path())
+            .into_iter()
+            .map(|(relative, _)| relative)
+            .collect();
+        assert_eq!(files, vec!["src/main.rs".to_string()]);
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+//! Project search tool handler for the Goose agent
+//!
+//! The project index is created lazily for the current working directory on first use and
+//! recreated if the working directory changes.
+
+use std::sync::Arc;
+
+use mcp_core::{ToolError, ToolResult};
+use rmcp::model::Content;
+
+use crate::agents::project_index::ProjectIndex;
+use crate::agents::router_tool_selector::create_embedding_provider;
+
+use super::Agent;
+
+const DEFAULT_SEARCH_RESULTS: usize = 8;
+
+impl Agent {
+    /// Handle project search tool calls
+    pub async fn handle_project_search(
+        &self,
+        arguments: serde_json::Value,
+    ) -> ToolResult<Vec<Content>> {
+        let query = arguments
+            .get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'query' parameter".to_string()))?;
+        let k = arguments
+            .get("k")
+            .and_then(|v| v.as_u64())
+            .map(|k| k as usize)
+            .unwrap_or(DEFAULT_SEARCH_RESULTS);
+
+        let index = self.project_index().await.map_err(|e| {
+            ToolError::ExecutionError(format!("Failed to open project index: {}", e))
+        })?;
+        let chunks = index
+            .search(query, k)
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("Project search failed: {}", e)))?;
+
+        if chunks.is_empty() {
+            return Ok(vec![Content::text(format!(
+                "No matching code found in {}",
+                index.root().display()
+            ))]);
+        }
+
+        Ok(chunks
+            .into_iter()
+            .map(|chunk| {
+                Content::text(format!(
+                    "{}:{}-{}\n```\n{}\n```",
+                    chunk.path, chunk.start_line, chunk.end_line, chunk.content
+                ))
+            })
+            .collect())
+    }
+
+    async fn project_index(&self) -> anyhow::Result<Arc<ProjectIndex>> {
+        let root = std::env::current_dir()?;
+        let mut project_index = self.project_index.lock().await;
+        if let Some(index) = project_index.as_ref() {
+            if index.root() == root {
+                return Ok(index.clone());
+            }
+        }
+
+        let embedding_provider = create_embedding_provider(self.provider().await?)?;
+        let index = Arc::new(ProjectIndex::new(root, embedding_provider).await?);
+        *project_index = Some(index.clone());
+        Ok(index)
+    }
+}
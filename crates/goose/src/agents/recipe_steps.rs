@@ -0,0 +1,126 @@
+//! Runs the `steps` of a recipe with the subagent executor
+//!
+//! Steps whose dependencies are complete run together as a wave in parallel. The run state is
+//! saved after every wave, so after a failure the next run skips the steps that already
+//! completed with the same inputs.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use rmcp::model::ServerNotification;
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use crate::agents::subagent_execution_tool::executor::execute_tasks_in_parallel;
+use crate::agents::subagent_execution_tool::lib::{Task, TaskStatus};
+use crate::agents::subagent_task_config::TaskConfig;
+use crate::recipe::steps::{input_hash, validate_steps, StepRunState};
+use crate::recipe::RecipeStep;
+
+use super::Agent;
+
+impl Agent {
+    /// Run recipe steps in dependency order and return the output of every step.
+    ///
+    /// `state_path` records completed steps; see `StepRunState::path_for`. It is removed once
+    /// all steps succeed.
+    pub async fn run_recipe_steps(
+        &self,
+        steps: &[RecipeStep],
+        state_path: &Path,
+        cancellation_token: Option<CancellationToken>,
+    ) -> Result<HashMap<String, Value>> {
+        validate_steps(steps)?;
+        let provider = self.provider().await.ok();
+        let mut state = StepRunState::load(state_path);
+        let mut outputs: HashMap<String, Value> = HashMap::new();
+
+        let mut pending: Vec<&RecipeStep> = steps.iter().collect();
+        while !pending.is_empty() {
+            let (ready, rest): (Vec<&RecipeStep>, Vec<&RecipeStep>) = pending
+                .into_iter()
+                .partition(|step| step.depends_on.iter().all(|d| outputs.contains_key(d)));
+            pending = rest;
+
+            let mut tasks = Vec::new();
+            let mut hashes = HashMap::new();
+            for step in ready {
+                let (task_type, payload) = step.task_payload(&outputs)?;
+                let hash = input_hash(&task_type, &payload);
+                if let Some(output) = state.completed_output(&step.id, &hash) {
+                    tracing::info!("Skipping step '{}', completed in a previous run", step.id);
+                    outputs.insert(step.id.clone(), output.clone());
+                    continue;
+                }
+                hashes.insert(step.id.clone(), (step, hash));
+                tasks.push(Task {
+                    id: step.id.clone(),
+                    task_type,
+                    payload,
+                });
+            }
+            if tasks.is_empty() {
+                continue;
+            }
+
+            // The executor reports progress through notifications, which nobody listens to here
+            let (notifier, mut notifications) = mpsc::channel::<ServerNotification>(100);
+            let drain = tokio::spawn(async move { while notifications.recv().await.is_some() {} });
+            let response = execute_tasks_in_parallel(
+                tasks,
+                notifier,
+                TaskConfig::new(provider.clone()),
+                cancellation_token.clone(),
+            )
+            .await;
+            drain.abort();
+
+            let mut failures = Vec::new();
+            for result in response.results {
+                let Some((step, hash)) = hashes.remove(&result.task_id) else {
+                    continue;
+                };
+                let output = match result.status {
+                    TaskStatus::Completed => {
+                        step.parse_output(result.data.as_ref().unwrap_or(&Value::Null))
+                    }
+                    _ => Err(anyhow!(
+                        "{}",
+                        result.error.unwrap_or_else(|| "Unknown error".to_string())
+                    )),
+                };
+                match output {
+                    Ok(output) => {
+                        state.record(&step.id, hash, output.clone());
+                        outputs.insert(step.id.clone(), output);
+                    }
+                    Err(e) => failures.push(format!("{}: {}", step.id, e)),
+                }
+            }
+            // Steps the executor didn't return a result for didn't run
+            failures.extend(hashes.keys().map(|id| format!("{}: did not run", id)));
+
+            state.save(state_path)?;
+            if !failures.is_empty() {
+                tracing::error!("Recipe steps failed: {}", failures.join("; "));
+                return Err(anyhow!(
+                    "Recipe steps failed:\n{}\nCompleted steps are saved and will be skipped when the recipe is run again.",
+                    failures.join("\n")
+                ));
+            }
+        }
+
+        if let Err(e) = std::fs::remove_file(state_path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!(
+                    "Failed to remove step state {}: {}",
+                    state_path.display(),
+                    e
+                );
+            }
+        }
+        Ok(outputs)
+    }
+}
@@ -1,5 +1,4 @@
 use std::collections::HashSet;
-use std::fs;
 use std::sync::Arc;
 
 use anyhow::Result;
@@ -8,7 +7,10 @@ use serde_json::{json, Map, Value};
 
 use crate::agents::subagent_execution_tool::lib::{ExecutionMode, Task};
 use crate::agents::subagent_execution_tool::tasks_manager::TasksManager;
-use crate::recipe::{Recipe, RecipeParameter, RecipeParameterRequirement, SubRecipe};
+use crate::recipe::build_recipe::collect_chain_parameters;
+use crate::recipe::compose::load_recipe_chain;
+use crate::recipe::read_recipe_file_content::read_recipe_file;
+use crate::recipe::{RecipeParameter, RecipeParameterRequirement, SubRecipe};
 
 use super::param_utils::prepare_command_params;
 
@@ -108,10 +110,11 @@ pub async fn create_sub_recipe_task(
 fn get_sub_recipe_parameter_definition(
     sub_recipe: &SubRecipe,
 ) -> Result<Option<Vec<RecipeParameter>>> {
-    let content = fs::read_to_string(sub_recipe.path.clone())
+    let recipe_file = read_recipe_file(&sub_recipe.path)
         .map_err(|e| anyhow::anyhow!("Failed to read recipe file {}: {}", sub_recipe.path, e))?;
-    let recipe = Recipe::from_content(&content)?;
-    Ok(recipe.parameters)
+    let chain = load_recipe_chain(recipe_file)?;
+    let (parameters, _) = collect_chain_parameters(&chain)?;
+    Ok(parameters)
 }
 
 fn get_params_with_values(sub_recipe: &SubRecipe) -> HashSet<String> {
@@ -128,5 +128,46 @@ mod tests {
                 }),
             );
         }
+
+        #[test]
+        fn test_includes_params_of_extended_recipe() {
+            let (mut sub_recipe, temp_dir) = prepare_sub_recipe(
+                r#"{
+                "version": "1.0.0",
+                "title": "Test Recipe",
+                "description": "A test recipe",
+                "prompt": "Test prompt",
+                "extends": "base.json",
+                "parameters": [
+                    {
+                        "key": "key2",
+                        "input_type": "number",
+                        "requirement": "optional",
+                        "description": "An optional parameter"
+                    }
+                ]
+            }"#,
+            );
+            std::fs::write(
+                temp_dir.path().join("base.json"),
+                SUB_RECIPE_FILE_CONTENT_WITH_TWO_PARAMS.replace("A test parameter", "Inherited"),
+            )
+            .unwrap();
+            sub_recipe.values = None;
+
+            let result = get_input_schema(&sub_recipe).unwrap();
+
+            verify_task_parameters(
+                result,
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "key2": { "type": "number", "description": "An optional parameter" },
+                        "key1": { "type": "string", "description": "Inherited" }
+                    },
+                    "required": ["key1"]
+                }),
+            );
+        }
     }
 }
@@ -16,6 +16,7 @@ use crate::providers::toolshim::{
     modify_system_prompt_for_tool_json, OllamaInterpreter,
 };
 use crate::session;
+use crate::session::stats::MessageStats;
 use rmcp::model::Tool;
 
 async fn toolshim_postprocess(
@@ -252,6 +253,7 @@ impl Agent {
             role: response.role.clone(),
             created: response.created,
             content: filtered_content,
+            pinned: response.pinned,
         };
 
         // Categorize tool requests
@@ -285,33 +287,43 @@ impl Agent {
                 return Err(anyhow::anyhow!("Failed to get session file path: {}", e));
             }
         };
-        let mut metadata = session::storage::read_metadata(&session_file_path)?;
+        session::storage::update_metadata_with(&session_file_path, |metadata| {
+            metadata.schedule_id = session_config.schedule_id.clone();
 
-        metadata.schedule_id = session_config.schedule_id.clone();
+            metadata.total_tokens = usage.usage.total_tokens;
+            metadata.input_tokens = usage.usage.input_tokens;
+            metadata.output_tokens = usage.usage.output_tokens;
 
-        metadata.total_tokens = usage.usage.total_tokens;
-        metadata.input_tokens = usage.usage.input_tokens;
-        metadata.output_tokens = usage.usage.output_tokens;
+            metadata.message_count = messages_length + 1;
 
-        metadata.message_count = messages_length + 1;
+            let accumulate = |a: Option<i32>, b: Option<i32>| -> Option<i32> {
+                match (a, b) {
+                    (Some(x), Some(y)) => Some(x + y),
+                    _ => a.or(b),
+                }
+            };
+            metadata.accumulated_total_tokens =
+                accumulate(metadata.accumulated_total_tokens, usage.usage.total_tokens);
+            metadata.accumulated_input_tokens =
+                accumulate(metadata.accumulated_input_tokens, usage.usage.input_tokens);
+            metadata.accumulated_output_tokens = accumulate(
+                metadata.accumulated_output_tokens,
+                usage.usage.output_tokens,
+            );
+            Ok(())
+        })
+    }
 
-        let accumulate = |a: Option<i32>, b: Option<i32>| -> Option<i32> {
-            match (a, b) {
-                (Some(x), Some(y)) => Some(x + y),
-                _ => a.or(b),
+    pub(crate) fn record_message_stats(
+        session_config: &crate::agents::types::SessionConfig,
+        stats: &MessageStats,
+    ) -> Result<()> {
+        let session_file_path = match session::storage::get_path(session_config.id.clone()) {
+            Ok(path) => path,
+            Err(e) => {
+                return Err(anyhow::anyhow!("Failed to get session file path: {}", e));
             }
         };
-        metadata.accumulated_total_tokens =
-            accumulate(metadata.accumulated_total_tokens, usage.usage.total_tokens);
-        metadata.accumulated_input_tokens =
-            accumulate(metadata.accumulated_input_tokens, usage.usage.input_tokens);
-        metadata.accumulated_output_tokens = accumulate(
-            metadata.accumulated_output_tokens,
-            usage.usage.output_tokens,
-        );
-
-        session::storage::update_metadata(&session_file_path, &metadata).await?;
-
-        Ok(())
+        session::stats::append_message_stats(&session_file_path, stats)
     }
 }
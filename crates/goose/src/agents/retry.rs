@@ -1,4 +1,7 @@
 use anyhow::Result;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use rmcp::model::Role;
 use std::process::Stdio;
 use std::sync::Arc;
 use std::time::Duration;
@@ -13,6 +16,8 @@ use crate::agents::types::{
 use crate::config::Config;
 use crate::conversation::message::Message;
 use crate::conversation::Conversation;
+use crate::providers::base::Provider;
+use crate::recipe::steps::extract_json;
 use crate::tool_monitor::ToolMonitor;
 
 /// Result of a retry logic evaluation
@@ -34,6 +39,48 @@ const GOOSE_RECIPE_RETRY_TIMEOUT_SECONDS: &str = "GOOSE_RECIPE_RETRY_TIMEOUT_SEC
 /// Environment variable for configuring on_failure timeout globally
 const GOOSE_RECIPE_ON_FAILURE_TIMEOUT_SECONDS: &str = "GOOSE_RECIPE_ON_FAILURE_TIMEOUT_SECONDS";
 
+/// Maximum characters of command output or transcript included in failure details
+const MAX_DETAIL_CHARS: usize = 2_000;
+const MAX_TRANSCRIPT_CHARS: usize = 20_000;
+
+const LLM_JUDGE_PROMPT: &str = "You judge whether an AI agent completed its task. \
+Read the transcript and decide whether the result meets the rubric below. \
+Reply with PASS or FAIL on the first line, followed by a short explanation.\n\nRubric:\n";
+
+/// What success checks can inspect besides the file system and network
+#[derive(Default, Clone)]
+pub struct CheckContext {
+    /// Output collected by the final output tool, or the last assistant message
+    pub final_output: Option<String>,
+    /// The conversation of the attempt being checked
+    pub messages: Vec<Message>,
+    /// Provider used by the LLM judge
+    pub provider: Option<Arc<dyn Provider>>,
+}
+
+impl CheckContext {
+    /// Build a context from the conversation, using the last assistant text as the final output
+    /// when the final output tool didn't collect one
+    pub fn new(
+        messages: Vec<Message>,
+        final_output: Option<String>,
+        provider: Option<Arc<dyn Provider>>,
+    ) -> Self {
+        let final_output = final_output.or_else(|| {
+            messages
+                .iter()
+                .rev()
+                .find(|message| message.role == Role::Assistant)
+                .map(|message| message.as_concat_text())
+        });
+        Self {
+            final_output,
+            messages,
+            provider,
+        }
+    }
+}
+
 /// Manages retry state and operations for agent execution
 #[derive(Debug)]
 pub struct RetryManager {
@@ -91,13 +138,24 @@ impl RetryManager {
         *self.attempts.lock().await
     }
 
-    /// Reset status for retry: clear message history and final output tool state
+    /// Reset status for retry: clear message history and final output tool state, and tell the
+    /// model which checks failed so the next attempt can address them
     async fn reset_status_for_retry(
         messages: &mut Conversation,
         initial_messages: &[Message],
         final_output_tool: &Arc<Mutex<Option<crate::agents::final_output_tool::FinalOutputTool>>>,
+        failures: &[String],
     ) {
-        *messages = Conversation::new_unvalidated(initial_messages.to_vec());
+        let mut reset_messages = initial_messages.to_vec();
+        let feedback = failure_feedback(failures);
+        // Fold the feedback into the last user message to keep roles alternating
+        match reset_messages.last_mut() {
+            Some(last) if last.role == Role::User => {
+                *last = last.clone().with_text(feedback);
+            }
+            _ => reset_messages.push(Message::user().with_text(feedback)),
+        }
+        *messages = Conversation::new_unvalidated(reset_messages);
         info!("Reset message history to initial state for retry");
 
         if let Some(final_output_tool) = final_output_tool.lock().await.as_mut() {
@@ -113,6 +171,7 @@ impl RetryManager {
         session: &Option<SessionConfig>,
         initial_messages: &[Message],
         final_output_tool: &Arc<Mutex<Option<crate::agents::final_output_tool::FinalOutputTool>>>,
+        check_context: &CheckContext,
     ) -> Result<RetryResult> {
         let Some(session_config) = session else {
             return Ok(RetryResult::Skipped);
@@ -122,9 +181,10 @@ impl RetryManager {
             return Ok(RetryResult::Skipped);
         };
 
-        let success = execute_success_checks(&retry_config.checks, retry_config).await?;
+        let failures =
+            evaluate_success_checks(&retry_config.checks, retry_config, check_context).await?;
 
-        if success {
+        if failures.is_empty() {
             info!("All success checks passed, no retry needed");
             return Ok(RetryResult::SuccessChecksPassed);
         }
@@ -148,7 +208,8 @@ impl RetryManager {
             execute_on_failure_command(on_failure_cmd, retry_config).await?;
         }
 
-        Self::reset_status_for_retry(messages, initial_messages, final_output_tool).await;
+        Self::reset_status_for_retry(messages, initial_messages, final_output_tool, &failures)
+            .await;
 
         let new_attempts = self.increment_attempts().await;
         info!("Incrementing retry attempts to {}", new_attempts);
@@ -192,29 +253,231 @@ pub async fn execute_success_checks(
     checks: &[SuccessCheck],
     retry_config: &RetryConfig,
 ) -> Result<bool> {
+    let failures = evaluate_success_checks(checks, retry_config, &CheckContext::default()).await?;
+    Ok(failures.is_empty())
+}
+
+/// Execute all success checks and return a description of each one that failed
+pub async fn evaluate_success_checks(
+    checks: &[SuccessCheck],
+    retry_config: &RetryConfig,
+    context: &CheckContext,
+) -> Result<Vec<String>> {
     let timeout = get_retry_timeout(retry_config);
 
+    let mut failures = Vec::new();
     for check in checks {
+        match evaluate_check(check, timeout, context).await? {
+            Some(failure) => {
+                warn!("Success check failed: {}", failure);
+                failures.push(failure);
+            }
+            None => info!("Success check passed: {:?}", check),
+        }
+    }
+    Ok(failures)
+}
+
+/// Evaluate a single check, returning why it failed or `None` if it passed
+fn evaluate_check<'a>(
+    check: &'a SuccessCheck,
+    timeout: Duration,
+    context: &'a CheckContext,
+) -> BoxFuture<'a, Result<Option<String>>> {
+    async move {
         match check {
             SuccessCheck::Shell { command } => {
                 let result = execute_shell_command(command, timeout).await?;
-                if !result.status.success() {
-                    warn!(
-                        "Success check failed: command '{}' exited with status {}, stderr: {}",
-                        command,
-                        result.status,
-                        String::from_utf8_lossy(&result.stderr)
-                    );
-                    return Ok(false);
+                if result.status.success() {
+                    return Ok(None);
                 }
-                info!(
-                    "Success check passed: command '{}' completed successfully",
-                    command
-                );
+                let mut failure = format!("command `{}` exited with {}", command, result.status);
+                for (name, output) in [("stdout", &result.stdout), ("stderr", &result.stderr)] {
+                    let output = String::from_utf8_lossy(output);
+                    if !output.trim().is_empty() {
+                        failure.push_str(&format!("\n{}:\n{}", name, truncate_tail(&output)));
+                    }
+                }
+                Ok(Some(failure))
+            }
+            SuccessCheck::FileExists { path } => {
+                if matching_files(path)?.is_empty() {
+                    Ok(Some(format!("no file matches `{}`", path)))
+                } else {
+                    Ok(None)
+                }
+            }
+            SuccessCheck::FileMatches { path, pattern } => {
+                let regex = regex::Regex::new(pattern)
+                    .map_err(|e| anyhow::anyhow!("Invalid pattern '{}': {}", pattern, e))?;
+                let files = matching_files(path)?;
+                if files.is_empty() {
+                    return Ok(Some(format!("no file matches `{}`", path)));
+                }
+                let matched = files.iter().any(|file| {
+                    std::fs::read_to_string(file)
+                        .map(|content| regex.is_match(&content))
+                        .unwrap_or(false)
+                });
+                if matched {
+                    Ok(None)
+                } else {
+                    Ok(Some(format!(
+                        "the content of `{}` does not match the pattern `{}`",
+                        path, pattern
+                    )))
+                }
+            }
+            SuccessCheck::JsonOutput { schema } => check_json_output(schema, context),
+            SuccessCheck::Http { url, status } => {
+                let expected = status.unwrap_or(200);
+                let client = reqwest::Client::builder().timeout(timeout).build()?;
+                match client.get(url).send().await {
+                    Ok(response) if response.status().as_u16() == expected => Ok(None),
+                    Ok(response) => Ok(Some(format!(
+                        "GET {} returned status {}, expected {}",
+                        url,
+                        response.status().as_u16(),
+                        expected
+                    ))),
+                    Err(e) => Ok(Some(format!("GET {} failed: {}", url, e))),
+                }
+            }
+            SuccessCheck::LlmJudge { rubric } => check_with_llm_judge(rubric, context).await,
+            SuccessCheck::All { checks } => {
+                let mut failures = Vec::new();
+                for check in checks {
+                    if let Some(failure) = evaluate_check(check, timeout, context).await? {
+                        failures.push(failure);
+                    }
+                }
+                if failures.is_empty() {
+                    Ok(None)
+                } else {
+                    Ok(Some(failures.join("\n")))
+                }
+            }
+            SuccessCheck::Any { checks } => {
+                let mut failures = Vec::new();
+                for check in checks {
+                    match evaluate_check(check, timeout, context).await? {
+                        None => return Ok(None),
+                        Some(failure) => failures.push(format!("- {}", failure)),
+                    }
+                }
+                Ok(Some(format!(
+                    "none of these alternatives passed:\n{}",
+                    failures.join("\n")
+                )))
             }
         }
     }
-    Ok(true)
+    .boxed()
+}
+
+fn matching_files(pattern: &str) -> Result<Vec<std::path::PathBuf>> {
+    let paths = glob::glob(pattern)
+        .map_err(|e| anyhow::anyhow!("Invalid path pattern '{}': {}", pattern, e))?;
+    Ok(paths.filter_map(|path| path.ok()).collect())
+}
+
+fn check_json_output(schema: &serde_json::Value, context: &CheckContext) -> Result<Option<String>> {
+    let Some(output) = context.final_output.as_deref() else {
+        return Ok(Some("there is no final output to validate".to_string()));
+    };
+    let Some(value) = extract_json(output) else {
+        return Ok(Some("the final output is not valid JSON".to_string()));
+    };
+    let validator = jsonschema::validator_for(schema)
+        .map_err(|e| anyhow::anyhow!("Invalid JSON schema in success check: {}", e))?;
+    let errors: Vec<String> = validator
+        .iter_errors(&value)
+        .map(|error| format!("{}: {}", error.instance_path, error))
+        .collect();
+    if errors.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(format!(
+            "the final output does not match the schema: {}",
+            errors.join("; ")
+        )))
+    }
+}
+
+async fn check_with_llm_judge(rubric: &str, context: &CheckContext) -> Result<Option<String>> {
+    let Some(provider) = &context.provider else {
+        return Ok(Some(
+            "no provider is available to judge the result".to_string(),
+        ));
+    };
+
+    let transcript = context
+        .messages
+        .iter()
+        .map(|message| {
+            let role = match message.role {
+                Role::User => "User",
+                Role::Assistant => "Assistant",
+            };
+            format!("{}: {}", role, message.as_concat_text())
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let mut request = format!(
+        "Transcript:\n{}",
+        truncate_tail_to(&transcript, MAX_TRANSCRIPT_CHARS)
+    );
+    if let Some(output) = &context.final_output {
+        request.push_str(&format!("\n\nFinal output:\n{}", output));
+    }
+
+    let system = format!("{}{}", LLM_JUDGE_PROMPT, rubric);
+    let (response, _) = provider
+        .complete(&system, &[Message::user().with_text(request)], &[])
+        .await
+        .map_err(|e| anyhow::anyhow!("LLM judge request failed: {}", e))?;
+    let verdict = response.as_concat_text();
+    let passed = verdict
+        .trim()
+        .lines()
+        .next()
+        .is_some_and(|line| line.to_uppercase().contains("PASS"));
+    if passed {
+        Ok(None)
+    } else {
+        Ok(Some(format!(
+            "the result does not meet the rubric: {}",
+            verdict.trim()
+        )))
+    }
+}
+
+/// Keep the end of long output, which is where errors usually are
+fn truncate_tail(text: &str) -> String {
+    truncate_tail_to(text, MAX_DETAIL_CHARS)
+}
+
+fn truncate_tail_to(text: &str, max_chars: usize) -> String {
+    let text = text.trim();
+    let count = text.chars().count();
+    if count <= max_chars {
+        return text.to_string();
+    }
+    let tail: String = text.chars().skip(count - max_chars).collect();
+    format!("…{}", tail)
+}
+
+/// Message telling the model why the previous attempt was rejected
+fn failure_feedback(failures: &[String]) -> String {
+    let details = failures
+        .iter()
+        .map(|failure| format!("- {}", failure))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "A previous attempt at this task did not pass these success checks:\n{}\n\nMake sure they pass this time.",
+        details
+    )
 }
 
 /// Execute a shell command with cross-platform compatibility and mandatory timeout
@@ -378,6 +641,146 @@ mod tests {
         assert!(!result.unwrap());
     }
 
+    #[tokio::test]
+    async fn test_file_checks() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("report.md");
+        std::fs::write(&file, "# Report\nAll tests passed\n").unwrap();
+        let glob = format!("{}/*.md", temp_dir.path().display());
+        let retry_config = create_test_retry_config();
+        let context = CheckContext::default();
+
+        let checks = vec![
+            SuccessCheck::FileExists { path: glob.clone() },
+            SuccessCheck::FileMatches {
+                path: glob.clone(),
+                pattern: r"(?m)^All tests passed$".to_string(),
+            },
+        ];
+        let failures = evaluate_success_checks(&checks, &retry_config, &context)
+            .await
+            .unwrap();
+        assert!(failures.is_empty());
+
+        let checks = vec![
+            SuccessCheck::FileExists {
+                path: format!("{}/*.txt", temp_dir.path().display()),
+            },
+            SuccessCheck::FileMatches {
+                path: glob,
+                pattern: "failed".to_string(),
+            },
+        ];
+        let failures = evaluate_success_checks(&checks, &retry_config, &context)
+            .await
+            .unwrap();
+        assert_eq!(failures.len(), 2);
+        assert!(failures[0].contains("no file matches"));
+        assert!(failures[1].contains("does not match the pattern"));
+    }
+
+    #[tokio::test]
+    async fn test_json_output_check() {
+        let retry_config = create_test_retry_config();
+        let checks = vec![SuccessCheck::JsonOutput {
+            schema: serde_json::json!({
+                "type": "object",
+                "properties": {"count": {"type": "integer"}},
+                "required": ["count"]
+            }),
+        }];
+
+        let context = CheckContext::new(
+            vec![Message::assistant().with_text("Done:\n```json\n{\"count\": 3}\n```")],
+            None,
+            None,
+        );
+        let failures = evaluate_success_checks(&checks, &retry_config, &context)
+            .await
+            .unwrap();
+        assert!(failures.is_empty());
+
+        let context = CheckContext::new(vec![], Some(r#"{"count": "three"}"#.to_string()), None);
+        let failures = evaluate_success_checks(&checks, &retry_config, &context)
+            .await
+            .unwrap();
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].contains("does not match the schema"));
+    }
+
+    #[tokio::test]
+    async fn test_composite_checks() {
+        let retry_config = create_test_retry_config();
+        let context = CheckContext::default();
+        let shell = |command: &str| SuccessCheck::Shell {
+            command: command.to_string(),
+        };
+
+        let any = vec![SuccessCheck::Any {
+            checks: vec![shell("false"), shell("true")],
+        }];
+        let failures = evaluate_success_checks(&any, &retry_config, &context)
+            .await
+            .unwrap();
+        assert!(failures.is_empty());
+
+        let all = vec![SuccessCheck::All {
+            checks: vec![shell("true"), shell("echo 'broken build' >&2; false")],
+        }];
+        let failures = evaluate_success_checks(&all, &retry_config, &context)
+            .await
+            .unwrap();
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].contains("broken build"));
+    }
+
+    #[test]
+    fn test_success_check_deserialization() {
+        let checks: Vec<SuccessCheck> = serde_yaml::from_str(
+            r#"
+- type: file_exists
+  path: "dist/*.js"
+- type: http
+  url: "http://localhost:8080/health"
+- type: any
+  checks:
+    - type: shell
+      command: "cargo test"
+    - type: llm_judge
+      rubric: "The README explains how to run the tests"
+"#,
+        )
+        .unwrap();
+        assert!(matches!(checks[0], SuccessCheck::FileExists { .. }));
+        assert!(matches!(checks[1], SuccessCheck::Http { status: None, .. }));
+        match &checks[2] {
+            SuccessCheck::Any { checks } => {
+                assert!(matches!(checks[1], SuccessCheck::LlmJudge { .. }))
+            }
+            other => panic!("unexpected check {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reset_for_retry_feeds_back_failures() {
+        let final_output_tool = Arc::new(Mutex::new(None));
+        let initial_messages = vec![Message::user().with_text("Build the site")];
+        let mut messages = Conversation::new_unvalidated(vec![]);
+
+        RetryManager::reset_status_for_retry(
+            &mut messages,
+            &initial_messages,
+            &final_output_tool,
+            &["no file matches `dist/*.html`".to_string()],
+        )
+        .await;
+
+        assert_eq!(messages.len(), 1);
+        let text = messages.messages()[0].as_concat_text();
+        assert!(text.starts_with("Build the site"));
+        assert!(text.contains("no file matches `dist/*.html`"));
+    }
+
     #[tokio::test]
     async fn test_execute_shell_command_success() {
         let result = execute_shell_command("echo 'hello world'", Duration::from_secs(30)).await;
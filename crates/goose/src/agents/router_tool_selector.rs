@@ -8,11 +8,11 @@ use serde::Serialize;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::collections::VecDeque;
-use std::env;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use crate::agents::tool_vectordb::ToolVectorDB;
+use crate::config::Config;
 use crate::conversation::message::Message;
 use crate::model::ModelConfig;
 use crate::prompt_template::render_global_file;
@@ -49,26 +49,7 @@ pub struct VectorToolSelector {
 impl VectorToolSelector {
     pub async fn new(provider: Arc<dyn Provider>, table_name: String) -> Result<Self> {
         let vector_db = ToolVectorDB::new(Some(table_name)).await?;
-
-        let embedding_provider = if env::var("GOOSE_EMBEDDING_MODEL_PROVIDER").is_ok() {
-            // If env var is set, create a new provider for embeddings
-            // Get embedding model and provider from environment variables
-            let embedding_model = env::var("GOOSE_EMBEDDING_MODEL")
-                .unwrap_or_else(|_| "text-embedding-3-small".to_string());
-            let embedding_provider_name =
-                env::var("GOOSE_EMBEDDING_MODEL_PROVIDER").unwrap_or_else(|_| "openai".to_string());
-
-            // Create the provider using the factory
-            let model_config = ModelConfig::new(embedding_model.as_str())
-                .context("Failed to create model config for embedding provider")?;
-            providers::create(&embedding_provider_name, model_config).context(format!(
-                "Failed to create {} provider for embeddings. If using OpenAI, make sure OPENAI_API_KEY env var is set or that you have configured the OpenAI provider via Goose before.",
-                embedding_provider_name
-            ))?
-        } else {
-            // Otherwise fall back to using the same provider instance as used for base goose model
-            provider.clone()
-        };
+        let embedding_provider = create_embedding_provider(provider)?;
 
         Ok(Self {
             vector_db: Arc::new(RwLock::new(vector_db)),
@@ -78,6 +59,27 @@ impl VectorToolSelector {
     }
 }
 
+/// The provider used for embeddings: GOOSE_EMBEDDING_MODEL_PROVIDER and GOOSE_EMBEDDING_MODEL
+/// when set, otherwise the same provider instance as used for the base goose model
+pub(crate) fn create_embedding_provider(provider: Arc<dyn Provider>) -> Result<Arc<dyn Provider>> {
+    let config = Config::global();
+    let Ok(embedding_provider_name) = config.get_param::<String>("GOOSE_EMBEDDING_MODEL_PROVIDER")
+    else {
+        return Ok(provider);
+    };
+    let embedding_model = config
+        .get_param::<String>("GOOSE_EMBEDDING_MODEL")
+        .unwrap_or_else(|_| "text-embedding-3-small".to_string());
+
+    // Create the provider using the factory
+    let model_config = ModelConfig::new(embedding_model.as_str())
+        .context("Failed to create model config for embedding provider")?;
+    providers::create(&embedding_provider_name, model_config).context(format!(
+        "Failed to create {} provider for embeddings. If using OpenAI, make sure OPENAI_API_KEY env var is set or that you have configured the OpenAI provider via Goose before.",
+        embedding_provider_name
+    ))
+}
+
 #[async_trait]
 impl RouterToolSelector for VectorToolSelector {
     async fn select_tools(&self, params: Value) -> Result<Vec<Content>, ToolError> {
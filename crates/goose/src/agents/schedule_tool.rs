@@ -3,6 +3,7 @@
 //! This module contains all the handlers for the schedule management platform tool,
 //! including job creation, execution, monitoring, and session management.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use chrono::Utc;
@@ -151,6 +152,17 @@ impl Agent {
             current_session_id: None,
             process_start_time: None,
             execution_mode: Some(execution_mode.to_string()),
+            parameters: HashMap::new(),
+            provider: None,
+            model: None,
+            working_dir: None,
+            extensions: Vec::new(),
+            triggers: Vec::new(),
+            overlap: Default::default(),
+            catch_up: Default::default(),
+            catch_up_window_seconds: None,
+            max_runtime_seconds: None,
+            retry: None,
         };
 
         match scheduler.add_scheduled_job(job).await {
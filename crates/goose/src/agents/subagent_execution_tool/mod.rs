@@ -1,4 +1,4 @@
-mod executor;
+pub(crate) mod executor;
 pub mod lib;
 pub mod notification_events;
 pub mod subagent_execute_task_tool;
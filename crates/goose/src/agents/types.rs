@@ -69,6 +69,48 @@ pub enum SuccessCheck {
         /// The shell command to execute
         command: String,
     },
+    /// Check that at least one file matches a path or glob pattern
+    #[serde(alias = "file_exists")]
+    FileExists {
+        /// Path or glob pattern, relative to the working directory
+        path: String,
+    },
+    /// Check that the content of a file matches a regular expression
+    #[serde(alias = "file_matches")]
+    FileMatches {
+        /// Path or glob pattern; the check passes if any matching file matches
+        path: String,
+        /// Regular expression to search for in the file content
+        pattern: String,
+    },
+    /// Check that the final output is JSON that validates against a JSON schema
+    #[serde(alias = "json_output")]
+    JsonOutput {
+        /// The JSON schema the output must validate against
+        #[schema(value_type = Object)]
+        schema: serde_json::Value,
+    },
+    /// Check that an HTTP GET request returns the expected status, e.g. for a local service
+    #[serde(alias = "http")]
+    Http {
+        /// The URL to request
+        url: String,
+        /// Expected status code (default: 200)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        status: Option<u16>,
+    },
+    /// Ask the model to judge the conversation against a rubric
+    #[serde(alias = "llm_judge")]
+    LlmJudge {
+        /// What a successful result looks like
+        rubric: String,
+    },
+    /// Pass if all nested checks pass
+    #[serde(alias = "all")]
+    All { checks: Vec<SuccessCheck> },
+    /// Pass if any nested check passes
+    #[serde(alias = "any")]
+    Any { checks: Vec<SuccessCheck> },
 }
 
 /// A frontend tool that will be executed by the frontend rather than an extension
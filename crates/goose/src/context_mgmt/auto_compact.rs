@@ -5,12 +5,12 @@ use crate::{
     config::Config,
     context_mgmt::{
         common::{SYSTEM_PROMPT_TOKEN_OVERHEAD, TOOLS_TOKEN_OVERHEAD},
-        get_messages_token_counts_async,
+        estimate_target_context_limit, get_messages_token_counts_async,
     },
     token_counter::create_async_token_counter,
 };
 use anyhow::Result;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 /// Result of auto-compaction check
 #[derive(Debug)]
@@ -23,6 +23,8 @@ pub struct AutoCompactResult {
     pub tokens_before: Option<usize>,
     /// Token count after compaction (if compaction occurred)
     pub tokens_after: Option<usize>,
+    /// Name of the compaction strategy that was used (if compaction occurred)
+    pub strategy: Option<String>,
 }
 
 /// Result of checking if compaction is needed
@@ -64,12 +66,7 @@ pub async fn check_compaction_needed(
     session_metadata: Option<&crate::session::storage::SessionMetadata>,
 ) -> Result<CompactionCheckResult> {
     // Get threshold from config or use override
-    let config = Config::global();
-    let threshold = threshold_override.unwrap_or_else(|| {
-        config
-            .get_param::<f64>("GOOSE_AUTO_COMPACT_THRESHOLD")
-            .unwrap_or(0.8) // Default to 80%
-    });
+    let threshold = compaction_threshold(threshold_override);
 
     let provider = agent.provider().await?;
     let context_limit = provider.get_model_config().context_limit();
@@ -167,9 +164,85 @@ pub async fn perform_compaction(
     Ok((compacted_messages, tokens_before, tokens_after))
 }
 
+/// Compact messages with the named strategy, falling back to summarizing if it fails
+///
+/// # Returns
+/// * Tuple of (compacted_messages, tokens_before, tokens_after)
+pub async fn perform_compaction_with_strategy(
+    agent: &Agent,
+    messages: &[Message],
+    strategy_name: &str,
+    context_limit: usize,
+) -> Result<(Conversation, usize, usize)> {
+    let token_counter = create_async_token_counter()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create token counter: {}", e))?;
+    let tokens_before: usize = get_messages_token_counts_async(&token_counter, messages)
+        .iter()
+        .sum();
+
+    info!(
+        "Performing {} compaction on {} tokens",
+        strategy_name, tokens_before
+    );
+
+    let (compacted_messages, compacted_token_counts) = match agent
+        .compact_context(messages, strategy_name, Some(context_limit))
+        .await
+    {
+        Ok(compacted) => compacted,
+        Err(e) if strategy_name != "summarize" => {
+            warn!(
+                "Compaction strategy {} failed, summarizing instead: {}",
+                strategy_name, e
+            );
+            agent
+                .compact_context(messages, "summarize", Some(context_limit))
+                .await?
+        }
+        Err(e) => return Err(e),
+    };
+    let tokens_after: usize = compacted_token_counts.iter().sum();
+
+    info!(
+        "Compaction complete: {} tokens -> {} tokens ({:.1}% reduction)",
+        tokens_before,
+        tokens_after,
+        (1.0 - (tokens_after as f64 / tokens_before as f64)) * 100.0
+    );
+
+    Ok((compacted_messages, tokens_before, tokens_after))
+}
+
+/// Token budget for the messages after auto-compaction: below the threshold once the system
+/// prompt and tools are accounted for, and never more than the provider's target limit
+async fn compaction_target(
+    agent: &Agent,
+    context_limit: usize,
+    threshold_override: Option<f64>,
+) -> Result<usize> {
+    let provider_target = estimate_target_context_limit(agent.provider().await?);
+    let threshold_tokens =
+        (context_limit as f64 * compaction_threshold(threshold_override)) as usize;
+    match threshold_tokens.saturating_sub(SYSTEM_PROMPT_TOKEN_OVERHEAD + TOOLS_TOKEN_OVERHEAD) {
+        0 => Ok(provider_target),
+        target => Ok(target.min(provider_target)),
+    }
+}
+
+fn compaction_threshold(threshold_override: Option<f64>) -> f64 {
+    threshold_override.unwrap_or_else(|| {
+        Config::global()
+            .get_param::<f64>("GOOSE_AUTO_COMPACT_THRESHOLD")
+            .unwrap_or(0.8) // Default to 80%
+    })
+}
+
 /// Check if messages need compaction and compact them if necessary
 ///
-/// This is a convenience wrapper function that combines checking and compaction.
+/// This is a convenience wrapper function that combines checking and compaction. Messages are
+/// compacted with the agent's compaction strategy (see `context_mgmt::strategy`) until they are
+/// back under the threshold, falling back to summarizing if that strategy fails.
 /// If the most recent message is a user message, it will be preserved by removing it
 /// before compaction and adding it back afterwards.
 ///
@@ -203,6 +276,7 @@ pub async fn check_and_compact_messages(
             messages: Conversation::new_unvalidated(messages.to_vec()),
             tokens_before: None,
             tokens_after: None,
+            strategy: None,
         });
     }
 
@@ -224,9 +298,14 @@ pub async fn check_and_compact_messages(
         (messages, None)
     };
 
-    // Perform the compaction on messages excluding the preserved user message
-    let (mut compacted_messages, tokens_before, tokens_after) =
-        perform_compaction(agent, messages_to_compact).await?;
+    let strategy_name = agent.compaction_strategy().await;
+    let (mut compacted_messages, tokens_before, tokens_after) = perform_compaction_with_strategy(
+        agent,
+        messages_to_compact,
+        &strategy_name,
+        compaction_target(agent, check_result.context_limit, threshold_override).await?,
+    )
+    .await?;
 
     // Add back the preserved user message if it exists
     if let Some(user_message) = preserved_user_message {
@@ -238,6 +317,7 @@ pub async fn check_and_compact_messages(
         messages: compacted_messages,
         tokens_before: Some(tokens_before + SYSTEM_PROMPT_TOKEN_OVERHEAD + TOOLS_TOKEN_OVERHEAD),
         tokens_after: Some(tokens_after + SYSTEM_PROMPT_TOKEN_OVERHEAD + TOOLS_TOKEN_OVERHEAD),
+        strategy: Some(strategy_name),
     })
 }
 
@@ -318,6 +398,7 @@ mod tests {
             accumulated_total_tokens: Some(100),
             accumulated_input_tokens: Some(50),
             accumulated_output_tokens: Some(50),
+            artifacts: Vec::new(),
         }
     }
 
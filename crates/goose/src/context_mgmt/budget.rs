@@ -0,0 +1,368 @@
+//! Per-component token breakdown of the next provider request
+//!
+//! When a request hits the context limit it's not obvious whether the system prompt, an
+//! extension's tool schemas, `.goosehints` or the conversation itself is to blame. A
+//! [`ContextBudget`] counts each of these separately with the same counter used for
+//! compaction, and suggests what to disable or compact.
+
+use rmcp::model::{Role, Tool};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+use crate::agents::extension::ExtensionInfo;
+use crate::conversation::message::{Message, MessageContent};
+use crate::token_counter::AsyncTokenCounter;
+
+/// Headings the developer extension puts in front of `.goosehints` in its instructions
+const HINTS_HEADINGS: [&str; 2] = ["### Global Hints", "### Project Hints"];
+const PREVIEW_CHARS: usize = 80;
+
+/// Share of the request above which a single extension's tools are worth disabling
+const EXTENSION_ADVICE_RATIO: f64 = 0.10;
+/// Share of the context limit above which the history should be compacted
+const HISTORY_ADVICE_RATIO: f64 = 0.50;
+/// Share of the context limit above which a single message is called out
+const MESSAGE_ADVICE_RATIO: f64 = 0.10;
+const HINTS_ADVICE_TOKENS: usize = 2_000;
+const MAX_EXTENSIONS: usize = 5;
+const MAX_TOOLS: usize = 50;
+
+/// Token cost of a named part of the request
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenCost {
+    pub name: String,
+    pub tokens: usize,
+}
+
+/// Token cost of the tool schemas one extension contributes
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionToolsCost {
+    pub extension: String,
+    pub tool_count: usize,
+    pub tokens: usize,
+}
+
+/// Token cost of one message in the conversation
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageCost {
+    pub index: usize,
+    pub role: String,
+    pub pinned: bool,
+    pub tokens: usize,
+    /// Start of the message text, or the tool it calls or answers
+    pub preview: String,
+}
+
+/// Breakdown of the token cost of the next request to the provider
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextBudget {
+    /// Tokens of the whole request, as counted by `count_everything`
+    pub total_tokens: usize,
+    pub context_limit: usize,
+    /// The system prompt split into the base prompt and each extension's instructions
+    pub system_prompt: Vec<TokenCost>,
+    /// `.goosehints` included in the system prompt through extension instructions
+    pub hints: Vec<TokenCost>,
+    /// Tool schemas grouped by the extension that provides them, most expensive first
+    pub tools: Vec<ExtensionToolsCost>,
+    pub messages: Vec<MessageCost>,
+    /// Suggestions for reducing the size of the request
+    pub advice: Vec<String>,
+}
+
+impl ContextBudget {
+    pub fn compute(
+        token_counter: &AsyncTokenCounter,
+        system_prompt: &str,
+        extensions: &[ExtensionInfo],
+        tools: &[Tool],
+        messages: &[Message],
+        context_limit: usize,
+    ) -> Self {
+        let total_tokens = token_counter.count_everything(system_prompt, messages, tools, &[]);
+
+        let mut sections = Vec::new();
+        let mut hints = Vec::new();
+        for extension in extensions {
+            let (instructions, extension_hints) = split_hints(&extension.instructions);
+            if !instructions.trim().is_empty() {
+                sections.push(TokenCost {
+                    name: format!("{} instructions", extension.name),
+                    tokens: token_counter.count_tokens(instructions),
+                });
+            }
+            for (heading, text) in extension_hints {
+                hints.push(TokenCost {
+                    name: heading.trim_start_matches('#').trim().to_string(),
+                    tokens: token_counter.count_tokens(text),
+                });
+            }
+        }
+        let extension_tokens: usize = sections
+            .iter()
+            .chain(hints.iter())
+            .map(|cost| cost.tokens)
+            .sum();
+        sections.insert(
+            0,
+            TokenCost {
+                name: "base prompt".to_string(),
+                tokens: token_counter
+                    .count_tokens(system_prompt)
+                    .saturating_sub(extension_tokens),
+            },
+        );
+
+        let mut tools_by_extension: BTreeMap<String, Vec<Tool>> = BTreeMap::new();
+        for tool in tools {
+            let extension = tool
+                .name
+                .split_once("__")
+                .map(|(prefix, _)| prefix.to_string())
+                .unwrap_or_else(|| "other".to_string());
+            tools_by_extension
+                .entry(extension)
+                .or_default()
+                .push(tool.clone());
+        }
+        let mut tool_costs: Vec<ExtensionToolsCost> = tools_by_extension
+            .into_iter()
+            .map(|(extension, tools)| ExtensionToolsCost {
+                extension,
+                tool_count: tools.len(),
+                tokens: token_counter.count_tokens_for_tools(&tools),
+            })
+            .collect();
+        tool_costs.sort_by(|a, b| b.tokens.cmp(&a.tokens));
+
+        let messages = messages
+            .iter()
+            .enumerate()
+            .map(|(index, message)| MessageCost {
+                index,
+                role: match message.role {
+                    Role::User => "user".to_string(),
+                    Role::Assistant => "assistant".to_string(),
+                },
+                pinned: message.pinned,
+                tokens: token_counter.count_chat_tokens("", std::slice::from_ref(message), &[]),
+                preview: preview(message),
+            })
+            .collect();
+
+        let mut budget = Self {
+            total_tokens,
+            context_limit,
+            system_prompt: sections,
+            hints,
+            tools: tool_costs,
+            messages,
+            advice: Vec::new(),
+        };
+        budget.advice = budget.advise();
+        budget
+    }
+
+    pub fn history_tokens(&self) -> usize {
+        self.messages.iter().map(|message| message.tokens).sum()
+    }
+
+    fn advise(&self) -> Vec<String> {
+        let mut advice = Vec::new();
+
+        if self.context_limit > 0 && self.total_tokens > self.context_limit {
+            advice.push(format!(
+                "The next request is {} tokens over the model's context limit of {}.",
+                self.total_tokens - self.context_limit,
+                self.context_limit
+            ));
+        }
+
+        let extension_count = self
+            .tools
+            .iter()
+            .filter(|cost| cost.extension != "platform")
+            .count();
+        let tool_count: usize = self.tools.iter().map(|cost| cost.tool_count).sum();
+        if extension_count > MAX_EXTENSIONS || tool_count > MAX_TOOLS {
+            advice.push(format!(
+                "{} extensions provide {} tools; more than {} extensions or {} tools makes it harder for the model to pick the right one.",
+                extension_count, tool_count, MAX_EXTENSIONS, MAX_TOOLS
+            ));
+        }
+
+        for cost in &self.tools {
+            if cost.extension == "platform" || self.total_tokens == 0 {
+                continue;
+            }
+            let share = cost.tokens as f64 / self.total_tokens as f64;
+            if share >= EXTENSION_ADVICE_RATIO {
+                advice.push(format!(
+                    "Disabling the {} extension would save about {} tokens ({:.0}% of the request) of tool definitions.",
+                    cost.extension,
+                    cost.tokens,
+                    share * 100.0
+                ));
+            }
+        }
+
+        let hints_tokens: usize = self.hints.iter().map(|cost| cost.tokens).sum();
+        if hints_tokens > HINTS_ADVICE_TOKENS {
+            advice.push(format!(
+                ".goosehints add {} tokens to every request; consider trimming them.",
+                hints_tokens
+            ));
+        }
+
+        if self.context_limit > 0 {
+            let history_tokens = self.history_tokens();
+            let history_share = history_tokens as f64 / self.context_limit as f64;
+            if history_share >= HISTORY_ADVICE_RATIO {
+                advice.push(format!(
+                    "The conversation uses {} tokens ({:.0}% of the context limit); compacting it would free the most space.",
+                    history_tokens,
+                    history_share * 100.0
+                ));
+            }
+
+            let largest = self.messages.iter().max_by_key(|message| message.tokens);
+            if let Some(message) = largest {
+                let share = message.tokens as f64 / self.context_limit as f64;
+                if share >= MESSAGE_ADVICE_RATIO {
+                    advice.push(format!(
+                        "Message {} ({}) alone uses {} tokens ({:.0}% of the context limit).",
+                        message.index,
+                        message.preview,
+                        message.tokens,
+                        share * 100.0
+                    ));
+                }
+            }
+        }
+
+        advice
+    }
+}
+
+/// Split extension instructions into the instructions themselves and any hints sections
+fn split_hints(instructions: &str) -> (&str, Vec<(&str, &str)>) {
+    let mut starts: Vec<(usize, &str)> = HINTS_HEADINGS
+        .iter()
+        .filter_map(|heading| instructions.find(heading).map(|start| (start, *heading)))
+        .collect();
+    starts.sort();
+
+    let Some((first, _)) = starts.first() else {
+        return (instructions, Vec::new());
+    };
+    let hints = starts
+        .iter()
+        .enumerate()
+        .map(|(i, (start, heading))| {
+            let end = starts
+                .get(i + 1)
+                .map(|(next, _)| *next)
+                .unwrap_or(instructions.len());
+            (*heading, &instructions[*start..end])
+        })
+        .collect();
+    (&instructions[..*first], hints)
+}
+
+fn preview(message: &Message) -> String {
+    let text = message
+        .content
+        .iter()
+        .find_map(|content| match content {
+            MessageContent::Text(text) if !text.text.trim().is_empty() => {
+                Some(text.text.trim().to_string())
+            }
+            MessageContent::ToolRequest(request) => Some(match &request.tool_call {
+                Ok(call) => format!("calls {}", call.name),
+                Err(_) => "invalid tool call".to_string(),
+            }),
+            MessageContent::ToolResponse(_) => Some("tool result".to_string()),
+            MessageContent::Image(_) => Some("image".to_string()),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    let single_line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if single_line.chars().count() > PREVIEW_CHARS {
+        let truncated: String = single_line.chars().take(PREVIEW_CHARS).collect();
+        format!("{}…", truncated)
+    } else {
+        single_line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::object;
+
+    fn tool(name: &str) -> Tool {
+        Tool::new(
+            name.to_string(),
+            "A tool that does something useful with a long enough description".to_string(),
+            object!({"type": "object", "properties": {"path": {"type": "string"}}}),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_budget_breaks_down_request() {
+        let token_counter = AsyncTokenCounter::new().await.unwrap();
+        let extensions = vec![ExtensionInfo::new(
+            "developer",
+            "Use the developer tools.\n### Project Hints\nAlways run cargo fmt.",
+            false,
+        )];
+        let tools = vec![
+            tool("developer__shell"),
+            tool("developer__text_editor"),
+            tool("platform__manage_extensions"),
+        ];
+        let messages = vec![
+            Message::user()
+                .with_text("Fix the failing test")
+                .with_pinned(true),
+            Message::assistant().with_text("Looking into it"),
+        ];
+
+        let budget = ContextBudget::compute(
+            &token_counter,
+            "You are goose. Use the developer tools.\n### Project Hints\nAlways run cargo fmt.",
+            &extensions,
+            &tools,
+            &messages,
+            1_000,
+        );
+
+        let names: Vec<&str> = budget
+            .system_prompt
+            .iter()
+            .map(|cost| cost.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["base prompt", "developer instructions"]);
+        assert_eq!(budget.hints.len(), 1);
+        assert_eq!(budget.hints[0].name, "Project Hints");
+
+        assert_eq!(budget.tools[0].extension, "developer");
+        assert_eq!(budget.tools[0].tool_count, 2);
+        assert_eq!(budget.tools[1].extension, "platform");
+
+        assert_eq!(budget.messages.len(), 2);
+        assert!(budget.messages[0].pinned);
+        assert_eq!(budget.messages[0].preview, "Fix the failing test");
+        assert!(budget.total_tokens >= budget.history_tokens());
+        assert!(budget
+            .advice
+            .iter()
+            .any(|advice| advice.contains("developer extension")));
+    }
+}
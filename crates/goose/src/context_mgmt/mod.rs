@@ -1,5 +1,7 @@
 pub mod auto_compact;
+pub mod budget;
 mod common;
+pub mod strategy;
 pub mod summarize;
 pub mod truncate;
 
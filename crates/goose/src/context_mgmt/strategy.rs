@@ -0,0 +1,444 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use rmcp::model::Role;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::common::get_messages_token_counts_async;
+use super::summarize::{append_after_pinned, partition_pinned, summarize_messages_async};
+use super::truncate::{
+    truncate_messages, OldestFirstTruncation, ToolOutputElision, TruncationStrategy,
+};
+use crate::config::Config;
+use crate::conversation::message::Message;
+use crate::conversation::Conversation;
+use crate::providers::base::Provider;
+use crate::token_counter::AsyncTokenCounter;
+
+/// Config key selecting the compaction strategy when neither the session nor a recipe sets one
+pub const COMPACTION_STRATEGY_CONFIG_KEY: &str = "GOOSE_COMPACTION_STRATEGY";
+pub const DEFAULT_COMPACTION_STRATEGY: &str = "auto";
+
+/// Prefix of the assistant message that replaces summarized messages. A later summary covers
+/// this message like any other, which is how a rolling summary folds in the earlier ones.
+const SUMMARY_HEADER: &str = "Summary of our conversation so far:";
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CompactionStrategyMetadata {
+    pub name: String,
+    pub description: String,
+}
+
+impl CompactionStrategyMetadata {
+    fn new(name: &str, description: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+        }
+    }
+}
+
+/// A way of shrinking a conversation to fit within a token budget
+#[async_trait]
+pub trait CompactionStrategy: Send + Sync {
+    fn metadata() -> CompactionStrategyMetadata
+    where
+        Self: Sized;
+
+    /// Compact `messages` to fit within `context_limit` tokens, returning the new messages and
+    /// their token counts
+    async fn compact(
+        &self,
+        provider: Arc<dyn Provider>,
+        token_counter: &AsyncTokenCounter,
+        messages: &[Message],
+        context_limit: usize,
+    ) -> Result<(Conversation, Vec<usize>)>;
+}
+
+pub fn strategies() -> Vec<CompactionStrategyMetadata> {
+    vec![
+        AutoCompaction::metadata(),
+        OldestFirstCompaction::metadata(),
+        ToolOutputElisionCompaction::metadata(),
+        SummarizeCompaction::metadata(),
+        SummarizeMiddleCompaction::metadata(),
+        RollingSummaryCompaction::metadata(),
+    ]
+}
+
+pub fn create(name: &str) -> Result<Box<dyn CompactionStrategy>> {
+    match name {
+        "auto" => Ok(Box::new(AutoCompaction)),
+        "oldest_first" => Ok(Box::new(OldestFirstCompaction)),
+        "tool_output_elision" => Ok(Box::new(ToolOutputElisionCompaction)),
+        "summarize" => Ok(Box::new(SummarizeCompaction)),
+        "summarize_middle" => Ok(Box::new(SummarizeMiddleCompaction::default())),
+        "rolling_summary" => Ok(Box::new(RollingSummaryCompaction)),
+        _ => Err(anyhow::anyhow!("Unknown compaction strategy: {}", name)),
+    }
+}
+
+/// The strategy to use, in order of precedence: the session's own choice (e.g. from a recipe),
+/// GOOSE_COMPACTION_STRATEGY, then the default
+pub fn resolve_strategy_name(session_override: Option<&str>) -> String {
+    session_override
+        .map(str::to_string)
+        .or_else(|| {
+            Config::global()
+                .get_param::<String>(COMPACTION_STRATEGY_CONFIG_KEY)
+                .ok()
+        })
+        .unwrap_or_else(|| DEFAULT_COMPACTION_STRATEGY.to_string())
+}
+
+/// How to truncate a conversation that overflowed the provider's context, for the given
+/// strategy name. Dropping the oldest messages is the default; eliding old tool outputs first
+/// is opted into by choosing the tool_output_elision strategy.
+pub fn overflow_truncation(strategy_name: &str) -> Box<dyn TruncationStrategy> {
+    match strategy_name {
+        "tool_output_elision" => Box::new(ToolOutputElision::default()),
+        _ => Box::new(OldestFirstTruncation),
+    }
+}
+
+fn truncate_with(
+    strategy: &dyn TruncationStrategy,
+    token_counter: &AsyncTokenCounter,
+    messages: &[Message],
+    context_limit: usize,
+) -> Result<(Conversation, Vec<usize>)> {
+    let token_counts = get_messages_token_counts_async(token_counter, messages);
+    truncate_messages(messages, &token_counts, context_limit, strategy)
+}
+
+/// Elide old tool outputs, and summarize only when that is not enough
+pub struct AutoCompaction;
+
+#[async_trait]
+impl CompactionStrategy for AutoCompaction {
+    fn metadata() -> CompactionStrategyMetadata {
+        CompactionStrategyMetadata::new(
+            "auto",
+            "Elide old tool outputs first and summarize the conversation only if that is not enough",
+        )
+    }
+
+    async fn compact(
+        &self,
+        provider: Arc<dyn Provider>,
+        token_counter: &AsyncTokenCounter,
+        messages: &[Message],
+        context_limit: usize,
+    ) -> Result<(Conversation, Vec<usize>)> {
+        if let Some(pruned) = prune_tool_outputs(token_counter, messages, context_limit) {
+            return Ok(pruned);
+        }
+        SummarizeCompaction
+            .compact(provider, token_counter, messages, context_limit)
+            .await
+    }
+}
+
+/// Replace old tool outputs with stubs until the messages fit under `context_limit`
+///
+/// Returns the pruned messages with their token counts, or None when pruning alone can't get
+/// under the limit and the conversation needs to be summarized instead.
+pub fn prune_tool_outputs(
+    token_counter: &AsyncTokenCounter,
+    messages: &[Message],
+    context_limit: usize,
+) -> Option<(Conversation, Vec<usize>)> {
+    let mut pruned = messages.to_vec();
+    let mut token_counts = get_messages_token_counts_async(token_counter, &pruned);
+    let tokens_before: usize = token_counts.iter().sum();
+
+    ToolOutputElision::default().shrink_messages(&mut pruned, &mut token_counts, context_limit);
+
+    // Shrinking works from estimates, so measure the result properly before relying on it
+    let token_counts = get_messages_token_counts_async(token_counter, &pruned);
+    let tokens_after: usize = token_counts.iter().sum();
+    if tokens_after >= tokens_before || tokens_after > context_limit {
+        tracing::debug!(
+            "Pruning tool outputs is not enough: {} -> {} tokens, limit {}",
+            tokens_before,
+            tokens_after,
+            context_limit
+        );
+        return None;
+    }
+
+    tracing::info!(
+        "Pruned tool outputs: {} tokens -> {} tokens",
+        tokens_before,
+        tokens_after
+    );
+    Some((Conversation::new_unvalidated(pruned), token_counts))
+}
+
+/// Drop whole messages, oldest first
+pub struct OldestFirstCompaction;
+
+#[async_trait]
+impl CompactionStrategy for OldestFirstCompaction {
+    fn metadata() -> CompactionStrategyMetadata {
+        CompactionStrategyMetadata::new("oldest_first", "Remove the oldest messages first")
+    }
+
+    async fn compact(
+        &self,
+        _provider: Arc<dyn Provider>,
+        token_counter: &AsyncTokenCounter,
+        messages: &[Message],
+        context_limit: usize,
+    ) -> Result<(Conversation, Vec<usize>)> {
+        truncate_with(
+            &OldestFirstTruncation,
+            token_counter,
+            messages,
+            context_limit,
+        )
+    }
+}
+
+/// Replace old tool outputs with stubs, then drop whole messages oldest first
+pub struct ToolOutputElisionCompaction;
+
+#[async_trait]
+impl CompactionStrategy for ToolOutputElisionCompaction {
+    fn metadata() -> CompactionStrategyMetadata {
+        CompactionStrategyMetadata::new(
+            "tool_output_elision",
+            "Replace old tool outputs with short stubs, then remove the oldest messages if needed",
+        )
+    }
+
+    async fn compact(
+        &self,
+        _provider: Arc<dyn Provider>,
+        token_counter: &AsyncTokenCounter,
+        messages: &[Message],
+        context_limit: usize,
+    ) -> Result<(Conversation, Vec<usize>)> {
+        truncate_with(
+            &ToolOutputElision::default(),
+            token_counter,
+            messages,
+            context_limit,
+        )
+    }
+}
+
+/// Summarize the whole conversation
+pub struct SummarizeCompaction;
+
+#[async_trait]
+impl CompactionStrategy for SummarizeCompaction {
+    fn metadata() -> CompactionStrategyMetadata {
+        CompactionStrategyMetadata::new("summarize", "Summarize the entire conversation")
+    }
+
+    async fn compact(
+        &self,
+        provider: Arc<dyn Provider>,
+        token_counter: &AsyncTokenCounter,
+        messages: &[Message],
+        context_limit: usize,
+    ) -> Result<(Conversation, Vec<usize>)> {
+        summarize_messages_async(provider, messages, token_counter, context_limit).await
+    }
+}
+
+/// Keep the opening task and the most recent messages verbatim and summarize what is between
+pub struct SummarizeMiddleCompaction {
+    /// Number of most recent messages to keep, extended back to the start of a turn
+    pub keep_recent: usize,
+}
+
+impl Default for SummarizeMiddleCompaction {
+    fn default() -> Self {
+        Self { keep_recent: 6 }
+    }
+}
+
+#[async_trait]
+impl CompactionStrategy for SummarizeMiddleCompaction {
+    fn metadata() -> CompactionStrategyMetadata {
+        CompactionStrategyMetadata::new(
+            "summarize_middle",
+            "Keep the first task and the most recent messages, and summarize the middle",
+        )
+    }
+
+    async fn compact(
+        &self,
+        provider: Arc<dyn Provider>,
+        token_counter: &AsyncTokenCounter,
+        messages: &[Message],
+        context_limit: usize,
+    ) -> Result<(Conversation, Vec<usize>)> {
+        let head_end = anchored_head_end(messages);
+        // When the recent window reaches back into the first turn there is nothing to summarize
+        let tail_start =
+            turn_start_at_or_before(messages, messages.len().saturating_sub(self.keep_recent))
+                .map_or(head_end, |start| start.max(head_end));
+        summarize_span(
+            provider,
+            token_counter,
+            messages,
+            head_end,
+            tail_start,
+            context_limit,
+        )
+        .await
+    }
+}
+
+/// Keep the opening task and the latest turn, and fold everything in between (including any
+/// earlier summary) into a new summary
+pub struct RollingSummaryCompaction;
+
+#[async_trait]
+impl CompactionStrategy for RollingSummaryCompaction {
+    fn metadata() -> CompactionStrategyMetadata {
+        CompactionStrategyMetadata::new(
+            "rolling_summary",
+            "Keep the first task and the latest turn, and roll everything else into a running summary",
+        )
+    }
+
+    async fn compact(
+        &self,
+        provider: Arc<dyn Provider>,
+        token_counter: &AsyncTokenCounter,
+        messages: &[Message],
+        context_limit: usize,
+    ) -> Result<(Conversation, Vec<usize>)> {
+        let head_end = anchored_head_end(messages);
+        // When the latest turn is the first task itself, everything after it gets summarized
+        let tail_start = turn_start_at_or_before(messages, messages.len())
+            .filter(|&start| start >= head_end)
+            .unwrap_or(messages.len());
+        summarize_span(
+            provider,
+            token_counter,
+            messages,
+            head_end,
+            tail_start,
+            context_limit,
+        )
+        .await
+    }
+}
+
+fn is_user_prompt(message: &Message) -> bool {
+    message.role == Role::User && !message.is_tool_response()
+}
+
+/// End of the anchored head: everything up to and including the first user prompt
+fn anchored_head_end(messages: &[Message]) -> usize {
+    messages
+        .iter()
+        .position(is_user_prompt)
+        .map(|i| i + 1)
+        .unwrap_or(0)
+}
+
+/// The start of the turn containing `index`, i.e. the closest user prompt at or before it.
+/// Turns are never split, so no tool response is separated from its request.
+fn turn_start_at_or_before(messages: &[Message], index: usize) -> Option<usize> {
+    if messages.is_empty() {
+        return None;
+    }
+    let index = index.min(messages.len().saturating_sub(1));
+    messages[..=index].iter().rposition(is_user_prompt)
+}
+
+/// Replace `messages[head_end..tail_start]` with a single summary message
+async fn summarize_span(
+    provider: Arc<dyn Provider>,
+    token_counter: &AsyncTokenCounter,
+    messages: &[Message],
+    head_end: usize,
+    tail_start: usize,
+    context_limit: usize,
+) -> Result<(Conversation, Vec<usize>)> {
+    if messages.is_empty() || tail_start <= head_end {
+        let token_counts = get_messages_token_counts_async(token_counter, messages);
+        return Ok((
+            Conversation::new_unvalidated(messages.to_vec()),
+            token_counts,
+        ));
+    }
+
+    // Pinned messages in the span stay as they are, ahead of the summary
+    let (pinned, unpinned) = partition_pinned(&messages[head_end..tail_start]);
+    if unpinned.is_empty() {
+        let token_counts = get_messages_token_counts_async(token_counter, messages);
+        return Ok((
+            Conversation::new_unvalidated(messages.to_vec()),
+            token_counts,
+        ));
+    }
+
+    let (summary, _) =
+        summarize_messages_async(provider, &unpinned, token_counter, context_limit).await?;
+    let summary_text = summary
+        .iter()
+        .map(|message| message.as_concat_text())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    let mut compacted = messages[..head_end].to_vec();
+    compacted.extend(append_after_pinned(
+        pinned,
+        [Message::assistant().with_text(format!("{}\n\n{}", SUMMARY_HEADER, summary_text))],
+    ));
+    compacted.extend_from_slice(&messages[tail_start..]);
+
+    let token_counts = get_messages_token_counts_async(token_counter, &compacted);
+    Ok((Conversation::new_unvalidated(compacted), token_counts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_core::tool::ToolCall;
+    use serde_json::json;
+
+    fn conversation() -> Vec<Message> {
+        vec![
+            Message::user().with_text("Build the project"),
+            Message::assistant()
+                .with_tool_request("call_1", Ok(ToolCall::new("developer__shell", json!({})))),
+            Message::user().with_tool_response("call_1", Ok(vec![])),
+            Message::assistant().with_text("Built"),
+            Message::user().with_text("Now run the tests"),
+            Message::assistant()
+                .with_tool_request("call_2", Ok(ToolCall::new("developer__shell", json!({})))),
+            Message::user().with_tool_response("call_2", Ok(vec![])),
+        ]
+    }
+
+    #[test]
+    fn test_registry_creates_every_listed_strategy() {
+        for metadata in strategies() {
+            assert!(create(&metadata.name).is_ok(), "{}", metadata.name);
+        }
+        assert!(create("nonexistent").is_err());
+    }
+
+    #[test]
+    fn test_span_boundaries_never_split_a_turn() {
+        let messages = conversation();
+
+        assert_eq!(anchored_head_end(&messages), 1);
+        // The latest turn starts at the second prompt, even though the window ends on a tool
+        // response
+        assert_eq!(turn_start_at_or_before(&messages, messages.len()), Some(4));
+        assert_eq!(turn_start_at_or_before(&messages, 2), Some(0));
+        assert_eq!(turn_start_at_or_before(&[], 0), None);
+    }
+}
@@ -8,6 +8,7 @@ use crate::token_counter::{AsyncTokenCounter, TokenCounter};
 use anyhow::Result;
 use rmcp::model::Role;
 use serde::Serialize;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 // Constants for the summarization prompt and a follow-up user message.
@@ -159,17 +160,97 @@ pub async fn summarize_messages_chunked(
     ))
 }
 
+/// Split messages into the pinned ones, which are never summarized, and the rest. Tool calls
+/// that are partly pinned are kept whole, so a pinned request keeps its response and the
+/// other way around.
+pub(crate) fn partition_pinned(messages: &[Message]) -> (Vec<Message>, Vec<Message>) {
+    let mut keep: Vec<bool> = messages.iter().map(|message| message.pinned).collect();
+    loop {
+        let pinned_tool_ids: HashSet<&str> = messages
+            .iter()
+            .zip(&keep)
+            .filter(|(_, keep)| **keep)
+            .flat_map(|(message, _)| message.get_tool_ids())
+            .collect();
+        let mut changed = false;
+        for (message, keep) in messages.iter().zip(keep.iter_mut()) {
+            if !*keep && !message.get_tool_ids().is_disjoint(&pinned_tool_ids) {
+                *keep = true;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let (pinned, unpinned): (Vec<_>, Vec<_>) =
+        messages.iter().zip(keep).partition(|(_, keep)| *keep);
+    (
+        pinned
+            .into_iter()
+            .map(|(message, _)| message.clone())
+            .collect(),
+        unpinned
+            .into_iter()
+            .map(|(message, _)| message.clone())
+            .collect(),
+    )
+}
+
+/// Put summary messages after the pinned messages they were split from. The first summary
+/// message takes the opposite role of the last pinned message, so fixing up the conversation
+/// never merges the summary into a pinned message.
+pub(crate) fn append_after_pinned(
+    pinned: Vec<Message>,
+    summary: impl IntoIterator<Item = Message>,
+) -> Vec<Message> {
+    let mut messages = pinned;
+    for (i, mut message) in summary.into_iter().enumerate() {
+        if i == 0 {
+            if let Some(last_pinned) = messages.last() {
+                message.role = match last_pinned.role {
+                    Role::User => Role::Assistant,
+                    Role::Assistant => Role::User,
+                };
+            }
+        }
+        messages.push(message);
+    }
+    messages
+}
+
 /// Main summarization function that chooses the best algorithm based on context size.
 ///
 /// This function will:
 /// 1. First try the one-shot summarization if there's enough context window available
 /// 2. Fall back to the chunked approach if the one-shot fails or if context is too limited
 /// 3. Choose the algorithm based on absolute token requirements rather than percentages
+///
+/// Pinned messages are left out of the summary and returned verbatim, in order, ahead of it.
 pub async fn summarize_messages(
     provider: Arc<dyn Provider>,
     messages: &[Message],
     token_counter: &TokenCounter,
     context_limit: usize,
+) -> Result<(Conversation, Vec<usize>), anyhow::Error> {
+    let (pinned, unpinned) = partition_pinned(messages);
+    let (summary, token_counts) =
+        summarize_unpinned_messages(provider, &unpinned, token_counter, context_limit).await?;
+    if pinned.is_empty() {
+        return Ok((summary, token_counts));
+    }
+
+    let messages = append_after_pinned(pinned, summary.messages().clone());
+    let token_counts = get_messages_token_counts(token_counter, &messages);
+    Ok((Conversation::new_unvalidated(messages), token_counts))
+}
+
+async fn summarize_unpinned_messages(
+    provider: Arc<dyn Provider>,
+    messages: &[Message],
+    token_counter: &TokenCounter,
+    context_limit: usize,
 ) -> Result<(Conversation, Vec<usize>), anyhow::Error> {
     // Calculate total tokens in messages
     let total_tokens: usize = get_messages_token_counts(token_counter, messages)
@@ -207,12 +288,32 @@ pub async fn summarize_messages(
     summarize_messages_chunked(provider, messages, token_counter, context_limit).await
 }
 
-/// Async version using AsyncTokenCounter for better performance
+/// Async version using AsyncTokenCounter for better performance. Like `summarize_messages`, it
+/// keeps pinned messages verbatim ahead of the summary.
 pub async fn summarize_messages_async(
     provider: Arc<dyn Provider>,
     messages: &[Message],
     token_counter: &AsyncTokenCounter,
     context_limit: usize,
+) -> Result<(Conversation, Vec<usize>), anyhow::Error> {
+    let (pinned, unpinned) = partition_pinned(messages);
+    let (summary, token_counts) =
+        summarize_unpinned_messages_async(provider, &unpinned, token_counter, context_limit)
+            .await?;
+    if pinned.is_empty() {
+        return Ok((summary, token_counts));
+    }
+
+    let messages = append_after_pinned(pinned, summary.messages().clone());
+    let token_counts = get_messages_token_counts_async(token_counter, &messages);
+    Ok((Conversation::new_unvalidated(messages), token_counts))
+}
+
+async fn summarize_unpinned_messages_async(
+    provider: Arc<dyn Provider>,
+    messages: &[Message],
+    token_counter: &AsyncTokenCounter,
+    context_limit: usize,
 ) -> Result<(Conversation, Vec<usize>), anyhow::Error> {
     let chunk_size = context_limit / 3; // 33% of the context window.
     let summary_prompt_tokens = token_counter.count_tokens(SUMMARY_PROMPT);
@@ -691,4 +792,44 @@ mod tests {
         let (summarized_messages, _) = result.unwrap();
         assert_eq!(summarized_messages.len(), 1, "Should produce a summary.");
     }
+
+    #[tokio::test]
+    async fn test_summarize_messages_keeps_pinned_messages() {
+        let provider = create_mock_provider().expect("failed to create mock provider");
+        let token_counter = TokenCounter::new();
+        let mut messages = create_test_messages();
+        messages[0].pinned = true;
+
+        let (summarized_messages, token_counts) =
+            summarize_messages(provider, &messages, &token_counter, 10_000)
+                .await
+                .expect("summarization should succeed");
+
+        // The pinned task comes first, verbatim, and the summary follows as the next turn
+        assert_eq!(summarized_messages.len(), 2);
+        assert_eq!(summarized_messages.messages()[0], messages[0]);
+        assert_eq!(summarized_messages.messages()[1].role, Role::Assistant);
+        assert!(!summarized_messages.messages()[1].pinned);
+        assert_eq!(token_counts.len(), 2);
+    }
+
+    #[test]
+    fn test_partition_pinned_keeps_tool_pairs() {
+        let mut request = Message::assistant().with_tool_request(
+            "call_1",
+            Ok(mcp_core::ToolCall::new("search", serde_json::json!({}))),
+        );
+        request.pinned = true;
+        let response = Message::user().with_tool_response("call_1", Ok(vec![]));
+        let messages = vec![
+            set_up_text_message("Find the bug", Role::User),
+            request.clone(),
+            response.clone(),
+            set_up_text_message("Found it", Role::Assistant),
+        ];
+
+        let (pinned, unpinned) = partition_pinned(&messages);
+        assert_eq!(pinned, vec![request, response]);
+        assert_eq!(unpinned.len(), 2);
+    }
 }
@@ -2,8 +2,8 @@ use crate::conversation::message::{Message, MessageContent};
 use crate::conversation::Conversation;
 use crate::utils::safe_truncate;
 use anyhow::{anyhow, Result};
-use rmcp::model::{RawContent, ResourceContents, Role};
-use std::collections::HashSet;
+use rmcp::model::{Content, RawContent, ResourceContents, Role};
+use std::collections::{HashMap, HashSet};
 use std::ops::DerefMut;
 use tracing::{debug, warn};
 
@@ -30,7 +30,7 @@ fn handle_oversized_messages(
     };
 
     for (i, (message, &original_tokens)) in messages.iter().zip(token_counts.iter()).enumerate() {
-        if original_tokens > context_limit {
+        if original_tokens > context_limit && !message.pinned {
             warn!(
                 "Message {} has {} tokens, exceeding context limit of {}",
                 i, original_tokens, context_limit
@@ -194,9 +194,14 @@ pub fn truncate_messages(
         ));
     }
 
-    // Step 1: Calculate total tokens
+    // Step 1: Calculate total tokens, letting the strategy shrink messages in place first
     let mut total_tokens: usize = token_counts.iter().sum();
     debug!("Total tokens before truncation: {}", total_tokens);
+    if total_tokens > context_limit {
+        strategy.shrink_messages(&mut messages, &mut token_counts, context_limit);
+        total_tokens = token_counts.iter().sum();
+        debug!("Total tokens after shrinking messages: {}", total_tokens);
+    }
 
     // Check if any individual message is larger than the context limit
     // First, check for any message that's too large
@@ -231,9 +236,10 @@ pub fn truncate_messages(
         )); // No truncation needed
     }
 
-    // Step 2: Determine indices to remove based on strategy
-    let indices_to_remove =
+    // Step 2: Determine indices to remove based on strategy, never removing pinned messages
+    let mut indices_to_remove =
         strategy.determine_indices_to_remove(&messages, &token_counts, context_limit)?;
+    keep_pinned(&messages, &mut indices_to_remove);
 
     // Circuit breaker: if we can't remove enough messages, fail gracefully
     let tokens_to_remove: usize = indices_to_remove
@@ -270,9 +276,14 @@ pub fn truncate_messages(
         }
     }
 
-    // Step 4: Ensure the last message is a user message with TextContent only
+    // Step 4: Ensure the last message is a user message with TextContent only. This holds even
+    // for pinned messages, since providers reject anything else; a tool response is always
+    // popped before its request, so no tool call is left half removed.
     while let Some(last_msg) = messages.last() {
         if last_msg.role != Role::User || !last_msg.has_only_text_content() {
+            if last_msg.pinned {
+                warn!("Removing a pinned message from the end of the conversation");
+            }
             let _ = messages.pop().ok_or(anyhow!("Failed to pop message"))?;
             let removed_tokens = token_counts
                 .pop()
@@ -283,9 +294,12 @@ pub fn truncate_messages(
         }
     }
 
-    // Step 5: Check first msg is a User message with TextContent only
+    // Step 5: Check first msg is a User message with TextContent only, pinned or not
     while let Some(first_msg) = messages.first() {
         if first_msg.role != Role::User || !first_msg.has_only_text_content() {
+            if first_msg.pinned {
+                warn!("Removing a pinned message from the start of the conversation");
+            }
             let _ = messages.remove(0);
             let removed_tokens = token_counts.remove(0);
             total_tokens -= removed_tokens;
@@ -316,6 +330,41 @@ pub fn truncate_messages(
     ))
 }
 
+/// Take pinned messages out of `indices_to_remove`, along with any message that shares a tool
+/// call with a message that stays, so a tool request never outlives its response or the other
+/// way around
+fn keep_pinned(messages: &[Message], indices_to_remove: &mut HashSet<usize>) {
+    let mut kept_tool_ids: HashSet<&str> = messages
+        .iter()
+        .enumerate()
+        .filter(|(i, message)| message.pinned || !indices_to_remove.contains(i))
+        .flat_map(|(_, message)| message.get_tool_ids())
+        .collect();
+
+    loop {
+        let keep: Vec<usize> = indices_to_remove
+            .iter()
+            .copied()
+            .filter(|&i| {
+                messages.get(i).is_some_and(|message| {
+                    message.pinned
+                        || message
+                            .get_tool_ids()
+                            .iter()
+                            .any(|id| kept_tool_ids.contains(id))
+                })
+            })
+            .collect();
+        if keep.is_empty() {
+            break;
+        }
+        for i in keep {
+            indices_to_remove.remove(&i);
+            kept_tool_ids.extend(messages[i].get_tool_ids());
+        }
+    }
+}
+
 /// Trait representing a truncation strategy
 pub trait TruncationStrategy {
     /// Determines the indices of messages to remove to fit within the context limit.
@@ -324,13 +373,27 @@ pub trait TruncationStrategy {
     /// - `token_counts`: A parallel array containing the token count for each message.
     /// - `context_limit`: The maximum allowed context length in tokens.
     ///
-    /// Returns a vector of indices to remove.
+    /// Returns a vector of indices to remove. Pinned messages must be kept, along with the other
+    /// half of any tool call they are part of; `truncate_messages` drops them from the result.
     fn determine_indices_to_remove(
         &self,
         messages: &[Message],
         token_counts: &[usize],
         context_limit: usize,
     ) -> Result<HashSet<usize>>;
+
+    /// Shrinks messages in place before any are removed, e.g. by eliding old tool output.
+    ///
+    /// Implementations must keep every tool request paired with its response, leave pinned
+    /// messages untouched and update `token_counts` for the messages they change. The default
+    /// leaves messages untouched.
+    fn shrink_messages(
+        &self,
+        _messages: &mut [Message],
+        _token_counts: &mut [usize],
+        _context_limit: usize,
+    ) {
+    }
 }
 
 /// Strategy to truncate messages by removing the oldest first
@@ -347,10 +410,26 @@ impl TruncationStrategy for OldestFirstTruncation {
         let mut total_tokens: usize = token_counts.iter().sum();
         let mut tool_ids_to_remove = HashSet::new();
 
+        // Tool calls that are partly pinned have to stay whole
+        let pinned_tool_ids: HashSet<String> = messages
+            .iter()
+            .filter(|message| message.pinned)
+            .flat_map(|message| message.get_tool_ids())
+            .map(str::to_string)
+            .collect();
+
         for (i, message) in messages.iter().enumerate() {
             if total_tokens <= context_limit {
                 break;
             }
+            if message.pinned
+                || message
+                    .get_tool_ids()
+                    .iter()
+                    .any(|id| pinned_tool_ids.contains(*id))
+            {
+                continue;
+            }
 
             // Remove the message
             indices_to_remove.insert(i);
@@ -385,6 +464,142 @@ impl TruncationStrategy for OldestFirstTruncation {
     }
 }
 
+const ELIDED_OUTPUT_MARKER: &str = "[tool output elided]";
+const ELIDED_PREVIEW_CHARS: usize = 200;
+
+/// Strategy that first replaces old tool outputs with short stubs, oldest first, and only
+/// removes whole messages (oldest first) when that is not enough.
+///
+/// Stale `shell` and `text_editor view` outputs make up most of a long conversation, and the
+/// model can get them back by repeating the call. The tool requests and responses stay in
+/// place, so pairing is unaffected; only the response content is replaced.
+pub struct ToolOutputElision {
+    /// Number of most recent tool responses that are never elided
+    pub keep_recent: usize,
+}
+
+impl Default for ToolOutputElision {
+    fn default() -> Self {
+        Self { keep_recent: 3 }
+    }
+}
+
+impl ToolOutputElision {
+    fn stub(tool_name: Option<&str>, original: &str) -> String {
+        let first_line = original.lines().find(|line| !line.trim().is_empty());
+        let preview = first_line
+            .map(|line| {
+                format!(
+                    "\nIt began with: {}",
+                    safe_truncate(line, ELIDED_PREVIEW_CHARS)
+                )
+            })
+            .unwrap_or_default();
+        format!(
+            "{} The output of {} ({} characters) was removed to save context. Repeat the tool call if you need it again.{}",
+            ELIDED_OUTPUT_MARKER,
+            tool_name
+                .map(|name| format!("`{}`", name))
+                .unwrap_or_else(|| "this tool call".to_string()),
+            original.chars().count(),
+            preview
+        )
+    }
+}
+
+impl TruncationStrategy for ToolOutputElision {
+    fn determine_indices_to_remove(
+        &self,
+        messages: &[Message],
+        token_counts: &[usize],
+        context_limit: usize,
+    ) -> Result<HashSet<usize>> {
+        OldestFirstTruncation.determine_indices_to_remove(messages, token_counts, context_limit)
+    }
+
+    fn shrink_messages(
+        &self,
+        messages: &mut [Message],
+        token_counts: &mut [usize],
+        context_limit: usize,
+    ) {
+        let estimate_tokens = |text: &str| -> usize { (text.len() / 4).max(1) };
+
+        let tool_names: HashMap<String, String> = messages
+            .iter()
+            .flat_map(|message| message.content.iter())
+            .filter_map(|content| match content {
+                MessageContent::ToolRequest(request) => request
+                    .tool_call
+                    .as_ref()
+                    .ok()
+                    .map(|call| (request.id.clone(), call.name.clone())),
+                _ => None,
+            })
+            .collect();
+
+        let response_messages: Vec<usize> = messages
+            .iter()
+            .enumerate()
+            .filter(|(_, message)| message.is_tool_response() && !message.pinned)
+            .map(|(i, _)| i)
+            .collect();
+        let elidable = response_messages.len().saturating_sub(self.keep_recent);
+
+        let mut total_tokens: usize = token_counts.iter().sum();
+        for &i in &response_messages[..elidable] {
+            if total_tokens <= context_limit {
+                break;
+            }
+
+            let mut changed = false;
+            for content in &mut messages[i].content {
+                let MessageContent::ToolResponse(response) = content else {
+                    continue;
+                };
+                let Ok(result) = &response.tool_result else {
+                    continue;
+                };
+                let text: String = result
+                    .iter()
+                    .filter_map(|item| match &item.raw {
+                        RawContent::Text(text) => Some(text.text.as_str()),
+                        RawContent::Resource(resource) => match &resource.resource {
+                            ResourceContents::TextResourceContents { text, .. } => {
+                                Some(text.as_str())
+                            }
+                            _ => None,
+                        },
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                if text.starts_with(ELIDED_OUTPUT_MARKER) {
+                    continue;
+                }
+
+                let stub = Self::stub(tool_names.get(&response.id).map(String::as_str), &text);
+                if result.len() == 1 && stub.len() >= text.len() {
+                    continue;
+                }
+                response.tool_result = Ok(vec![Content::text(stub)]);
+                changed = true;
+            }
+
+            if changed {
+                let new_tokens =
+                    estimate_message_tokens(&messages[i], &estimate_tokens).min(token_counts[i]);
+                debug!(
+                    "ToolOutputElision: Elided tool output at index {}. Tokens {} -> {}",
+                    i, token_counts[i], new_tokens
+                );
+                total_tokens -= token_counts[i] - new_tokens;
+                token_counts[i] = new_tokens;
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -725,4 +940,93 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_tool_output_elision_prunes_before_removing_messages() -> Result<()> {
+        let shell = |command: &str| ToolCall::new("developer__shell", json!({"command": command}));
+        let long_output = "total 42\n".to_string() + &"-rw-r--r-- file\n".repeat(200);
+
+        let messages_with_counts = vec![
+            user_text(1, 10),
+            assistant_tool_request("call_1", shell("ls -la"), 10),
+            user_tool_response("call_1", vec![Content::text(long_output.clone())], 800),
+            assistant_tool_request("call_2", shell("cat README.md"), 10),
+            user_tool_response("call_2", vec![Content::text(long_output)], 800),
+            assistant_text(2, 10),
+            user_text(3, 10),
+        ];
+        let (messages, token_counts): (Vec<Message>, Vec<usize>) =
+            messages_with_counts.into_iter().unzip();
+
+        let strategy = ToolOutputElision { keep_recent: 1 };
+        let (truncated, truncated_counts) =
+            truncate_messages(&messages, &token_counts, 1000, &strategy)?;
+
+        // Nothing was removed, only the oldest output was replaced
+        assert_eq!(truncated.len(), messages.len());
+        assert!(truncated_counts.iter().sum::<usize>() <= 1000);
+
+        let MessageContent::ToolResponse(elided) = &truncated.messages()[2].content[0] else {
+            panic!("expected a tool response");
+        };
+        let elided_text = elided.tool_result.as_ref().unwrap()[0]
+            .as_text()
+            .unwrap()
+            .text
+            .clone();
+        assert!(elided_text.starts_with(ELIDED_OUTPUT_MARKER));
+        assert!(elided_text.contains("`developer__shell`"));
+        assert!(elided_text.contains("total 42"));
+
+        // The most recent output is kept as is
+        assert_eq!(truncated.messages()[4], messages[4]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pinned_messages_are_never_truncated() -> Result<()> {
+        let (messages, token_counts) = create_messages_with_counts(4, 10, true);
+        let mut messages = messages.messages().clone();
+        messages[0].pinned = true;
+
+        let (truncated, truncated_counts) =
+            truncate_messages(&messages, &token_counts, 45, &OldestFirstTruncation)?;
+
+        // The pinned task is kept verbatim and the oldest unpinned messages go instead
+        assert_eq!(truncated.messages()[0], messages[0]);
+        assert_eq!(truncated.messages()[1..], messages[4..]);
+        assert!(truncated_counts.iter().sum::<usize>() <= 45);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pinned_final_assistant_message_still_ends_on_user() -> Result<()> {
+        let (mut messages, mut token_counts): (Vec<Message>, Vec<usize>) = vec![
+            user_text(1, 10),
+            assistant_text(2, 10),
+            user_text(3, 10),
+            assistant_tool_request("tool1", ToolCall::new("read_file", json!({})), 10),
+            user_tool_response("tool1", vec![Content::text("contents")], 10),
+        ]
+        .into_iter()
+        .unzip();
+        let (message, tokens) = assistant_text(4, 10);
+        messages.push(message.with_pinned(true));
+        token_counts.push(tokens);
+
+        let (truncated, truncated_counts) =
+            truncate_messages(&messages, &token_counts, 45, &OldestFirstTruncation)?;
+
+        let last = truncated.messages().last().unwrap();
+        assert_eq!(last.role, Role::User);
+        assert!(last.has_only_text_content());
+        assert_eq!(truncated.len(), truncated_counts.len());
+        let tool_ids: Vec<_> = truncated
+            .messages()
+            .iter()
+            .flat_map(|message| message.get_tool_ids())
+            .collect();
+        assert!(tool_ids.is_empty());
+        Ok(())
+    }
 }
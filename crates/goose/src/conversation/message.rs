@@ -347,6 +347,9 @@ pub struct Message {
     #[serde(default = "default_created")]
     pub created: i64,
     pub content: Vec<MessageContent>,
+    /// Pinned messages are never summarized or truncated away during compaction
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
 }
 
 impl fmt::Debug for Message {
@@ -373,6 +376,7 @@ impl Message {
             role,
             created,
             content,
+            pinned: false,
         }
     }
     pub fn debug(&self) -> String {
@@ -386,6 +390,7 @@ impl Message {
             role: Role::User,
             created: Utc::now().timestamp(),
             content: Vec::new(),
+            pinned: false,
         }
     }
 
@@ -396,6 +401,7 @@ impl Message {
             role: Role::Assistant,
             created: Utc::now().timestamp(),
             content: Vec::new(),
+            pinned: false,
         }
     }
 
@@ -404,6 +410,12 @@ impl Message {
         self
     }
 
+    /// Pin or unpin the message, keeping it verbatim through context compaction
+    pub fn with_pinned(mut self, pinned: bool) -> Self {
+        self.pinned = pinned;
+        self
+    }
+
     /// Add any MessageContent to the message
     pub fn with_content(mut self, content: MessageContent) -> Self {
         self.content.push(content);
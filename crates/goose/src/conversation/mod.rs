@@ -1,8 +1,11 @@
 use crate::conversation::message::{Message, MessageContent};
+use mcp_core::handler::ToolError;
 use rmcp::model::Role;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::fmt;
 use thiserror::Error;
+use utoipa::ToSchema;
 
 pub mod message;
 mod tool_result_serde;
@@ -103,9 +106,13 @@ impl Conversation {
     }
 
     fn validate(self) -> Result<Self, InvalidConversation> {
-        let (_messages, issues) = fix_messages(self.0.clone());
+        let issues = validate_conversation(&self);
         if !issues.is_empty() {
-            let reason = issues.join("\n");
+            let reason = issues
+                .iter()
+                .map(|issue| issue.to_string())
+                .collect::<Vec<_>>()
+                .join("\n");
             Err(InvalidConversation {
                 reason,
                 conversation: self,
@@ -122,19 +129,214 @@ impl Default for Conversation {
     }
 }
 
+/// How serious a conversation issue is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    /// Tolerated by most providers, but repaired anyway
+    Warning,
+    /// Causes providers to reject the request
+    Error,
+}
+
+/// What went wrong in a conversation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum IssueKind {
+    EmptyMessage,
+    ToolRequestInUserMessage { id: String },
+    ToolConfirmationRequestInUserMessage { id: String },
+    ThinkingInUserMessage,
+    OrphanedToolResponse { id: String },
+    ToolResponseInAssistantMessage { id: String },
+    FrontendToolRequestInAssistantMessage { id: String },
+    OrphanedToolRequest { id: String },
+    ConsecutiveMessages { role: String },
+    LeadingAssistantMessage,
+    TrailingAssistantMessage,
+    EmptyConversation,
+}
+
+/// What was done about an issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum RepairAction {
+    Removed,
+    Merged,
+    SynthesizedErrorResponse,
+    AddedPlaceholder,
+}
+
+/// A problem found in a conversation, the position of the message it was found in
+/// (an index into the conversation as it was passed in) and how it was repaired
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ConversationIssue {
+    pub kind: IssueKind,
+    pub severity: IssueSeverity,
+    pub index: Option<usize>,
+    pub action: RepairAction,
+}
+
+impl ConversationIssue {
+    fn new(kind: IssueKind, index: Option<usize>, action: RepairAction) -> Self {
+        let severity = match kind {
+            IssueKind::EmptyMessage
+            | IssueKind::ThinkingInUserMessage
+            | IssueKind::ToolConfirmationRequestInUserMessage { .. }
+            | IssueKind::FrontendToolRequestInAssistantMessage { .. }
+            | IssueKind::ConsecutiveMessages { .. } => IssueSeverity::Warning,
+            _ => IssueSeverity::Error,
+        };
+        Self {
+            kind,
+            severity,
+            index,
+            action,
+        }
+    }
+}
+
+impl fmt::Display for ConversationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.kind, self.action) {
+            (IssueKind::EmptyMessage, _) => write!(f, "Removed empty message"),
+            (IssueKind::ToolRequestInUserMessage { id }, _) => {
+                write!(f, "Removed tool request '{}' from user message", id)
+            }
+            (IssueKind::ToolConfirmationRequestInUserMessage { id }, _) => write!(
+                f,
+                "Removed tool confirmation request '{}' from user message",
+                id
+            ),
+            (IssueKind::ThinkingInUserMessage, _) => {
+                write!(f, "Removed thinking content from user message")
+            }
+            (IssueKind::OrphanedToolResponse { id }, _) => {
+                write!(f, "Removed orphaned tool response '{}'", id)
+            }
+            (IssueKind::ToolResponseInAssistantMessage { id }, _) => {
+                write!(f, "Removed tool response '{}' from assistant message", id)
+            }
+            (IssueKind::FrontendToolRequestInAssistantMessage { id }, _) => write!(
+                f,
+                "Removed frontend tool request '{}' from assistant message",
+                id
+            ),
+            (IssueKind::OrphanedToolRequest { id }, RepairAction::SynthesizedErrorResponse) => {
+                write!(f, "Added error response for orphaned tool request '{}'", id)
+            }
+            (IssueKind::OrphanedToolRequest { id }, _) => {
+                write!(f, "Removed orphaned tool request '{}'", id)
+            }
+            (IssueKind::ConsecutiveMessages { role }, RepairAction::Merged) => {
+                write!(f, "Merged consecutive {} messages", role)
+            }
+            (IssueKind::ConsecutiveMessages { role }, _) => {
+                write!(f, "Removed consecutive {} message", role)
+            }
+            (IssueKind::LeadingAssistantMessage, _) => {
+                write!(f, "Removed leading assistant message")
+            }
+            (IssueKind::TrailingAssistantMessage, _) => {
+                write!(f, "Removed trailing assistant message")
+            }
+            (IssueKind::EmptyConversation, _) => {
+                write!(f, "Added placeholder user message to empty conversation")
+            }
+        }
+    }
+}
+
+/// How to repair tool requests that never got a response
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum OrphanedRequestRepair {
+    /// Remove the tool request
+    #[default]
+    Drop,
+    /// Keep the tool request and answer it with an error tool response
+    SynthesizeErrorResponse,
+}
+
+/// How to repair consecutive messages with the same role
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ConsecutiveMessageRepair {
+    /// Fold the later message's content into the earlier one
+    #[default]
+    Merge,
+    /// Keep the earlier message and drop the later one
+    Drop,
+}
+
+/// Policies used when repairing a conversation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairPolicy {
+    #[serde(default)]
+    pub orphaned_tool_requests: OrphanedRequestRepair,
+    #[serde(default)]
+    pub consecutive_messages: ConsecutiveMessageRepair,
+    /// Keep a trailing assistant message instead of removing it. Stored sessions legitimately
+    /// end with the assistant's reply; only a conversation sent to a provider must not.
+    #[serde(skip)]
+    pub keep_trailing_assistant: bool,
+}
+
+const ORPHANED_REQUEST_ERROR: &str = "Tool call was interrupted before it returned a result";
+
+/// A message tagged with its position in the conversation being repaired
+type Indexed = (usize, Message);
+
 /// Fix a conversation that we're about to send to an LLM. So the last and first
 /// messages should always be from the user.
 pub fn fix_conversation(conversation: Conversation) -> (Conversation, Vec<String>) {
-    let messages = conversation.messages().clone();
-    let (messages, issues) = fix_messages(messages);
+    let (conversation, issues) = repair_conversation(conversation, &RepairPolicy::default());
+    (
+        conversation,
+        issues.iter().map(|issue| issue.to_string()).collect(),
+    )
+}
+
+/// Check a conversation without changing it, returning every issue that
+/// `repair_conversation` would fix with the default policy
+pub fn validate_conversation(conversation: &Conversation) -> Vec<ConversationIssue> {
+    let (_messages, issues) =
+        fix_messages(conversation.messages().clone(), &RepairPolicy::default());
+    issues
+}
+
+/// Repair a conversation according to `policy`, returning the repaired conversation and the
+/// issues that were fixed
+pub fn repair_conversation(
+    conversation: Conversation,
+    policy: &RepairPolicy,
+) -> (Conversation, Vec<ConversationIssue>) {
+    let (messages, issues) = fix_messages(conversation.messages().clone(), policy);
     (Conversation::new_unvalidated(messages), issues)
 }
 
-fn fix_messages(messages: Vec<Message>) -> (Vec<Message>, Vec<String>) {
+fn fix_messages(
+    messages: Vec<Message>,
+    policy: &RepairPolicy,
+) -> (Vec<Message>, Vec<ConversationIssue>) {
+    let messages: Vec<Indexed> = messages.into_iter().enumerate().collect();
     let (messages_1, empty_removed) = remove_empty_messages(messages);
-    let (messages_2, tool_calling_fixed) = fix_tool_calling(messages_1);
-    let (messages_3, messages_merged) = merge_consecutive_messages(messages_2);
-    let (messages_4, lead_trail_fixed) = fix_lead_trail(messages_3);
+    let (messages_2, mut tool_calling_fixed) = fix_tool_calling(messages_1, policy);
+    let (mut messages_3, mut messages_merged) = merge_consecutive_messages(messages_2, policy);
+
+    // Dropping a consecutive message can take one half of a tool request/response pair with
+    // it, so pair the tool calls up again until no more messages are dropped
+    let mut dropped = !messages_merged.is_empty();
+    while dropped && policy.consecutive_messages == ConsecutiveMessageRepair::Drop {
+        let (repaired, fixed) = fix_tool_calling(messages_3, policy);
+        tool_calling_fixed.extend(fixed);
+        let (merged, merged_issues) = merge_consecutive_messages(repaired, policy);
+        dropped = !merged_issues.is_empty();
+        messages_3 = merged;
+        messages_merged.extend(merged_issues);
+    }
+    let (messages_4, lead_trail_fixed) = fix_lead_trail(messages_3, policy);
     let (messages_5, populated_if_empty) = populate_if_empty(messages_4);
 
     let mut issues = Vec::new();
@@ -144,16 +346,23 @@ fn fix_messages(messages: Vec<Message>) -> (Vec<Message>, Vec<String>) {
     issues.extend(lead_trail_fixed);
     issues.extend(populated_if_empty);
 
-    (messages_5, issues)
+    (
+        messages_5.into_iter().map(|(_, message)| message).collect(),
+        issues,
+    )
 }
 
-fn remove_empty_messages(messages: Vec<Message>) -> (Vec<Message>, Vec<String>) {
+fn remove_empty_messages(messages: Vec<Indexed>) -> (Vec<Indexed>, Vec<ConversationIssue>) {
     let mut issues = Vec::new();
     let filtered_messages = messages
         .into_iter()
-        .filter(|msg| {
+        .filter(|(index, msg)| {
             if msg.content.is_empty() {
-                issues.push("Removed empty message".to_string());
+                issues.push(ConversationIssue::new(
+                    IssueKind::EmptyMessage,
+                    Some(*index),
+                    RepairAction::Removed,
+                ));
                 false
             } else {
                 true
@@ -163,11 +372,15 @@ fn remove_empty_messages(messages: Vec<Message>) -> (Vec<Message>, Vec<String>)
     (filtered_messages, issues)
 }
 
-fn fix_tool_calling(mut messages: Vec<Message>) -> (Vec<Message>, Vec<String>) {
+fn fix_tool_calling(
+    mut messages: Vec<Indexed>,
+    policy: &RepairPolicy,
+) -> (Vec<Indexed>, Vec<ConversationIssue>) {
     let mut issues = Vec::new();
     let mut pending_tool_requests: HashSet<String> = HashSet::new();
 
-    for message in &mut messages {
+    for (index, message) in &mut messages {
+        let index = Some(*index);
         let mut content_to_remove = Vec::new();
 
         match message.role {
@@ -176,29 +389,42 @@ fn fix_tool_calling(mut messages: Vec<Message>) -> (Vec<Message>, Vec<String>) {
                     match content {
                         MessageContent::ToolRequest(req) => {
                             content_to_remove.push(idx);
-                            issues.push(format!(
-                                "Removed tool request '{}' from user message",
-                                req.id
+                            issues.push(ConversationIssue::new(
+                                IssueKind::ToolRequestInUserMessage { id: req.id.clone() },
+                                index,
+                                RepairAction::Removed,
                             ));
                         }
                         MessageContent::ToolConfirmationRequest(req) => {
                             content_to_remove.push(idx);
-                            issues.push(format!(
-                                "Removed tool confirmation request '{}' from user message",
-                                req.id
+                            issues.push(ConversationIssue::new(
+                                IssueKind::ToolConfirmationRequestInUserMessage {
+                                    id: req.id.clone(),
+                                },
+                                index,
+                                RepairAction::Removed,
                             ));
                         }
                         MessageContent::Thinking(_) | MessageContent::RedactedThinking(_) => {
                             content_to_remove.push(idx);
-                            issues.push("Removed thinking content from user message".to_string());
+                            issues.push(ConversationIssue::new(
+                                IssueKind::ThinkingInUserMessage,
+                                index,
+                                RepairAction::Removed,
+                            ));
                         }
                         MessageContent::ToolResponse(resp) => {
                             if pending_tool_requests.contains(&resp.id) {
                                 pending_tool_requests.remove(&resp.id);
                             } else {
                                 content_to_remove.push(idx);
-                                issues
-                                    .push(format!("Removed orphaned tool response '{}'", resp.id));
+                                issues.push(ConversationIssue::new(
+                                    IssueKind::OrphanedToolResponse {
+                                        id: resp.id.clone(),
+                                    },
+                                    index,
+                                    RepairAction::Removed,
+                                ));
                             }
                         }
                         _ => {}
@@ -210,16 +436,22 @@ fn fix_tool_calling(mut messages: Vec<Message>) -> (Vec<Message>, Vec<String>) {
                     match content {
                         MessageContent::ToolResponse(resp) => {
                             content_to_remove.push(idx);
-                            issues.push(format!(
-                                "Removed tool response '{}' from assistant message",
-                                resp.id
+                            issues.push(ConversationIssue::new(
+                                IssueKind::ToolResponseInAssistantMessage {
+                                    id: resp.id.clone(),
+                                },
+                                index,
+                                RepairAction::Removed,
                             ));
                         }
                         MessageContent::FrontendToolRequest(req) => {
                             content_to_remove.push(idx);
-                            issues.push(format!(
-                                "Removed frontend tool request '{}' from assistant message",
-                                req.id
+                            issues.push(ConversationIssue::new(
+                                IssueKind::FrontendToolRequestInAssistantMessage {
+                                    id: req.id.clone(),
+                                },
+                                index,
+                                RepairAction::Removed,
                             ));
                         }
                         MessageContent::ToolRequest(req) => {
@@ -236,14 +468,31 @@ fn fix_tool_calling(mut messages: Vec<Message>) -> (Vec<Message>, Vec<String>) {
         }
     }
 
-    for message in &mut messages {
+    let mut repaired: Vec<Indexed> = Vec::with_capacity(messages.len());
+    let mut synthesized: Option<Indexed> = None;
+    for (index, mut message) in messages {
+        if let Some((response_index, response)) = synthesized.take() {
+            // Answer the orphaned requests alongside the tool responses that did come back,
+            // otherwise in a message of their own
+            if message.role == Role::User && has_tool_response(&message) {
+                let mut content = response.content;
+                content.extend(message.content);
+                message.content = content;
+            } else {
+                repaired.push((response_index, response));
+            }
+        }
+
+        let mut orphaned = Vec::new();
         if message.role == Role::Assistant {
             let mut content_to_remove = Vec::new();
             for (idx, content) in message.content.iter().enumerate() {
                 if let MessageContent::ToolRequest(req) = content {
                     if pending_tool_requests.contains(&req.id) {
-                        content_to_remove.push(idx);
-                        issues.push(format!("Removed orphaned tool request '{}'", req.id));
+                        orphaned.push(req.id.clone());
+                        if policy.orphaned_tool_requests == OrphanedRequestRepair::Drop {
+                            content_to_remove.push(idx);
+                        }
                     }
                 }
             }
@@ -251,26 +500,76 @@ fn fix_tool_calling(mut messages: Vec<Message>) -> (Vec<Message>, Vec<String>) {
                 message.content.remove(idx);
             }
         }
+
+        let action = match policy.orphaned_tool_requests {
+            OrphanedRequestRepair::Drop => RepairAction::Removed,
+            OrphanedRequestRepair::SynthesizeErrorResponse => {
+                RepairAction::SynthesizedErrorResponse
+            }
+        };
+        for id in &orphaned {
+            issues.push(ConversationIssue::new(
+                IssueKind::OrphanedToolRequest { id: id.clone() },
+                Some(index),
+                action,
+            ));
+        }
+
+        if action == RepairAction::SynthesizedErrorResponse && !orphaned.is_empty() {
+            let mut response = Message::user();
+            for id in orphaned {
+                response = response.with_tool_response(
+                    id,
+                    Err(ToolError::ExecutionError(
+                        ORPHANED_REQUEST_ERROR.to_string(),
+                    )),
+                );
+            }
+            synthesized = Some((index, response));
+        }
+        repaired.push((index, message));
     }
-    let (messages, empty_removed) = remove_empty_messages(messages);
+    repaired.extend(synthesized);
+
+    let (messages, empty_removed) = remove_empty_messages(repaired);
     issues.extend(empty_removed);
     (messages, issues)
 }
 
-fn merge_consecutive_messages(messages: Vec<Message>) -> (Vec<Message>, Vec<String>) {
+fn merge_consecutive_messages(
+    messages: Vec<Indexed>,
+    policy: &RepairPolicy,
+) -> (Vec<Indexed>, Vec<ConversationIssue>) {
     let mut issues = Vec::new();
-    let mut merged_messages: Vec<Message> = Vec::new();
+    let mut merged_messages: Vec<Indexed> = Vec::new();
 
-    for message in messages {
-        if let Some(last) = merged_messages.last_mut() {
+    for (index, message) in messages {
+        if let Some((_, last)) = merged_messages.last_mut() {
             let effective = effective_role(&message);
             if effective_role(last) == effective {
-                last.content.extend(message.content);
-                issues.push(format!("Merged consecutive {} messages", effective));
+                let action = match policy.consecutive_messages {
+                    ConsecutiveMessageRepair::Merge => {
+                        last.content.extend(message.content);
+                        last.pinned |= message.pinned;
+                        RepairAction::Merged
+                    }
+                    ConsecutiveMessageRepair::Drop => {
+                        // A pinned message is kept over the unpinned one before it
+                        if message.pinned && !last.pinned {
+                            *last = message;
+                        }
+                        RepairAction::Removed
+                    }
+                };
+                issues.push(ConversationIssue::new(
+                    IssueKind::ConsecutiveMessages { role: effective },
+                    Some(index),
+                    action,
+                ));
                 continue;
             }
         }
-        merged_messages.push(message);
+        merged_messages.push((index, message));
     }
 
     (merged_messages, issues)
@@ -294,20 +593,31 @@ fn effective_role(message: &Message) -> String {
     }
 }
 
-fn fix_lead_trail(mut messages: Vec<Message>) -> (Vec<Message>, Vec<String>) {
+fn fix_lead_trail(
+    mut messages: Vec<Indexed>,
+    policy: &RepairPolicy,
+) -> (Vec<Indexed>, Vec<ConversationIssue>) {
     let mut issues = Vec::new();
 
-    if let Some(first) = messages.first() {
+    if let Some((index, first)) = messages.first() {
         if first.role == Role::Assistant {
+            issues.push(ConversationIssue::new(
+                IssueKind::LeadingAssistantMessage,
+                Some(*index),
+                RepairAction::Removed,
+            ));
             messages.remove(0);
-            issues.push("Removed leading assistant message".to_string());
         }
     }
 
-    if let Some(last) = messages.last() {
-        if last.role == Role::Assistant {
+    if let Some((index, last)) = messages.last() {
+        if last.role == Role::Assistant && !policy.keep_trailing_assistant {
+            issues.push(ConversationIssue::new(
+                IssueKind::TrailingAssistantMessage,
+                Some(*index),
+                RepairAction::Removed,
+            ));
             messages.pop();
-            issues.push("Removed trailing assistant message".to_string());
         }
     }
 
@@ -316,12 +626,16 @@ fn fix_lead_trail(mut messages: Vec<Message>) -> (Vec<Message>, Vec<String>) {
 
 const PLACEHOLDER_USER_MESSAGE: &str = "Hello";
 
-fn populate_if_empty(mut messages: Vec<Message>) -> (Vec<Message>, Vec<String>) {
+fn populate_if_empty(mut messages: Vec<Indexed>) -> (Vec<Indexed>, Vec<ConversationIssue>) {
     let mut issues = Vec::new();
 
     if messages.is_empty() {
-        issues.push("Added placeholder user message to empty conversation".to_string());
-        messages.push(Message::user().with_text(PLACEHOLDER_USER_MESSAGE));
+        issues.push(ConversationIssue::new(
+            IssueKind::EmptyConversation,
+            None,
+            RepairAction::AddedPlaceholder,
+        ));
+        messages.push((0, Message::user().with_text(PLACEHOLDER_USER_MESSAGE)));
     }
     (messages, issues)
 }
@@ -361,7 +675,12 @@ pub fn debug_conversation_fix(
 #[cfg(test)]
 mod tests {
     use crate::conversation::message::Message;
-    use crate::conversation::{debug_conversation_fix, fix_conversation, Conversation};
+    use crate::conversation::message::MessageContent;
+    use crate::conversation::{
+        debug_conversation_fix, fix_conversation, repair_conversation, validate_conversation,
+        ConsecutiveMessageRepair, IssueKind, IssueSeverity, OrphanedRequestRepair, RepairAction,
+        RepairPolicy,
+    };
     use mcp_core::tool::ToolCall;
     use rmcp::model::Role;
     use serde_json::json;
@@ -531,4 +850,148 @@ mod tests {
         let (_fixed, issues) = run_verify(messages);
         assert_eq!(issues.len(), 0);
     }
+
+    #[test]
+    fn test_validate_reports_original_positions() {
+        let conversation = Conversation::new_unvalidated(vec![
+            Message::user().with_text("Hello"),
+            Message::assistant().with_text("Hi"),
+            Message::assistant().with_text("there"),
+            Message::user().with_text("Thanks"),
+        ]);
+
+        let issues = validate_conversation(&conversation);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            issues[0].kind,
+            IssueKind::ConsecutiveMessages {
+                role: "assistant".to_string()
+            }
+        );
+        assert_eq!(issues[0].severity, IssueSeverity::Warning);
+        assert_eq!(issues[0].index, Some(2));
+        assert_eq!(issues[0].action, RepairAction::Merged);
+        assert_eq!(conversation.len(), 4);
+    }
+
+    #[test]
+    fn test_synthesize_error_for_orphaned_tool_request() {
+        let conversation = Conversation::new_unvalidated(vec![
+            Message::user().with_text("Search twice"),
+            Message::assistant()
+                .with_tool_request("search_1", Ok(ToolCall::new("search", json!({}))))
+                .with_tool_request("search_2", Ok(ToolCall::new("search", json!({})))),
+            Message::user().with_tool_response("search_2", Ok(vec![])),
+            Message::assistant().with_text("Done"),
+            Message::user().with_text("Thanks"),
+        ]);
+        let policy = RepairPolicy {
+            orphaned_tool_requests: OrphanedRequestRepair::SynthesizeErrorResponse,
+            ..Default::default()
+        };
+
+        let (fixed, issues) = repair_conversation(conversation, &policy);
+
+        assert_eq!(fixed.len(), 5);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            issues[0].kind,
+            IssueKind::OrphanedToolRequest {
+                id: "search_1".to_string()
+            }
+        );
+        assert_eq!(issues[0].severity, IssueSeverity::Error);
+        assert_eq!(issues[0].action, RepairAction::SynthesizedErrorResponse);
+
+        let responses: Vec<_> = fixed.messages()[2]
+            .content
+            .iter()
+            .filter_map(|content| match content {
+                MessageContent::ToolResponse(resp) => {
+                    Some((resp.id.clone(), resp.tool_result.is_err()))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            responses,
+            vec![
+                ("search_1".to_string(), true),
+                ("search_2".to_string(), false)
+            ]
+        );
+        assert!(validate_conversation(&fixed).is_empty());
+    }
+
+    #[test]
+    fn test_drop_consecutive_messages() {
+        let conversation = Conversation::new_unvalidated(vec![
+            Message::user().with_text("first"),
+            Message::user().with_text("second"),
+        ]);
+        let policy = RepairPolicy {
+            consecutive_messages: ConsecutiveMessageRepair::Drop,
+            ..Default::default()
+        };
+
+        let (fixed, issues) = repair_conversation(conversation, &policy);
+
+        assert_eq!(fixed.len(), 1);
+        assert_eq!(fixed.messages()[0].as_concat_text(), "first");
+        assert_eq!(issues[0].to_string(), "Removed consecutive user message");
+    }
+
+    #[test]
+    fn test_drop_consecutive_tool_responses_keeps_tool_pairs() {
+        let conversation = Conversation::new_unvalidated(vec![
+            Message::user().with_text("Search twice"),
+            Message::assistant()
+                .with_tool_request("search_1", Ok(ToolCall::new("search", json!({}))))
+                .with_tool_request("search_2", Ok(ToolCall::new("search", json!({})))),
+            Message::user().with_tool_response("search_1", Ok(vec![])),
+            Message::user().with_tool_response("search_2", Ok(vec![])),
+            Message::assistant().with_text("Done"),
+            Message::user().with_text("Thanks"),
+        ]);
+        let policy = RepairPolicy {
+            consecutive_messages: ConsecutiveMessageRepair::Drop,
+            ..Default::default()
+        };
+
+        let (fixed, issues) = repair_conversation(conversation, &policy);
+
+        assert_eq!(fixed.len(), 5);
+        assert!(issues.iter().any(|issue| issue.kind
+            == IssueKind::OrphanedToolRequest {
+                id: "search_2".to_string()
+            }));
+        let requests: Vec<_> = fixed.messages()[1]
+            .content
+            .iter()
+            .filter_map(|content| match content {
+                MessageContent::ToolRequest(req) => Some(req.id.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(requests, vec!["search_1".to_string()]);
+        assert!(validate_conversation(&fixed).is_empty());
+    }
+
+    #[test]
+    fn test_keep_trailing_assistant() {
+        let conversation = Conversation::new_unvalidated(vec![
+            Message::user().with_text("Hello"),
+            Message::assistant().with_text("Hi there"),
+        ]);
+        let policy = RepairPolicy {
+            keep_trailing_assistant: true,
+            ..Default::default()
+        };
+
+        let (fixed, issues) = repair_conversation(conversation, &policy);
+
+        assert_eq!(fixed.len(), 2);
+        assert!(issues.is_empty());
+    }
 }
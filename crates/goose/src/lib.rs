@@ -12,6 +12,7 @@ pub mod recipe;
 pub mod recipe_deeplink;
 pub mod scheduler;
 pub mod scheduler_factory;
+pub mod scheduler_history;
 pub mod scheduler_trait;
 pub mod session;
 pub mod temporal_scheduler;
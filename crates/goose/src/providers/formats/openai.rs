@@ -525,6 +525,7 @@ where
                         role: Role::Assistant,
                         created: chrono::Utc::now().timestamp(),
                         content: contents,
+                        pinned: false,
                     }),
                     usage,
                 )
@@ -535,6 +536,7 @@ where
                         role: Role::Assistant,
                         created: chrono::Utc::now().timestamp(),
                         content: vec![MessageContent::text(text)],
+                        pinned: false,
                     }),
                     if chunk.choices[0].finish_reason.is_some() {
                         usage
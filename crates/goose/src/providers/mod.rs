@@ -23,7 +23,7 @@ pub mod ollama;
 pub mod openai;
 pub mod openrouter;
 pub mod pricing;
-mod retry;
+pub(crate) mod retry;
 pub mod sagemaker_tgi;
 pub mod snowflake;
 pub mod testprovider;
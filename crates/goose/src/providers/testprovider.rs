@@ -7,7 +7,7 @@ use std::fs;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 
-use super::base::{Provider, ProviderMetadata, ProviderUsage};
+use super::base::{Provider, ProviderMetadata, ProviderUsage, Usage};
 use super::errors::ProviderError;
 use crate::conversation::message::Message;
 use crate::model::ModelConfig;
@@ -159,6 +159,64 @@ impl Provider for TestProvider {
     }
 }
 
+/// Provider that returns a fixed sequence of responses, one per call
+///
+/// Used to test recipes without a model: the script decides which tools get called and what
+/// the final answer is. Running past the end of the script is an error.
+pub struct ScriptedProvider {
+    responses: Mutex<std::vec::IntoIter<Message>>,
+}
+
+impl ScriptedProvider {
+    pub fn new(responses: Vec<Message>) -> Self {
+        Self {
+            responses: Mutex::new(responses.into_iter()),
+        }
+    }
+
+    /// Number of scripted responses that have not been returned yet
+    pub fn remaining(&self) -> usize {
+        self.responses.lock().unwrap().len()
+    }
+}
+
+#[async_trait]
+impl Provider for ScriptedProvider {
+    fn metadata() -> ProviderMetadata {
+        ProviderMetadata::new(
+            "scripted",
+            "Scripted Provider",
+            "Provider for testing that returns scripted responses in order",
+            "scripted-model",
+            vec!["scripted-model"],
+            "",
+            vec![],
+        )
+    }
+
+    async fn complete(
+        &self,
+        _system: &str,
+        _messages: &[Message],
+        _tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        let next = self.responses.lock().unwrap().next();
+        match next {
+            Some(message) => Ok((
+                message,
+                ProviderUsage::new("scripted-model".to_string(), Usage::default()),
+            )),
+            None => Err(ProviderError::ExecutionError(
+                "Scripted provider has no responses left".to_string(),
+            )),
+        }
+    }
+
+    fn get_model_config(&self) -> ModelConfig {
+        ModelConfig::new_or_fail("scripted-model")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -281,4 +339,19 @@ mod tests {
 
         let _ = fs::remove_file(temp_file);
     }
+
+    #[tokio::test]
+    async fn test_scripted_provider_returns_responses_in_order() {
+        let provider = ScriptedProvider::new(vec![
+            Message::assistant().with_text("first"),
+            Message::assistant().with_text("second"),
+        ]);
+
+        let (first, _) = provider.complete("", &[], &[]).await.unwrap();
+        assert_eq!(first.as_concat_text(), "first");
+        assert_eq!(provider.remaining(), 1);
+        let (second, _) = provider.complete("", &[], &[]).await.unwrap();
+        assert_eq!(second.as_concat_text(), "second");
+        assert!(provider.complete("", &[], &[]).await.is_err());
+    }
 }
@@ -1,9 +1,14 @@
+use crate::recipe::compose::{apply_includes, load_recipe_chain, resolve_recipe_reference};
+use crate::recipe::parameter_values::{validate_parameter_definitions, validate_parameter_values};
 use crate::recipe::read_recipe_file_content::RecipeFile;
-use crate::recipe::template_recipe::{parse_recipe_content, render_recipe_content_with_params};
+use crate::recipe::template_recipe::{
+    parse_recipe_content, render_recipe_content_with_params, render_recipe_for_preview,
+};
 use crate::recipe::{
     Recipe, RecipeParameter, RecipeParameterRequirement, BUILT_IN_RECIPE_DIR_PARAM,
 };
 use anyhow::Result;
+use serde_json::Value;
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
@@ -11,6 +16,8 @@ use std::path::Path;
 pub enum RecipeError {
     #[error("Missing required parameters: {parameters:?}")]
     MissingParams { parameters: Vec<String> },
+    #[error("Invalid parameter values:\n  {}", errors.join("\n  "))]
+    InvalidParams { errors: Vec<String> },
     #[error("Template rendering failed: {source}")]
     TemplateRendering { source: anyhow::Error },
     #[error("Recipe parsing failed: {source}")]
@@ -35,10 +42,19 @@ where
         .ok_or_else(|| anyhow::anyhow!("Error getting recipe directory"))?;
     let recipe_parameters = validate_recipe_parameters(&recipe_file_content, recipe_dir_str)?;
 
-    let (params_for_template, missing_params) =
-        apply_values_to_parameters(&params, recipe_parameters, recipe_dir_str, user_prompt_fn)?;
+    let (mut params_for_template, missing_params) = apply_values_to_parameters(
+        &params,
+        recipe_parameters.clone(),
+        recipe_dir_str,
+        user_prompt_fn,
+    )?;
 
     let rendered_content = if missing_params.is_empty() {
+        validate_parameter_values(
+            recipe_parameters.as_deref().unwrap_or_default(),
+            &mut params_for_template,
+        )
+        .map_err(|errors| RecipeError::InvalidParams { errors })?;
         render_recipe_content_with_params(&recipe_file_content, &params_for_template)?
     } else {
         String::new()
@@ -59,6 +75,64 @@ pub fn validate_recipe_parameters(
     Ok(recipe_parameters)
 }
 
+/// Validate the parameters of a recipe and the recipes it extends together, returning the
+/// merged parameter definitions. `chain` is most derived first, see `load_recipe_chain`.
+pub fn validate_chain_parameters(chain: &[RecipeFile]) -> Result<Option<Vec<RecipeParameter>>> {
+    let (parameters, template_variables) = collect_chain_parameters(chain)?;
+    validate_optional_parameters(&parameters)?;
+    validate_parameters_in_template(&parameters, &template_variables)?;
+    validate_parameter_definitions(parameters.as_deref().unwrap_or_default()).map_err(
+        |errors| anyhow::anyhow!("Invalid parameter definitions:\n  {}", errors.join("\n  ")),
+    )?;
+    Ok(parameters)
+}
+
+/// Merge the parameter definitions of a recipe and the recipes it extends, the most derived
+/// definition of a key winning, and collect the template variables used across them
+pub fn collect_chain_parameters(
+    chain: &[RecipeFile],
+) -> Result<(Option<Vec<RecipeParameter>>, HashSet<String>)> {
+    let mut parameters: Option<Vec<RecipeParameter>> = None;
+    let mut template_variables = HashSet::new();
+    for recipe_file in chain {
+        let (raw_recipe, variables) = parse_recipe_content(
+            &recipe_file.content,
+            recipe_file.parent_dir.to_string_lossy().to_string(),
+        )?;
+        template_variables.extend(variables);
+        for parameter in raw_recipe.parameters.into_iter().flatten() {
+            let merged = parameters.get_or_insert_with(Vec::new);
+            if !merged.iter().any(|p| p.key == parameter.key) {
+                merged.push(parameter);
+            }
+        }
+    }
+    Ok((parameters, template_variables))
+}
+
+/// Render a recipe and the recipes it extends for validation, deeplinks and explain, keeping
+/// parameters without a value as they are
+pub fn render_recipe_chain_for_preview(
+    chain: &[RecipeFile],
+    params: &HashMap<String, String>,
+) -> Result<Recipe> {
+    let mut recipe: Option<Recipe> = None;
+    for recipe_file in chain.iter().rev() {
+        let recipe_dir = recipe_file.parent_dir.to_string_lossy().to_string();
+        let mut file_params = params.clone();
+        if file_params.contains_key(BUILT_IN_RECIPE_DIR_PARAM) {
+            file_params.insert(BUILT_IN_RECIPE_DIR_PARAM.to_string(), recipe_dir.clone());
+        }
+        let mut layer = render_recipe_for_preview(&recipe_file.content, recipe_dir, &file_params)?;
+        resolve_layer_paths(&mut layer, &recipe_file.parent_dir)?;
+        recipe = Some(match recipe {
+            Some(base) => layer.extend(base),
+            None => layer,
+        });
+    }
+    recipe.ok_or_else(|| anyhow::anyhow!("No recipe to render"))
+}
+
 pub fn build_recipe_from_template<F>(
     recipe_file: RecipeFile,
     params: Vec<(String, String)>,
@@ -67,30 +141,163 @@ pub fn build_recipe_from_template<F>(
 where
     F: Fn(&str, &str) -> Result<String, anyhow::Error>,
 {
-    let recipe_parent_dir = recipe_file.parent_dir.clone();
-    let (rendered_content, missing_params) =
-        render_recipe_template(recipe_file, params.clone(), user_prompt_fn)
-            .map_err(|source| RecipeError::TemplateRendering { source })?;
+    build_recipe(recipe_file, params, user_prompt_fn, &HashSet::new())
+}
+
+/// Build a recipe to check it before the values of its `deferred` parameters are known, such
+/// as parameters filled in by a schedule trigger. Deferred parameters are rendered empty and
+/// their values aren't validated.
+pub fn build_recipe_with_deferred_params(
+    recipe_file: RecipeFile,
+    params: Vec<(String, String)>,
+    deferred: &HashSet<String>,
+) -> Result<Recipe, RecipeError> {
+    build_recipe(
+        recipe_file,
+        params,
+        None::<fn(&str, &str) -> Result<String>>,
+        deferred,
+    )
+}
+
+fn build_recipe<F>(
+    recipe_file: RecipeFile,
+    params: Vec<(String, String)>,
+    user_prompt_fn: Option<F>,
+    deferred: &HashSet<String>,
+) -> Result<Recipe, RecipeError>
+where
+    F: Fn(&str, &str) -> Result<String, anyhow::Error>,
+{
+    let recipe_dir_str = recipe_file.parent_dir.to_string_lossy().to_string();
+    let chain =
+        load_recipe_chain(recipe_file).map_err(|source| RecipeError::RecipeParsing { source })?;
+    let recipe_parameters = validate_chain_parameters(&chain)
+        .map_err(|source| RecipeError::TemplateRendering { source })?;
+    let (mut params_for_template, mut missing_params) = apply_values_to_parameters(
+        &params,
+        recipe_parameters.clone(),
+        &recipe_dir_str,
+        user_prompt_fn,
+    )
+    .map_err(|source| RecipeError::TemplateRendering { source })?;
+    missing_params.retain(|key| !deferred.contains(key));
 
     if !missing_params.is_empty() {
         return Err(RecipeError::MissingParams {
             parameters: missing_params,
         });
     }
+    let validated_parameters: Vec<RecipeParameter> = recipe_parameters
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|parameter| !deferred.contains(&parameter.key))
+        .collect();
+    validate_parameter_values(&validated_parameters, &mut params_for_template)
+        .map_err(|errors| RecipeError::InvalidParams { errors })?;
+    for key in deferred {
+        params_for_template.insert(key.clone(), String::new());
+    }
+
+    let placeholders = replace_unsafe_values(&mut params_for_template);
+
+    // Render the most general recipe first so each recipe can be layered on top of its base
+    let mut recipe: Option<Recipe> = None;
+    for recipe_file in chain.iter().rev() {
+        let mut file_params = params_for_template.clone();
+        file_params.insert(
+            BUILT_IN_RECIPE_DIR_PARAM.to_string(),
+            recipe_file.parent_dir.to_string_lossy().to_string(),
+        );
+        let rendered_content =
+            render_recipe_content_with_params(&recipe_file.content, &file_params)
+                .map_err(|source| RecipeError::TemplateRendering { source })?;
+        let mut layer = Recipe::from_content(&rendered_content)
+            .map_err(|source| RecipeError::RecipeParsing { source })?;
+        resolve_layer_paths(&mut layer, &recipe_file.parent_dir)
+            .map_err(|source| RecipeError::RecipeParsing { source })?;
+        recipe = Some(match recipe {
+            Some(base) => layer.extend(base),
+            None => layer,
+        });
+    }
 
-    let mut recipe = Recipe::from_content(&rendered_content)
-        .map_err(|source| RecipeError::RecipeParsing { source })?;
+    let recipe = recipe.ok_or_else(|| RecipeError::RecipeParsing {
+        source: anyhow::anyhow!("No recipe to build"),
+    })?;
+    restore_unsafe_values(recipe, &placeholders)
+        .map_err(|source| RecipeError::RecipeParsing { source })
+}
+
+/// Replace values that would change the structure of the YAML they are rendered into, such as
+/// multi-line text or text with quotes, by placeholders, returning the placeholders and values
+fn replace_unsafe_values(params: &mut HashMap<String, String>) -> Vec<(String, String)> {
+    let is_safe = |value: &str| {
+        value
+            .chars()
+            .all(|c| c.is_alphanumeric() || " _-./~+=()".contains(c))
+    };
+    let mut placeholders = Vec::new();
+    for (key, value) in params.iter_mut() {
+        if key == BUILT_IN_RECIPE_DIR_PARAM || is_safe(value) {
+            continue;
+        }
+        let placeholder = format!("goose-param-{}", uuid::Uuid::new_v4().simple());
+        placeholders.push((placeholder.clone(), std::mem::replace(value, placeholder)));
+    }
+    placeholders
+}
+
+/// Put the values replaced by `replace_unsafe_values` into the strings of the parsed recipe
+fn restore_unsafe_values(recipe: Recipe, placeholders: &[(String, String)]) -> Result<Recipe> {
+    fn restore(value: &mut Value, placeholders: &[(String, String)]) {
+        match value {
+            Value::String(text) => {
+                for (placeholder, original) in placeholders {
+                    if text.contains(placeholder.as_str()) {
+                        *text = text.replace(placeholder.as_str(), original);
+                    }
+                }
+            }
+            Value::Array(items) => items
+                .iter_mut()
+                .for_each(|item| restore(item, placeholders)),
+            Value::Object(fields) => fields
+                .values_mut()
+                .for_each(|field| restore(field, placeholders)),
+            _ => {}
+        }
+    }
+
+    if placeholders.is_empty() {
+        return Ok(recipe);
+    }
+    let mut value = serde_json::to_value(recipe)?;
+    restore(&mut value, placeholders);
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Resolve paths in one recipe of a chain relative to the directory of its file
+fn resolve_layer_paths(recipe: &mut Recipe, recipe_parent_dir: &Path) -> Result<()> {
+    apply_includes(recipe, recipe_parent_dir)?;
 
     if let Some(ref mut sub_recipes) = recipe.sub_recipes {
         for sub_recipe in sub_recipes {
-            if let Ok(resolved_path) = resolve_sub_recipe_path(&sub_recipe.path, &recipe_parent_dir)
+            if let Ok(resolved_path) = resolve_sub_recipe_path(&sub_recipe.path, recipe_parent_dir)
             {
                 sub_recipe.path = resolved_path;
             }
         }
     }
 
-    Ok(recipe)
+    for step in recipe.steps.iter_mut().flatten() {
+        if let Some(ref mut step_recipe) = step.recipe {
+            if let Ok(resolved_path) = resolve_sub_recipe_path(step_recipe, recipe_parent_dir) {
+                *step_recipe = resolved_path;
+            }
+        }
+    }
+    Ok(())
 }
 
 fn validate_parameters_in_template(
@@ -201,19 +408,13 @@ fn resolve_sub_recipe_path(
     sub_recipe_path: &str,
     parent_recipe_dir: &Path,
 ) -> Result<String, RecipeError> {
-    let path = if Path::new(sub_recipe_path).is_absolute() {
-        sub_recipe_path.to_string()
-    } else {
-        parent_recipe_dir
-            .join(sub_recipe_path)
-            .to_str()
-            .ok_or_else(|| RecipeError::RecipeParsing {
-                source: anyhow::anyhow!("Invalid sub-recipe path: {}", sub_recipe_path),
-            })?
-            .to_string()
-    };
-
-    Ok(path)
+    let path = resolve_recipe_reference(sub_recipe_path, parent_recipe_dir)
+        .map_err(|source| RecipeError::RecipeParsing { source })?;
+    path.to_str()
+        .map(|path| path.to_string())
+        .ok_or_else(|| RecipeError::RecipeParsing {
+            source: anyhow::anyhow!("Invalid sub-recipe path: {}", sub_recipe_path),
+        })
 }
 
 #[cfg(test)]
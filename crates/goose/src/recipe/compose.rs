@@ -0,0 +1,331 @@
+//! Recipe inheritance with `extends` and instruction fragments with `include`
+//!
+//! A recipe can extend another recipe by path or name. The recipes are rendered one by one,
+//! most general first, and each one is layered on top of the one it extends:
+//!
+//! * `instructions`, `context` and `artifacts` are appended to those of the extended recipe
+//! * `extensions`, `parameters` and `sub_recipes` are merged by name; entries of the extending
+//!   recipe replace entries with the same name
+//! * each field of `settings` overrides the same field of the extended recipe
+//! * every other field is taken from the extending recipe when it is set
+//!
+//! `include` lists files, relative to the recipe, whose content is put in front of the
+//! recipe's own instructions.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+
+use crate::recipe::read_recipe_file_content::{read_recipe_file, RecipeFile};
+use crate::recipe::registry::resolve_locked_recipe;
+use crate::recipe::template_recipe::parse_recipe_content;
+use crate::recipe::{Recipe, Settings};
+
+pub const RECIPE_PATH_ENV_VAR: &str = "GOOSE_RECIPE_PATH";
+const RECIPE_FILE_EXTENSIONS: [&str; 3] = ["yaml", "yml", "json"];
+
+/// Resolve a reference to another recipe from a recipe in `recipe_dir`.
+///
+/// Paths are relative to `recipe_dir`. A bare name such as `base` is looked up as
+/// `base.yaml`, `base.yml` or `base.json` in `recipe_dir`, then as the version pinned in the
+/// project's `goose-recipes.lock`, and then in the `GOOSE_RECIPE_PATH` directories.
+/// Unresolved references are returned relative to `recipe_dir`. Fails when the pinned version
+/// doesn't match the lockfile.
+pub fn resolve_recipe_reference(reference: &str, recipe_dir: &Path) -> Result<PathBuf> {
+    let path = Path::new(reference);
+    if path.is_absolute() || reference.starts_with('~') {
+        return Ok(path.to_path_buf());
+    }
+    let relative = recipe_dir.join(path);
+    if relative.exists() || path.extension().is_some() || path.components().count() > 1 {
+        return Ok(relative);
+    }
+
+    if let Some(path) = find_recipe_in_dir(reference, recipe_dir) {
+        return Ok(path);
+    }
+    if let Some(path) = resolve_locked_recipe(reference, recipe_dir)? {
+        return Ok(path);
+    }
+    Ok(recipe_path_dirs()
+        .iter()
+        .find_map(|dir| find_recipe_in_dir(reference, dir))
+        .unwrap_or(relative))
+}
+
+/// The directories listed in `GOOSE_RECIPE_PATH`, in order
+pub fn recipe_path_dirs() -> Vec<PathBuf> {
+    std::env::var_os(RECIPE_PATH_ENV_VAR)
+        .map(|recipe_path| std::env::split_paths(&recipe_path).collect())
+        .unwrap_or_default()
+}
+
+fn find_recipe_in_dir(name: &str, dir: &Path) -> Option<PathBuf> {
+    RECIPE_FILE_EXTENSIONS
+        .iter()
+        .map(|ext| dir.join(format!("{}.{}", name, ext)))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Load a recipe file and the files it extends, most derived first
+pub fn load_recipe_chain(recipe_file: RecipeFile) -> Result<Vec<RecipeFile>> {
+    let mut chain = vec![recipe_file];
+    let mut seen: HashSet<PathBuf> = chain.iter().map(|file| file.file_path.clone()).collect();
+
+    loop {
+        let current = chain.last().expect("chain is never empty");
+        // Recipes that don't parse are reported by parameter validation, which parses them again
+        let Ok((raw_recipe, _)) = parse_recipe_content(
+            &current.content,
+            current.parent_dir.to_string_lossy().to_string(),
+        ) else {
+            return Ok(chain);
+        };
+        let Some(extends) = raw_recipe.extends else {
+            return Ok(chain);
+        };
+
+        let base = resolve_recipe_reference(&extends, &current.parent_dir)
+            .and_then(read_recipe_file)
+            .map_err(|e| {
+                anyhow!(
+                    "Recipe {} extends '{}', which could not be loaded: {}",
+                    current.file_path.display(),
+                    extends,
+                    e
+                )
+            })?;
+        if !seen.insert(base.file_path.clone()) {
+            let cycle = chain
+                .iter()
+                .map(|file| file.file_path.display().to_string())
+                .chain(std::iter::once(base.file_path.display().to_string()))
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            return Err(anyhow!("Recipe extends cycle: {}", cycle));
+        }
+        chain.push(base);
+    }
+}
+
+/// Put the content of the recipe's `include` files in front of its instructions
+pub fn apply_includes(recipe: &mut Recipe, recipe_dir: &Path) -> Result<()> {
+    let Some(include) = recipe.include.take() else {
+        return Ok(());
+    };
+
+    let mut sections = Vec::new();
+    for fragment in &include {
+        let path = resolve_fragment_path(fragment, recipe_dir);
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow!("Failed to read included file {}: {}", path.display(), e))?;
+        sections.push(content.trim_end().to_string());
+    }
+    sections.extend(recipe.instructions.take());
+    recipe.instructions = Some(sections.join("\n\n"));
+    Ok(())
+}
+
+pub(crate) fn resolve_fragment_path(fragment: &str, recipe_dir: &Path) -> PathBuf {
+    if let Some(stripped) = fragment.strip_prefix("~/") {
+        if let Some(home_dir) = dirs::home_dir() {
+            return home_dir.join(stripped);
+        }
+    }
+    recipe_dir.join(fragment)
+}
+
+impl Recipe {
+    /// Layer this recipe on top of `base`, the recipe it extends
+    pub fn extend(self, base: Recipe) -> Recipe {
+        Recipe {
+            version: self.version,
+            title: self.title,
+            description: self.description,
+            instructions: concat_text(base.instructions, self.instructions),
+            prompt: self.prompt.or(base.prompt),
+            extensions: merge_by_name(base.extensions, self.extensions, |extension| {
+                extension.name()
+            }),
+            context: concat_lists(base.context, self.context),
+            settings: merge_settings(base.settings, self.settings),
+            activities: self.activities.or(base.activities),
+            author: self.author.or(base.author),
+            parameters: merge_by_name(base.parameters, self.parameters, |parameter| {
+                parameter.key.clone()
+            }),
+            response: self.response.or(base.response),
+            sub_recipes: merge_by_name(base.sub_recipes, self.sub_recipes, |sub_recipe| {
+                sub_recipe.name.clone()
+            }),
+            retry: self.retry.or(base.retry),
+            pinned: self.pinned.or(base.pinned),
+            steps: self.steps.or(base.steps),
+            extends: None,
+            include: None,
+            artifacts: concat_lists(base.artifacts, self.artifacts),
+        }
+    }
+}
+
+fn concat_text(base: Option<String>, derived: Option<String>) -> Option<String> {
+    match (base, derived) {
+        (Some(base), Some(derived)) => Some(format!("{}\n\n{}", base.trim_end(), derived)),
+        (base, derived) => derived.or(base),
+    }
+}
+
+fn concat_lists<T>(base: Option<Vec<T>>, derived: Option<Vec<T>>) -> Option<Vec<T>> {
+    match (base, derived) {
+        (Some(mut base), Some(derived)) => {
+            base.extend(derived);
+            Some(base)
+        }
+        (base, derived) => derived.or(base),
+    }
+}
+
+/// Entries of `base` in order, replaced by the entry of `derived` with the same name, followed
+/// by the new entries of `derived`
+fn merge_by_name<T, F>(base: Option<Vec<T>>, derived: Option<Vec<T>>, name: F) -> Option<Vec<T>>
+where
+    F: Fn(&T) -> String,
+{
+    let (base, derived) = match (base, derived) {
+        (Some(base), Some(derived)) => (base, derived),
+        (base, derived) => return derived.or(base),
+    };
+
+    let mut derived: Vec<Option<T>> = derived.into_iter().map(Some).collect();
+    let mut merged = Vec::with_capacity(base.len() + derived.len());
+    for item in base {
+        let item_name = name(&item);
+        let replacement = derived
+            .iter_mut()
+            .find(|candidate| candidate.as_ref().is_some_and(|c| name(c) == item_name))
+            .and_then(Option::take);
+        merged.push(replacement.unwrap_or(item));
+    }
+    merged.extend(derived.into_iter().flatten());
+    Some(merged)
+}
+
+fn merge_settings(base: Option<Settings>, derived: Option<Settings>) -> Option<Settings> {
+    match (base, derived) {
+        (Some(base), Some(derived)) => Some(Settings {
+            goose_provider: derived.goose_provider.or(base.goose_provider),
+            goose_model: derived.goose_model.or(base.goose_model),
+            temperature: derived.temperature.or(base.temperature),
+            compaction_strategy: derived.compaction_strategy.or(base.compaction_strategy),
+        }),
+        (base, derived) => derived.or(base),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &TempDir, name: &str, content: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_extend_merges_fields() {
+        let base = Recipe::from_content(
+            r#"
+title: Base
+description: Team defaults
+instructions: Follow the team style guide.
+extensions:
+  - type: builtin
+    name: developer
+    timeout: 300
+  - type: builtin
+    name: memory
+settings:
+  goose_provider: anthropic
+  temperature: 0.2
+"#,
+        )
+        .unwrap();
+        let derived = Recipe::from_content(
+            r#"
+title: Reviewer
+description: Reviews pull requests
+instructions: Review the diff.
+prompt: Review my changes
+extensions:
+  - type: builtin
+    name: developer
+    timeout: 600
+  - type: builtin
+    name: computercontroller
+settings:
+  temperature: 0.0
+"#,
+        )
+        .unwrap();
+
+        let recipe = derived.extend(base);
+        assert_eq!(recipe.title, "Reviewer");
+        assert_eq!(
+            recipe.instructions.as_deref(),
+            Some("Follow the team style guide.\n\nReview the diff.")
+        );
+        let extensions = recipe.extensions.unwrap();
+        let names: Vec<String> = extensions.iter().map(|e| e.name()).collect();
+        assert_eq!(names, vec!["developer", "memory", "computercontroller"]);
+        assert!(serde_json::to_string(&extensions[0])
+            .unwrap()
+            .contains("600"));
+        let settings = recipe.settings.unwrap();
+        assert_eq!(settings.goose_provider.as_deref(), Some("anthropic"));
+        assert_eq!(settings.temperature, Some(0.0));
+    }
+
+    #[test]
+    fn test_load_recipe_chain_reports_cycles() {
+        let dir = TempDir::new().unwrap();
+        write(
+            &dir,
+            "base.yaml",
+            "title: Base\ndescription: Base\ninstructions: Base\nextends: child.yaml\n",
+        );
+        let child = write(
+            &dir,
+            "child.yaml",
+            "title: Child\ndescription: Child\ninstructions: Child\nextends: base\n",
+        );
+
+        let err = load_recipe_chain(read_recipe_file(&child).unwrap()).unwrap_err();
+        let message = err.to_string();
+        assert!(message.starts_with("Recipe extends cycle:"), "{}", message);
+        assert!(message.contains("child.yaml -> "));
+        assert!(message.ends_with("child.yaml"));
+    }
+
+    #[test]
+    fn test_apply_includes() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, "preamble.md", "Be concise.\n");
+        let mut recipe = Recipe::builder()
+            .title("Included")
+            .description("Uses a fragment")
+            .instructions("Summarize the file.")
+            .include(vec!["preamble.md".to_string()])
+            .build()
+            .unwrap();
+
+        apply_includes(&mut recipe, dir.path()).unwrap();
+        assert_eq!(
+            recipe.instructions.as_deref(),
+            Some("Be concise.\n\nSummarize the file.")
+        );
+        assert!(recipe.include.is_none());
+    }
+}
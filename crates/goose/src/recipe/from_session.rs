@@ -0,0 +1,437 @@
+//! Turning a conversation into a reusable recipe
+//!
+//! The model writes the instructions and proposes the values that would change from one run
+//! to the next. The helpers here replace those values, and any URLs, ticket ids and file paths
+//! left in the text, with typed parameters defaulting to the original value. They also keep
+//! only the extensions whose tools were called and propose success checks from the
+//! verification commands run at the end of the session.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::NaiveDate;
+use regex::{Captures, NoExpand, Regex};
+use serde::Deserialize;
+
+use crate::agents::extension::ExtensionConfig;
+use crate::agents::extension_manager::normalize;
+use crate::agents::types::SuccessCheck;
+use crate::conversation::message::Message;
+use crate::recipe::parameter_values::coerce_parameter_value;
+use crate::recipe::{RecipeParameter, RecipeParameterInputType, RecipeParameterRequirement};
+
+/// Values shorter than this are too likely to appear by accident to be replaced
+const MIN_PARAMETER_VALUE_LEN: usize = 3;
+/// Most success checks proposed for a recipe
+const MAX_SUCCESS_CHECKS: usize = 3;
+
+/// A value the model found to vary between runs
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProposedParameter {
+    pub key: String,
+    #[serde(default)]
+    pub input_type: Option<RecipeParameterInputType>,
+    #[serde(default)]
+    pub description: String,
+    /// The value used in this session, which becomes the default
+    pub value: String,
+}
+
+/// Replace the values of `proposed` and the specifics detected in `text` with parameters
+pub fn parameterize(
+    text: &str,
+    proposed: Vec<ProposedParameter>,
+) -> (String, Vec<RecipeParameter>) {
+    let mut text = text.to_string();
+    let mut parameters: Vec<RecipeParameter> = Vec::new();
+
+    for proposal in proposed {
+        let key = parameter_key(&proposal.key);
+        if key.is_empty() || parameters.iter().any(|p| p.key == key) {
+            continue;
+        }
+        let placeholder = format!("{{{{ {} }}}}", key);
+        let already_templated = Regex::new(&format!(r"\{{\{{\s*{}\s*\}}\}}", regex::escape(&key)))
+            .map(|re| re.is_match(&text))
+            .unwrap_or(false);
+        let replaced = (proposal.value.len() >= MIN_PARAMETER_VALUE_LEN)
+            .then(|| replace_value(&text, &proposal.value, &placeholder))
+            .flatten();
+        match replaced {
+            Some(replaced) => text = replaced,
+            None if already_templated => {}
+            None => continue,
+        }
+        parameters.push(typed_parameter(
+            key,
+            proposal.input_type,
+            proposal.description,
+            proposal.value,
+        ));
+    }
+
+    // Replace the matches themselves, so a value that is the start of a longer one, like a
+    // URL and a page under it, doesn't take part of the longer value with it
+    for (hint, description, pattern) in detectors() {
+        let mut keys: HashMap<String, String> = HashMap::new();
+        text = pattern
+            .replace_all(&text, |caps: &Captures| {
+                let matched = &caps[0];
+                let value = matched.trim_end_matches(['.', ',', ';', ':']);
+                if value.contains("{{") {
+                    return matched.to_string();
+                }
+                let key = keys.entry(value.to_string()).or_insert_with(|| {
+                    let key = unique_key(hint, &parameters);
+                    parameters.push(typed_parameter(
+                        key.clone(),
+                        None,
+                        description.to_string(),
+                        value.to_string(),
+                    ));
+                    key
+                });
+                format!("{{{{ {} }}}}{}", key, &matched[value.len()..])
+            })
+            .into_owned();
+    }
+
+    (text, parameters)
+}
+
+/// Replace whole occurrences of `value` in `text`, so a value is never replaced inside a
+/// longer word, or `None` when there are none
+fn replace_value(text: &str, value: &str, replacement: &str) -> Option<String> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let start = if value.starts_with(is_word) {
+        r"\b"
+    } else {
+        ""
+    };
+    let end = if value.ends_with(is_word) { r"\b" } else { "" };
+    let re = Regex::new(&format!("{}{}{}", start, regex::escape(value), end)).ok()?;
+    re.is_match(text)
+        .then(|| re.replace_all(text, NoExpand(replacement)).into_owned())
+}
+
+fn detectors() -> Vec<(&'static str, &'static str, Regex)> {
+    vec![
+        (
+            "url",
+            "URL to work with",
+            Regex::new(r#"https?://[^\s)\]'"<>`]+"#).expect("valid regex"),
+        ),
+        (
+            "ticket_id",
+            "Ticket to work on",
+            Regex::new(r"\b[A-Z][A-Z0-9]+-\d+\b").expect("valid regex"),
+        ),
+        (
+            "file_path",
+            "File to work with",
+            Regex::new(r"(?:~|\.{1,2})?/?(?:[\w.-]+/)+[\w-]+\.\w+").expect("valid regex"),
+        ),
+    ]
+}
+
+fn parameter_key(raw: &str) -> String {
+    let key: String = raw
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let key = key.trim_matches('_').to_string();
+    if key.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("param_{}", key)
+    } else {
+        key
+    }
+}
+
+fn unique_key(hint: &str, parameters: &[RecipeParameter]) -> String {
+    let taken = |key: &str| parameters.iter().any(|p| p.key == key);
+    if !taken(hint) {
+        return hint.to_string();
+    }
+    (2..)
+        .map(|n| format!("{}_{}", hint, n))
+        .find(|key| !taken(key))
+        .expect("an unused key")
+}
+
+fn typed_parameter(
+    key: String,
+    input_type: Option<RecipeParameterInputType>,
+    description: String,
+    value: String,
+) -> RecipeParameter {
+    let mut parameter = RecipeParameter {
+        key,
+        input_type: input_type.unwrap_or_else(|| infer_input_type(&value)),
+        requirement: RecipeParameterRequirement::Optional,
+        description,
+        default: None,
+        options: None,
+        min: None,
+        max: None,
+        pattern: None,
+        max_size: None,
+        inline: None,
+    };
+    // Select needs options and file defaults must exist wherever the recipe runs
+    if matches!(
+        parameter.input_type,
+        RecipeParameterInputType::Select | RecipeParameterInputType::File
+    ) {
+        parameter.input_type = RecipeParameterInputType::String;
+    }
+    let default = match coerce_parameter_value(&parameter, &value) {
+        Ok(coerced) => coerced,
+        Err(_) => {
+            parameter.input_type = RecipeParameterInputType::String;
+            value
+        }
+    };
+    if parameter.description.trim().is_empty() {
+        parameter.description = format!("Value for {}", parameter.key);
+    }
+    parameter.default = Some(default);
+    parameter
+}
+
+fn infer_input_type(value: &str) -> RecipeParameterInputType {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false") {
+        RecipeParameterInputType::Boolean
+    } else if value.parse::<f64>().is_ok_and(|n| n.is_finite()) {
+        RecipeParameterInputType::Number
+    } else if NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok() {
+        RecipeParameterInputType::Date
+    } else {
+        RecipeParameterInputType::String
+    }
+}
+
+/// The extensions in `available` whose tools were called in `messages`
+pub fn used_extensions(
+    messages: &[Message],
+    available: &[ExtensionConfig],
+) -> Vec<ExtensionConfig> {
+    let prefixes: HashSet<String> = tool_calls(messages)
+        .filter_map(|(name, _, _)| name.split_once("__").map(|(prefix, _)| prefix.to_string()))
+        .collect();
+    available
+        .iter()
+        .filter(|extension| prefixes.contains(&normalize(extension.name())))
+        .cloned()
+        .collect()
+}
+
+/// Propose success checks from the verification commands that passed after the last edit
+pub fn propose_success_checks(messages: &[Message]) -> Vec<SuccessCheck> {
+    let verification = Regex::new(
+        r"\b(test|tests|check|lint|clippy|build|verify|pytest|jest|vitest|rspec|curl)\b",
+    )
+    .expect("valid regex");
+    let calls: Vec<(String, serde_json::Value, bool)> = tool_calls(messages)
+        .map(|(name, arguments, succeeded)| (name.to_string(), arguments.clone(), succeeded))
+        .collect();
+    let last_edit = calls.iter().rposition(|(name, arguments, _)| {
+        name.ends_with("__text_editor")
+            && arguments
+                .get("command")
+                .and_then(|c| c.as_str())
+                .is_some_and(|c| c != "view")
+    });
+
+    let mut commands: Vec<String> = Vec::new();
+    for (name, arguments, succeeded) in &calls[last_edit.map_or(0, |i| i + 1)..] {
+        let Some(command) = arguments.get("command").and_then(|c| c.as_str()) else {
+            continue;
+        };
+        if *succeeded
+            && name.ends_with("__shell")
+            && verification.is_match(command)
+            && !commands.iter().any(|c| c == command)
+        {
+            commands.push(command.to_string());
+        }
+    }
+    let skip = commands.len().saturating_sub(MAX_SUCCESS_CHECKS);
+    commands
+        .into_iter()
+        .skip(skip)
+        .map(|command| SuccessCheck::Shell { command })
+        .collect()
+}
+
+/// Tool calls in `messages` with their arguments and whether their response succeeded
+fn tool_calls(messages: &[Message]) -> impl Iterator<Item = (&str, &serde_json::Value, bool)> {
+    let failed: HashSet<&str> = messages
+        .iter()
+        .flat_map(|message| message.content.iter())
+        .filter_map(|content| content.as_tool_response())
+        .filter(|response| response.tool_result.is_err())
+        .map(|response| response.id.as_str())
+        .collect();
+    messages
+        .iter()
+        .flat_map(|message| message.content.iter())
+        .filter_map(|content| content.as_tool_request())
+        .filter_map(move |request| {
+            let call = request.tool_call.as_ref().ok()?;
+            Some((
+                call.name.as_str(),
+                &call.arguments,
+                !failed.contains(request.id.as_str()),
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_core::tool::ToolCall;
+    use rmcp::model::Content;
+    use serde_json::json;
+
+    fn call(id: &str, name: &str, arguments: serde_json::Value) -> [Message; 2] {
+        [
+            Message::assistant().with_tool_request(
+                id,
+                Ok(ToolCall {
+                    name: name.to_string(),
+                    arguments,
+                }),
+            ),
+            Message::user().with_tool_response(id, Ok(vec![Content::text("ok")])),
+        ]
+    }
+
+    #[test]
+    fn test_parameterize() {
+        let proposed = vec![ProposedParameter {
+            key: "Repository".to_string(),
+            input_type: None,
+            description: "Repository to triage".to_string(),
+            value: "block/goose".to_string(),
+        }];
+        let (text, parameters) = parameterize(
+            "Triage block/goose issues, starting with GOOSE-123 from https://example.com/board.",
+            proposed,
+        );
+
+        assert_eq!(
+            text,
+            "Triage {{ repository }} issues, starting with {{ ticket_id }} from {{ url }}."
+        );
+        let keys: Vec<&str> = parameters.iter().map(|p| p.key.as_str()).collect();
+        assert_eq!(keys, vec!["repository", "url", "ticket_id"]);
+        assert_eq!(parameters[0].default.as_deref(), Some("block/goose"));
+        assert_eq!(
+            parameters[1].default.as_deref(),
+            Some("https://example.com/board")
+        );
+    }
+
+    #[test]
+    fn test_parameterize_matches_whole_words() {
+        let proposed = vec![ProposedParameter {
+            key: "animal".to_string(),
+            input_type: None,
+            description: "Animal to describe".to_string(),
+            value: "goose".to_string(),
+        }];
+        let (text, parameters) = parameterize("Describe a goose, not a mongoose.", proposed);
+
+        assert_eq!(text, "Describe a {{ animal }}, not a mongoose.");
+        assert_eq!(parameters.len(), 1);
+
+        let (text, parameters) = parameterize(
+            "Read https://example.com/a and https://example.com/a/b",
+            Vec::new(),
+        );
+        assert_eq!(text, "Read {{ url }} and {{ url_2 }}");
+        assert_eq!(
+            parameters[1].default.as_deref(),
+            Some("https://example.com/a/b")
+        );
+    }
+
+    #[test]
+    fn test_typed_parameter_defaults() {
+        let count = typed_parameter("count".to_string(), None, String::new(), "12".to_string());
+        assert!(matches!(count.input_type, RecipeParameterInputType::Number));
+        assert_eq!(count.description, "Value for count");
+
+        let wrong = typed_parameter(
+            "since".to_string(),
+            Some(RecipeParameterInputType::Date),
+            "Start date".to_string(),
+            "last week".to_string(),
+        );
+        assert!(matches!(wrong.input_type, RecipeParameterInputType::String));
+        assert_eq!(wrong.default.as_deref(), Some("last week"));
+    }
+
+    #[test]
+    fn test_used_extensions_and_checks() {
+        let mut messages = Vec::new();
+        messages.extend(call(
+            "1",
+            "developer__shell",
+            json!({"command": "cargo test"}),
+        ));
+        messages.extend(call(
+            "2",
+            "developer__text_editor",
+            json!({"command": "write", "path": "src/lib.rs"}),
+        ));
+        messages.extend(call("3", "developer__shell", json!({"command": "ls"})));
+        messages.extend(call(
+            "4",
+            "developer__shell",
+            json!({"command": "cargo clippy -- -D warnings"}),
+        ));
+        messages.extend(call(
+            "5",
+            "developer__shell",
+            json!({"command": "cargo test"}),
+        ));
+
+        let available = vec![
+            ExtensionConfig::Builtin {
+                name: "developer".to_string(),
+                display_name: None,
+                description: None,
+                timeout: None,
+                bundled: None,
+            },
+            ExtensionConfig::Builtin {
+                name: "memory".to_string(),
+                display_name: None,
+                description: None,
+                timeout: None,
+                bundled: None,
+            },
+        ];
+        let used = used_extensions(&messages, &available);
+        assert_eq!(used.len(), 1);
+        assert_eq!(used[0].name(), "developer");
+
+        let checks = propose_success_checks(&messages);
+        let commands: Vec<String> = checks
+            .iter()
+            .filter_map(|check| match check {
+                SuccessCheck::Shell { command } => Some(command.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(commands, vec!["cargo clippy -- -D warnings", "cargo test"]);
+    }
+}
@@ -0,0 +1,532 @@
+//! Static checks run by `goose recipe validate`
+//!
+//! Linting goes further than building a recipe: it follows sub-recipes and step recipes,
+//! checks the values passed to them against the parameters they declare, looks up stdio
+//! extension commands on `PATH`, checks builtin extension names and validates response schemas
+//! and retry checks. Every problem found is collected so the whole report can be fed to CI.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::agents::extension::ExtensionConfig;
+use crate::agents::types::SuccessCheck;
+use crate::recipe::build_recipe::{collect_chain_parameters, render_recipe_chain_for_preview};
+use crate::recipe::compose::load_recipe_chain;
+use crate::recipe::parameter_values::validate_parameter_definitions;
+use crate::recipe::read_recipe_file_content::{read_recipe_file, RecipeFile};
+use crate::recipe::{
+    Recipe, RecipeParameter, RecipeParameterRequirement, Response, BUILT_IN_RECIPE_DIR_PARAM,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LintIssue {
+    pub severity: Severity,
+    /// Area of the recipe the issue is about, e.g. `parameters` or `extensions`
+    pub check: String,
+    /// Recipe file the issue was found in
+    pub file: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LintReport {
+    pub recipe: String,
+    /// False when any issue is an error
+    pub valid: bool,
+    pub issues: Vec<LintIssue>,
+}
+
+impl LintReport {
+    pub fn errors(&self) -> impl Iterator<Item = &LintIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+    }
+}
+
+/// Lint a recipe file and every recipe it runs. Builtin extensions must be one of
+/// `builtin_extensions`, the names `goose mcp` can start.
+pub fn lint_recipe(recipe_file: RecipeFile, builtin_extensions: &[&str]) -> LintReport {
+    let recipe = recipe_file.file_path.display().to_string();
+    let mut linter = Linter {
+        builtin_extensions,
+        ..Linter::default()
+    };
+    linter.lint_file(recipe_file);
+    let valid = !linter
+        .issues
+        .iter()
+        .any(|issue| issue.severity == Severity::Error);
+    LintReport {
+        recipe,
+        valid,
+        issues: linter.issues,
+    }
+}
+
+#[derive(Default)]
+struct Linter<'a> {
+    builtin_extensions: &'a [&'a str],
+    /// Parameters declared by each linted file, `None` while it is being linted or if it failed
+    linted: HashMap<PathBuf, Option<Vec<RecipeParameter>>>,
+    issues: Vec<LintIssue>,
+}
+
+impl Linter<'_> {
+    fn report(&mut self, severity: Severity, check: &str, file: &Path, message: String) {
+        self.issues.push(LintIssue {
+            severity,
+            check: check.to_string(),
+            file: file.display().to_string(),
+            message,
+        });
+    }
+
+    fn error(&mut self, check: &str, file: &Path, message: String) {
+        self.report(Severity::Error, check, file, message);
+    }
+
+    fn warning(&mut self, check: &str, file: &Path, message: String) {
+        self.report(Severity::Warning, check, file, message);
+    }
+
+    /// Lint one recipe file, returning the parameters it declares
+    fn lint_file(&mut self, recipe_file: RecipeFile) -> Option<Vec<RecipeParameter>> {
+        let file = recipe_file.file_path.clone();
+        if let Some(parameters) = self.linted.get(&file) {
+            return parameters.clone();
+        }
+        self.linted.insert(file.clone(), None);
+
+        let chain = match load_recipe_chain(recipe_file) {
+            Ok(chain) => chain,
+            Err(e) => {
+                self.error("extends", &file, e.to_string());
+                return None;
+            }
+        };
+        let parameters = match collect_chain_parameters(&chain) {
+            Ok((parameters, template_variables)) => {
+                let parameters = parameters.unwrap_or_default();
+                self.lint_parameters(&file, &parameters, &template_variables);
+                parameters
+            }
+            Err(e) => {
+                self.error("recipe", &file, e.to_string());
+                return None;
+            }
+        };
+        // Keep `{{ recipe_dir }}` working in sub-recipe paths, everything else stays as written
+        let preview_params =
+            HashMap::from([(BUILT_IN_RECIPE_DIR_PARAM.to_string(), String::new())]);
+        let recipe = match render_recipe_chain_for_preview(&chain, &preview_params) {
+            Ok(recipe) => recipe,
+            Err(e) => {
+                self.error("recipe", &file, e.to_string());
+                return None;
+            }
+        };
+
+        self.lint_extensions(&file, &recipe);
+        self.lint_responses(&file, &recipe);
+        self.lint_retry(&file, &recipe);
+        self.lint_sub_recipes(&file, &recipe);
+
+        self.linted.insert(file, Some(parameters.clone()));
+        Some(parameters)
+    }
+
+    fn lint_parameters(
+        &mut self,
+        file: &Path,
+        parameters: &[RecipeParameter],
+        template_variables: &HashSet<String>,
+    ) {
+        let keys: HashSet<&str> = parameters.iter().map(|p| p.key.as_str()).collect();
+        let mut undeclared: Vec<&String> = template_variables
+            .iter()
+            .filter(|variable| {
+                variable.as_str() != BUILT_IN_RECIPE_DIR_PARAM && !keys.contains(variable.as_str())
+            })
+            .collect();
+        undeclared.sort();
+        for variable in undeclared {
+            self.error(
+                "parameters",
+                file,
+                format!("Parameter '{}' is used but not declared", variable),
+            );
+        }
+
+        for param in parameters {
+            if !template_variables.contains(&param.key) {
+                self.error(
+                    "parameters",
+                    file,
+                    format!("Parameter '{}' is declared but never used", param.key),
+                );
+            }
+            if matches!(param.requirement, RecipeParameterRequirement::Optional)
+                && param.default.is_none()
+            {
+                self.error(
+                    "parameters",
+                    file,
+                    format!("Optional parameter '{}' has no default value", param.key),
+                );
+            }
+        }
+
+        if let Err(errors) = validate_parameter_definitions(parameters) {
+            for error in errors {
+                self.error("parameters", file, error);
+            }
+        }
+    }
+
+    fn lint_extensions(&mut self, file: &Path, recipe: &Recipe) {
+        for extension in recipe.extensions.iter().flatten() {
+            match extension {
+                ExtensionConfig::Stdio { name, cmd, .. } => {
+                    if !cmd.contains("{{") && find_executable(cmd).is_none() {
+                        self.error(
+                            "extensions",
+                            file,
+                            format!(
+                                "Command '{}' of extension '{}' was not found on PATH",
+                                cmd, name
+                            ),
+                        );
+                    }
+                }
+                ExtensionConfig::Builtin { name, .. } => {
+                    if !self.builtin_extensions.contains(&name.as_str()) {
+                        self.error(
+                            "extensions",
+                            file,
+                            format!(
+                                "Unknown builtin extension '{}', expected one of: {}",
+                                name,
+                                self.builtin_extensions.join(", ")
+                            ),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn lint_responses(&mut self, file: &Path, recipe: &Recipe) {
+        self.lint_response(file, "response", recipe.response.as_ref());
+        for step in recipe.steps.iter().flatten() {
+            self.lint_response(
+                file,
+                &format!("response of step '{}'", step.id),
+                step.response.as_ref(),
+            );
+        }
+    }
+
+    fn lint_response(&mut self, file: &Path, location: &str, response: Option<&Response>) {
+        let Some(schema) = response.and_then(|response| response.json_schema.as_ref()) else {
+            return;
+        };
+        if let Err(e) = jsonschema::validator_for(schema) {
+            self.error(
+                "response",
+                file,
+                format!("JSON schema validation failed for {}: {}", location, e),
+            );
+        }
+    }
+
+    fn lint_retry(&mut self, file: &Path, recipe: &Recipe) {
+        let Some(retry) = &recipe.retry else {
+            return;
+        };
+        if let Err(e) = retry.validate() {
+            self.error("retry", file, e);
+        }
+        if retry.checks.is_empty() {
+            self.warning(
+                "retry",
+                file,
+                "retry has no checks, so a run is never retried".to_string(),
+            );
+        }
+        for check in &retry.checks {
+            self.lint_success_check(file, check);
+        }
+    }
+
+    fn lint_success_check(&mut self, file: &Path, check: &SuccessCheck) {
+        match check {
+            SuccessCheck::Shell { command } if command.trim().is_empty() => {
+                self.error(
+                    "retry",
+                    file,
+                    "Shell check has an empty command".to_string(),
+                );
+            }
+            SuccessCheck::FileMatches { pattern, .. } => {
+                if let Err(e) = Regex::new(pattern) {
+                    self.error(
+                        "retry",
+                        file,
+                        format!("Invalid pattern '{}' in file_matches check: {}", pattern, e),
+                    );
+                }
+            }
+            SuccessCheck::JsonOutput { schema } => {
+                if let Err(e) = jsonschema::validator_for(schema) {
+                    self.error(
+                        "retry",
+                        file,
+                        format!("Invalid schema in json_output check: {}", e),
+                    );
+                }
+            }
+            SuccessCheck::All { checks } | SuccessCheck::Any { checks } => {
+                if checks.is_empty() {
+                    self.warning(
+                        "retry",
+                        file,
+                        "Check group has no nested checks".to_string(),
+                    );
+                }
+                for check in checks {
+                    self.lint_success_check(file, check);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn lint_sub_recipes(&mut self, file: &Path, recipe: &Recipe) {
+        for sub_recipe in recipe.sub_recipes.iter().flatten() {
+            self.lint_called_recipe(
+                file,
+                &format!("Sub-recipe '{}'", sub_recipe.name),
+                &sub_recipe.path,
+                sub_recipe.values.as_ref(),
+            );
+        }
+        for step in recipe.steps.iter().flatten() {
+            if let Some(path) = &step.recipe {
+                self.lint_called_recipe(
+                    file,
+                    &format!("Recipe of step '{}'", step.id),
+                    path,
+                    step.values.as_ref(),
+                );
+            }
+        }
+    }
+
+    fn lint_called_recipe(
+        &mut self,
+        file: &Path,
+        label: &str,
+        path: &str,
+        values: Option<&HashMap<String, String>>,
+    ) {
+        let recipe_file = match read_recipe_file(path) {
+            Ok(recipe_file) => recipe_file,
+            Err(e) => {
+                self.error(
+                    "sub_recipes",
+                    file,
+                    format!("{} could not be loaded from {}: {}", label, path, e),
+                );
+                return;
+            }
+        };
+        let Some(parameters) = self.lint_file(recipe_file) else {
+            return;
+        };
+        let mut passed: Vec<&String> = values.into_iter().flat_map(|v| v.keys()).collect();
+        passed.sort();
+        for key in passed {
+            if !parameters.iter().any(|param| &param.key == key) {
+                self.error(
+                    "sub_recipes",
+                    file,
+                    format!(
+                        "{} is given a value for '{}', which it does not declare",
+                        label, key
+                    ),
+                );
+            }
+        }
+    }
+}
+
+fn find_executable(cmd: &str) -> Option<PathBuf> {
+    let cmd_path = Path::new(cmd);
+    if cmd_path.components().count() > 1 {
+        return cmd_path.is_file().then(|| cmd_path.to_path_buf());
+    }
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .flat_map(|dir| {
+            let candidate = dir.join(cmd);
+            let windows_candidate = cfg!(windows).then(|| dir.join(format!("{}.exe", cmd)));
+            std::iter::once(candidate).chain(windows_candidate)
+        })
+        .find(|candidate| candidate.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &TempDir, name: &str, content: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn checks(report: &LintReport) -> Vec<(&str, &str)> {
+        report
+            .issues
+            .iter()
+            .map(|issue| (issue.check.as_str(), issue.message.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_valid_recipe() {
+        let dir = TempDir::new().unwrap();
+        write(
+            &dir,
+            "child.yaml",
+            r#"
+title: Child
+description: Child
+instructions: Handle {{ topic }}
+parameters:
+  - key: topic
+    input_type: string
+    requirement: required
+    description: Topic
+"#,
+        );
+        let parent = write(
+            &dir,
+            "parent.yaml",
+            r#"
+title: Parent
+description: Parent
+instructions: Delegate
+extensions:
+  - type: builtin
+    name: developer
+sub_recipes:
+  - name: child
+    path: ./child.yaml
+    values:
+      topic: tests
+"#,
+        );
+
+        let report = lint_recipe(read_recipe_file(&parent).unwrap(), &["developer"]);
+        assert!(report.valid, "{:?}", report.issues);
+        assert!(report.issues.is_empty());
+    }
+
+    #[test]
+    fn test_lint_reports_every_problem() {
+        let dir = TempDir::new().unwrap();
+        write(
+            &dir,
+            "child.yaml",
+            r#"
+title: Child
+description: Child
+instructions: Handle {{ topic }}
+parameters:
+  - key: topic
+    input_type: string
+    requirement: required
+    description: Topic
+  - key: unused
+    input_type: string
+    requirement: optional
+    default: x
+    description: Never used
+"#,
+        );
+        let parent = write(
+            &dir,
+            "parent.yaml",
+            r#"
+title: Parent
+description: Parent
+instructions: Delegate {{ goal }}
+extensions:
+  - type: builtin
+    name: not_a_builtin
+  - type: stdio
+    name: missing
+    cmd: goose-lint-test-command-that-does-not-exist
+    args: []
+response:
+  json_schema:
+    type: not_a_type
+retry:
+  max_retries: 2
+  checks:
+    - type: file_matches
+      path: out.txt
+      pattern: "("
+sub_recipes:
+  - name: child
+    path: ./child.yaml
+    values:
+      topic: tests
+      extra: value
+  - name: absent
+    path: ./absent.yaml
+"#,
+        );
+
+        let report = lint_recipe(read_recipe_file(&parent).unwrap(), &["developer"]);
+        assert!(!report.valid);
+        let found = checks(&report);
+        let has = |check: &str, text: &str| {
+            found
+                .iter()
+                .any(|(c, message)| *c == check && message.contains(text))
+        };
+        assert!(has("parameters", "'goal' is used but not declared"));
+        assert!(has("parameters", "'unused' is declared but never used"));
+        assert!(has("extensions", "not_a_builtin"));
+        assert!(has("extensions", "was not found on PATH"));
+        assert!(has("response", "JSON schema validation failed"));
+        assert!(has("retry", "Invalid pattern"));
+        assert!(has("sub_recipes", "'extra', which it does not declare"));
+        assert!(has(
+            "sub_recipes",
+            "Sub-recipe 'absent' could not be loaded"
+        ));
+
+        let unused = report
+            .issues
+            .iter()
+            .find(|issue| issue.message.contains("'unused'"))
+            .unwrap();
+        assert!(unused.file.ends_with("child.yaml"));
+    }
+}
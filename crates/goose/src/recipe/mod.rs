@@ -10,9 +10,18 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 pub mod build_recipe;
+pub mod compose;
+pub mod from_session;
+pub mod lint;
+pub mod parameter_values;
 pub mod read_recipe_file_content;
+pub mod registry;
+pub mod signing;
+pub mod steps;
 pub mod template_recipe;
 
+pub use steps::RecipeStep;
+
 pub const BUILT_IN_RECIPE_DIR_PARAM: &str = "recipe_dir";
 
 fn default_version() -> String {
@@ -39,6 +48,11 @@ fn default_version() -> String {
 /// * `parameters` - Additional parameters for the Recipe
 /// * `response` - Response configuration including JSON schema validation
 /// * `retry` - Retry configuration for automated validation and recovery
+/// * `pinned` - Parts of the recipe (`prompt`, `context`) kept verbatim through context compaction
+/// * `steps` - Steps run as a DAG before the prompt, each with instructions or a recipe
+/// * `extends` - Path or name of a recipe this one builds on, see [`compose`]
+/// * `include` - Files whose content is put in front of the instructions
+/// * `artifacts` - Globs of output files collected into the session after a run
 /// # Example
 ///
 ///
@@ -68,6 +82,11 @@ fn default_version() -> String {
 ///     response: None,
 ///     sub_recipes: None,
 ///     retry: None,
+///     pinned: None,
+///     steps: None,
+///     extends: None,
+///     include: None,
+///     artifacts: None,
 /// };
 ///
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
@@ -114,6 +133,29 @@ pub struct Recipe {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub retry: Option<RetryConfig>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pinned: Option<Vec<PinnedSection>>, // parts of the recipe that compaction must keep
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub steps: Option<Vec<RecipeStep>>, // workflow steps run before the prompt
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>, // recipe this one is layered on top of
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include: Option<Vec<String>>, // instruction fragments, relative to the recipe
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifacts: Option<Vec<String>>, // globs of output files collected after a run
+}
+
+/// Part of a recipe whose message is pinned, so it survives context compaction word for word
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PinnedSection {
+    Prompt,
+    Context,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
@@ -135,6 +177,11 @@ pub struct Settings {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f32>,
+
+    /// Compaction strategy to use when the conversation outgrows the context window, see
+    /// `context_mgmt::strategy::strategies()` for the available names
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compaction_strategy: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
@@ -229,6 +276,21 @@ pub struct RecipeParameter {
     pub default: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub options: Option<Vec<String>>,
+    /// Smallest allowed value of a `number` parameter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    /// Largest allowed value of a `number` parameter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    /// Regular expression the value has to match
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Largest allowed size in bytes of a `file` parameter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<u64>,
+    /// Pass the content of a `file` parameter to the recipe instead of its path
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inline: Option<bool>,
 }
 
 /// Builder for creating Recipe instances
@@ -250,6 +312,11 @@ pub struct RecipeBuilder {
     response: Option<Response>,
     sub_recipes: Option<Vec<SubRecipe>>,
     retry: Option<RetryConfig>,
+    pinned: Option<Vec<PinnedSection>>,
+    steps: Option<Vec<RecipeStep>>,
+    extends: Option<String>,
+    include: Option<Vec<String>>,
+    artifacts: Option<Vec<String>>,
 }
 
 impl Recipe {
@@ -283,6 +350,11 @@ impl Recipe {
             response: None,
             sub_recipes: None,
             retry: None,
+            pinned: None,
+            steps: None,
+            extends: None,
+            include: None,
+            artifacts: None,
         }
     }
     pub fn from_content(content: &str) -> Result<Self> {
@@ -314,8 +386,41 @@ impl Recipe {
             }
         }
 
+        if let Some(ref recipe_steps) = recipe.steps {
+            if let Err(validation_error) = steps::validate_steps(recipe_steps) {
+                return Err(anyhow::anyhow!(
+                    "Invalid steps configuration: {}",
+                    validation_error
+                ));
+            }
+        }
+
         Ok(recipe)
     }
+
+    /// Text of the first message of a session run from this recipe, and whether it is pinned.
+    ///
+    /// Pinned `context` entries are sent ahead of the prompt so they survive compaction with it.
+    pub fn initial_prompt(&self) -> Option<(String, bool)> {
+        let pinned = self.pinned.as_deref().unwrap_or_default();
+        let prompt = self.prompt.clone().filter(|s| !s.trim().is_empty());
+        let context = self
+            .context
+            .as_ref()
+            .filter(|context| pinned.contains(&PinnedSection::Context) && !context.is_empty());
+
+        match (context, prompt) {
+            (Some(context), prompt) => {
+                let mut text = context.join("\n\n");
+                if let Some(prompt) = prompt {
+                    text = format!("{}\n\n{}", text, prompt);
+                }
+                Some((text, true))
+            }
+            (None, Some(prompt)) => Some((prompt, pinned.contains(&PinnedSection::Prompt))),
+            (None, None) => None,
+        }
+    }
 }
 
 impl RecipeBuilder {
@@ -399,6 +504,36 @@ impl RecipeBuilder {
         self
     }
 
+    /// Sets which parts of the Recipe are pinned through context compaction
+    pub fn pinned(mut self, pinned: Vec<PinnedSection>) -> Self {
+        self.pinned = Some(pinned);
+        self
+    }
+
+    /// Sets the workflow steps of the Recipe
+    pub fn steps(mut self, steps: Vec<RecipeStep>) -> Self {
+        self.steps = Some(steps);
+        self
+    }
+
+    /// Sets the recipe the Recipe extends
+    pub fn extends(mut self, extends: impl Into<String>) -> Self {
+        self.extends = Some(extends.into());
+        self
+    }
+
+    /// Sets the instruction fragments the Recipe includes
+    pub fn include(mut self, include: Vec<String>) -> Self {
+        self.include = Some(include);
+        self
+    }
+
+    /// Sets the globs of output files collected after the Recipe runs
+    pub fn artifacts(mut self, artifacts: Vec<String>) -> Self {
+        self.artifacts = Some(artifacts);
+        self
+    }
+
     /// Builds the Recipe instance
     ///
     /// Returns an error if any required fields are missing
@@ -425,6 +560,11 @@ impl RecipeBuilder {
             response: self.response,
             sub_recipes: self.sub_recipes,
             retry: self.retry,
+            pinned: self.pinned,
+            steps: self.steps,
+            extends: self.extends,
+            include: self.include,
+            artifacts: self.artifacts,
         })
     }
 }
@@ -745,4 +885,36 @@ isGlobal: true"#;
         let extensions = recipe.extensions.unwrap();
         assert_eq!(extensions.len(), 0);
     }
+
+    #[test]
+    fn test_initial_prompt_pinning() {
+        let content = r#"{
+            "title": "Pinned",
+            "description": "Pins its task",
+            "prompt": "Fix the flaky test",
+            "context": ["Never change the public API"],
+            "pinned": ["context"]
+        }"#;
+        let mut recipe = Recipe::from_content(content).unwrap();
+        assert_eq!(
+            recipe.initial_prompt(),
+            Some((
+                "Never change the public API\n\nFix the flaky test".to_string(),
+                true
+            ))
+        );
+
+        // Unpinned context is left out of the conversation, as before
+        recipe.pinned = None;
+        assert_eq!(
+            recipe.initial_prompt(),
+            Some(("Fix the flaky test".to_string(), false))
+        );
+
+        recipe.pinned = Some(vec![PinnedSection::Prompt]);
+        assert_eq!(
+            recipe.initial_prompt(),
+            Some(("Fix the flaky test".to_string(), true))
+        );
+    }
 }
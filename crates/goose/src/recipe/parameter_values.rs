@@ -0,0 +1,293 @@
+//! Validation and coercion of recipe parameter values by their declared `input_type`
+//!
+//! Values reach templates as strings, so coercion normalizes them: booleans become `true` or
+//! `false`, dates become `YYYY-MM-DD` and files with `inline: true` are replaced by their
+//! content.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use chrono::{DateTime, NaiveDate};
+use regex::Regex;
+
+use crate::recipe::{RecipeParameter, RecipeParameterInputType};
+
+/// Largest file inlined when a `file` parameter doesn't set `max_size`
+pub const DEFAULT_MAX_INLINE_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Check that the constraints declared on parameters are usable, e.g. that patterns compile
+pub fn validate_parameter_definitions(parameters: &[RecipeParameter]) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+    for param in parameters {
+        if let Some(pattern) = &param.pattern {
+            if let Err(e) = Regex::new(pattern) {
+                errors.push(format!(
+                    "{}: invalid pattern '{}': {}",
+                    param.key, pattern, e
+                ));
+            }
+        }
+        if let (Some(min), Some(max)) = (param.min, param.max) {
+            if min > max {
+                errors.push(format!(
+                    "{}: min {} is greater than max {}",
+                    param.key, min, max
+                ));
+            }
+        }
+        if matches!(param.input_type, RecipeParameterInputType::Select)
+            && param
+                .options
+                .as_ref()
+                .is_none_or(|options| options.is_empty())
+        {
+            errors.push(format!("{}: select parameters need options", param.key));
+        }
+        if let Some(default) = &param.default {
+            // Empty defaults stand for "not set", and default files are checked when used
+            if !default.is_empty() && !matches!(param.input_type, RecipeParameterInputType::File) {
+                if let Err(e) = coerce_parameter_value(param, default) {
+                    errors.push(format!("{}: invalid default: {}", param.key, e));
+                }
+            }
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Validate and coerce the values given for `parameters` in place, returning every problem
+/// found. Values without a parameter definition are left alone.
+pub fn validate_parameter_values(
+    parameters: &[RecipeParameter],
+    values: &mut HashMap<String, String>,
+) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+    for param in parameters {
+        let Some(value) = values.get(&param.key) else {
+            continue;
+        };
+        if value.is_empty() && param.default.as_deref() == Some("") {
+            continue;
+        }
+        match coerce_parameter_value(param, value) {
+            Ok(coerced) => {
+                values.insert(param.key.clone(), coerced);
+            }
+            Err(e) => errors.push(format!("{}: {}", param.key, e)),
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Validate a single value against its parameter and return the value passed to templates
+pub fn coerce_parameter_value(param: &RecipeParameter, value: &str) -> Result<String, String> {
+    let coerced = match param.input_type {
+        RecipeParameterInputType::String => value.to_string(),
+        RecipeParameterInputType::Number => {
+            let number: f64 = value
+                .trim()
+                .parse()
+                .ok()
+                .filter(|number: &f64| number.is_finite())
+                .ok_or_else(|| format!("expected a number, got '{}'", value))?;
+            if let Some(min) = param.min {
+                if number < min {
+                    return Err(format!("{} is less than the minimum of {}", number, min));
+                }
+            }
+            if let Some(max) = param.max {
+                if number > max {
+                    return Err(format!("{} is greater than the maximum of {}", number, max));
+                }
+            }
+            value.trim().to_string()
+        }
+        RecipeParameterInputType::Boolean => match value.trim().to_lowercase().as_str() {
+            "true" | "yes" | "y" | "on" | "1" => "true".to_string(),
+            "false" | "no" | "n" | "off" | "0" => "false".to_string(),
+            _ => return Err(format!("expected true or false, got '{}'", value)),
+        },
+        RecipeParameterInputType::Date => {
+            let value = value.trim();
+            let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .or_else(|_| DateTime::parse_from_rfc3339(value).map(|dt| dt.date_naive()))
+                .map_err(|_| {
+                    format!(
+                        "expected a date like 2025-01-31 or an RFC 3339 timestamp, got '{}'",
+                        value
+                    )
+                })?;
+            date.format("%Y-%m-%d").to_string()
+        }
+        RecipeParameterInputType::File => coerce_file(param, value)?,
+        RecipeParameterInputType::Select => {
+            let options = param.options.as_deref().unwrap_or_default();
+            if !options.iter().any(|option| option == value) {
+                return Err(format!(
+                    "'{}' is not one of the options: {}",
+                    value,
+                    options.join(", ")
+                ));
+            }
+            value.to_string()
+        }
+    };
+
+    if let Some(pattern) = &param.pattern {
+        let regex =
+            Regex::new(pattern).map_err(|e| format!("invalid pattern '{}': {}", pattern, e))?;
+        if !regex.is_match(value) {
+            return Err(format!(
+                "'{}' does not match the pattern {}",
+                value, pattern
+            ));
+        }
+    }
+    Ok(coerced)
+}
+
+fn coerce_file(param: &RecipeParameter, value: &str) -> Result<String, String> {
+    let path = match value.strip_prefix("~/").zip(dirs::home_dir()) {
+        Some((stripped, home_dir)) => home_dir.join(stripped),
+        None => PathBuf::from(value),
+    };
+    let metadata =
+        std::fs::metadata(&path).map_err(|e| format!("cannot read file '{}': {}", value, e))?;
+    if !metadata.is_file() {
+        return Err(format!("'{}' is not a file", value));
+    }
+
+    let inline = param.inline.unwrap_or(false);
+    let max_size = param
+        .max_size
+        .or(inline.then_some(DEFAULT_MAX_INLINE_FILE_SIZE));
+    if let Some(max_size) = max_size {
+        if metadata.len() > max_size {
+            return Err(format!(
+                "'{}' is {} bytes, more than the limit of {} bytes",
+                value,
+                metadata.len(),
+                max_size
+            ));
+        }
+    }
+
+    if inline {
+        std::fs::read_to_string(&path)
+            .map_err(|e| format!("cannot read '{}' as text: {}", value, e))
+    } else {
+        Ok(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recipe::RecipeParameterRequirement;
+
+    fn param(input_type: RecipeParameterInputType) -> RecipeParameter {
+        RecipeParameter {
+            key: "value".to_string(),
+            input_type,
+            requirement: RecipeParameterRequirement::Required,
+            description: "A value".to_string(),
+            default: None,
+            options: None,
+            min: None,
+            max: None,
+            pattern: None,
+            max_size: None,
+            inline: None,
+        }
+    }
+
+    #[test]
+    fn test_coerce_scalar_types() {
+        let mut number = param(RecipeParameterInputType::Number);
+        number.min = Some(1.0);
+        number.max = Some(10.0);
+        assert_eq!(coerce_parameter_value(&number, " 2.5 ").unwrap(), "2.5");
+        assert!(coerce_parameter_value(&number, "11")
+            .unwrap_err()
+            .contains("maximum"));
+        assert!(coerce_parameter_value(&number, "ten")
+            .unwrap_err()
+            .contains("expected a number"));
+
+        let boolean = param(RecipeParameterInputType::Boolean);
+        assert_eq!(coerce_parameter_value(&boolean, "Yes").unwrap(), "true");
+        assert_eq!(coerce_parameter_value(&boolean, "0").unwrap(), "false");
+        assert!(coerce_parameter_value(&boolean, "maybe").is_err());
+
+        let date = param(RecipeParameterInputType::Date);
+        assert_eq!(
+            coerce_parameter_value(&date, "2025-03-01T10:00:00+02:00").unwrap(),
+            "2025-03-01"
+        );
+        assert!(coerce_parameter_value(&date, "March 1st").is_err());
+
+        let mut select = param(RecipeParameterInputType::Select);
+        select.options = Some(vec!["small".to_string(), "large".to_string()]);
+        assert_eq!(coerce_parameter_value(&select, "large").unwrap(), "large");
+        assert!(coerce_parameter_value(&select, "medium")
+            .unwrap_err()
+            .contains("small, large"));
+
+        let mut string = param(RecipeParameterInputType::String);
+        string.pattern = Some(r"^[A-Z]+-\d+$".to_string());
+        assert!(coerce_parameter_value(&string, "GOOSE-42").is_ok());
+        assert!(coerce_parameter_value(&string, "goose").is_err());
+    }
+
+    #[test]
+    fn test_coerce_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("notes.txt");
+        std::fs::write(&path, "remember the milk").unwrap();
+        let path = path.to_string_lossy().to_string();
+
+        let mut file = param(RecipeParameterInputType::File);
+        assert_eq!(coerce_parameter_value(&file, &path).unwrap(), path);
+        assert!(coerce_parameter_value(&file, "/does/not/exist").is_err());
+
+        file.inline = Some(true);
+        assert_eq!(
+            coerce_parameter_value(&file, &path).unwrap(),
+            "remember the milk"
+        );
+        file.max_size = Some(4);
+        assert!(coerce_parameter_value(&file, &path)
+            .unwrap_err()
+            .contains("limit of 4 bytes"));
+    }
+
+    #[test]
+    fn test_validate_parameter_values_reports_all_errors() {
+        let mut count = param(RecipeParameterInputType::Number);
+        count.key = "count".to_string();
+        let mut flag = param(RecipeParameterInputType::Boolean);
+        flag.key = "flag".to_string();
+        let mut values = HashMap::from([
+            ("count".to_string(), "many".to_string()),
+            ("flag".to_string(), "on".to_string()),
+        ]);
+
+        let errors = validate_parameter_values(&[count, flag], &mut values).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("count:"));
+        assert_eq!(values["flag"], "true");
+
+        let mut select = param(RecipeParameterInputType::Select);
+        select.default = Some("x".to_string());
+        let errors = validate_parameter_definitions(&[select]).unwrap_err();
+        assert_eq!(errors.len(), 2);
+    }
+}
@@ -0,0 +1,509 @@
+//! Recipe registry with versioned recipes, a local install store and a project lockfile
+//!
+//! A registry is a directory or an HTTP server with an `index.json` listing recipes and their
+//! versions:
+//!
+//! ```json
+//! {
+//!   "recipes": [
+//!     {
+//!       "name": "code-review",
+//!       "description": "Reviews a diff",
+//!       "versions": [
+//!         { "version": "1.2.0", "path": "code-review/1.2.0/recipe.yaml", "sha256": "..." }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Paths are relative to the index, and a recipe may be signed by a `.sig` file next to it.
+//! Installed recipes are kept by name and version in the data directory, so once a version is
+//! installed it can be used without the registry. The versions a project uses are pinned in
+//! `goose-recipes.lock`, which is also how recipe names in `extends` and `sub_recipes` are
+//! resolved.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use etcetera::{choose_app_strategy, AppStrategy};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config::{Config, APP_STRATEGY};
+use crate::recipe::signing::signature_path;
+
+pub use semver::{Version, VersionReq};
+
+pub const GOOSE_RECIPE_REGISTRY_CONFIG_KEY: &str = "GOOSE_RECIPE_REGISTRY";
+pub const LOCKFILE_NAME: &str = "goose-recipes.lock";
+const INDEX_FILE_NAME: &str = "index.json";
+const RECIPE_FILE_EXTENSIONS: [&str; 3] = ["yaml", "yml", "json"];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RegistryIndex {
+    pub recipes: Vec<IndexEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub versions: Vec<IndexVersion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexVersion {
+    pub version: Version,
+    /// Location of the recipe file relative to the index
+    pub path: String,
+    pub sha256: String,
+}
+
+impl RegistryIndex {
+    /// The newest version of `name` that satisfies `requirement`
+    pub fn resolve(&self, name: &str, requirement: &VersionReq) -> Result<&IndexVersion> {
+        let entry = self
+            .recipes
+            .iter()
+            .find(|entry| entry.name == name)
+            .ok_or_else(|| anyhow!("Recipe '{}' is not in the registry", name))?;
+        entry
+            .versions
+            .iter()
+            .filter(|candidate| requirement.matches(&candidate.version))
+            .max_by(|a, b| a.version.cmp(&b.version))
+            .ok_or_else(|| {
+                anyhow!(
+                    "No version of recipe '{}' matches {}; available: {}",
+                    name,
+                    requirement,
+                    entry
+                        .versions
+                        .iter()
+                        .map(|v| v.version.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+    }
+}
+
+/// Where a registry index is served from
+#[derive(Debug, Clone, PartialEq)]
+pub enum Registry {
+    Directory(PathBuf),
+    Http(url::Url),
+}
+
+impl Registry {
+    pub fn from_location(location: &str) -> Result<Self> {
+        if location.starts_with("http://") || location.starts_with("https://") {
+            let mut url = url::Url::parse(location)
+                .map_err(|e| anyhow!("Invalid registry URL {}: {}", location, e))?;
+            if !url.path().ends_with('/') {
+                url.set_path(&format!("{}/", url.path()));
+            }
+            return Ok(Registry::Http(url));
+        }
+        let path = match location.strip_prefix("~/").zip(dirs::home_dir()) {
+            Some((stripped, home_dir)) => home_dir.join(stripped),
+            None => PathBuf::from(location),
+        };
+        Ok(Registry::Directory(path))
+    }
+
+    /// The registry set with `GOOSE_RECIPE_REGISTRY`
+    pub fn configured() -> Result<Self> {
+        let location: String = Config::global()
+            .get_param(GOOSE_RECIPE_REGISTRY_CONFIG_KEY)
+            .map_err(|_| {
+                anyhow!(
+                    "No recipe registry configured; set {} to a directory or URL",
+                    GOOSE_RECIPE_REGISTRY_CONFIG_KEY
+                )
+            })?;
+        Self::from_location(&location)
+    }
+
+    pub fn location(&self) -> String {
+        match self {
+            Registry::Directory(path) => path.display().to_string(),
+            Registry::Http(url) => url.to_string(),
+        }
+    }
+
+    async fn read(&self, path: &str) -> Result<Vec<u8>> {
+        match self {
+            Registry::Directory(dir) => {
+                let file = dir.join(path);
+                fs::read(&file).map_err(|e| anyhow!("Failed to read {}: {}", file.display(), e))
+            }
+            Registry::Http(base) => {
+                let url = base
+                    .join(path)
+                    .map_err(|e| anyhow!("Invalid registry path {}: {}", path, e))?;
+                let response = reqwest::get(url.clone())
+                    .await
+                    .and_then(|response| response.error_for_status())
+                    .map_err(|e| anyhow!("Failed to fetch {}: {}", url, e))?;
+                Ok(response.bytes().await?.to_vec())
+            }
+        }
+    }
+
+    pub async fn index(&self) -> Result<RegistryIndex> {
+        let content = self.read(INDEX_FILE_NAME).await?;
+        serde_json::from_slice(&content)
+            .map_err(|e| anyhow!("Invalid registry index at {}: {}", self.location(), e))
+    }
+
+    /// Download a recipe version and check it against the checksum in the index
+    pub async fn download(&self, version: &IndexVersion) -> Result<Vec<u8>> {
+        let content = self.read(&version.path).await?;
+        let checksum = sha256_hex(&content);
+        if checksum != version.sha256 {
+            return Err(anyhow!(
+                "Checksum mismatch for {}: expected {}, got {}",
+                version.path,
+                version.sha256,
+                checksum
+            ));
+        }
+        Ok(content)
+    }
+}
+
+pub fn sha256_hex(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+/// Recipe versions pinned by a project
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Lockfile {
+    #[serde(default)]
+    pub recipes: BTreeMap<String, LockedRecipe>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockedRecipe {
+    pub version: Version,
+    /// Requirement used by `update` to pick newer versions
+    pub requirement: VersionReq,
+    pub sha256: String,
+    pub registry: String,
+}
+
+impl Lockfile {
+    /// Load a lockfile, or an empty one if it doesn't exist yet
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        serde_yaml::from_str(&content)
+            .map_err(|e| anyhow!("Invalid lockfile {}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_yaml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// The nearest lockfile in `start` or one of its parents
+    pub fn find(start: &Path) -> Option<PathBuf> {
+        start
+            .ancestors()
+            .map(|dir| dir.join(LOCKFILE_NAME))
+            .find(|path| path.is_file())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstalledRecipe {
+    pub name: String,
+    pub version: Version,
+    pub path: PathBuf,
+}
+
+/// Installed recipes, stored as `<root>/<name>/<version>/<file>`
+pub struct RecipeStore {
+    root: PathBuf,
+}
+
+impl RecipeStore {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    pub fn default_location() -> Result<Self> {
+        let strategy = choose_app_strategy(APP_STRATEGY.clone())?;
+        Ok(Self::new(strategy.data_dir().join("recipes")))
+    }
+
+    pub fn recipe_path(&self, name: &str, version: &Version) -> Option<PathBuf> {
+        let dir = self.root.join(name).join(version.to_string());
+        fs::read_dir(dir)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .find(|path| {
+                path.extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| RECIPE_FILE_EXTENSIONS.contains(&ext))
+            })
+    }
+
+    pub fn list(&self) -> Result<Vec<InstalledRecipe>> {
+        let mut installed = Vec::new();
+        let Ok(names) = fs::read_dir(&self.root) else {
+            return Ok(installed);
+        };
+        for name_entry in names.filter_map(|entry| entry.ok()) {
+            let name = name_entry.file_name().to_string_lossy().to_string();
+            for version_entry in fs::read_dir(name_entry.path())?.filter_map(|entry| entry.ok()) {
+                let Ok(version) = Version::parse(&version_entry.file_name().to_string_lossy())
+                else {
+                    continue;
+                };
+                if let Some(path) = self.recipe_path(&name, &version) {
+                    installed.push(InstalledRecipe {
+                        name: name.clone(),
+                        version,
+                        path,
+                    });
+                }
+            }
+        }
+        installed.sort_by(|a, b| a.name.cmp(&b.name).then(a.version.cmp(&b.version)));
+        Ok(installed)
+    }
+
+    /// The newest installed version of `name` that satisfies `requirement`
+    pub fn newest_matching(
+        &self,
+        name: &str,
+        requirement: &VersionReq,
+    ) -> Result<Option<InstalledRecipe>> {
+        Ok(self
+            .list()?
+            .into_iter()
+            .filter(|recipe| recipe.name == name && requirement.matches(&recipe.version))
+            .max_by(|a, b| a.version.cmp(&b.version)))
+    }
+
+    /// Install a version from the registry unless it is already installed with the same content
+    pub async fn install(
+        &self,
+        registry: &Registry,
+        name: &str,
+        version: &IndexVersion,
+    ) -> Result<PathBuf> {
+        if let Some(path) = self.recipe_path(name, &version.version) {
+            if fs::read(&path).is_ok_and(|content| sha256_hex(&content) == version.sha256) {
+                return Ok(path);
+            }
+        }
+
+        let content = registry.download(version).await?;
+        let file_name = Path::new(&version.path)
+            .file_name()
+            .ok_or_else(|| anyhow!("Invalid recipe path in index: {}", version.path))?;
+        let dir = self.root.join(name).join(version.version.to_string());
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(file_name);
+        fs::write(&path, content)?;
+        // Signatures are optional and published next to the recipe, see `signing`
+        if let Ok(signature) = registry.read(&format!("{}.sig", version.path)).await {
+            fs::write(signature_path(&path), signature)?;
+        }
+        Ok(path)
+    }
+}
+
+/// Resolve a recipe name through the lockfile nearest to `start_dir`, or to the working
+/// directory, if it pins an installed version of the recipe. Fails when the installed file
+/// doesn't match the checksum in the lockfile.
+pub fn resolve_locked_recipe(name: &str, start_dir: &Path) -> Result<Option<PathBuf>> {
+    let Some(lockfile_path) = Lockfile::find(start_dir).or_else(|| {
+        std::env::current_dir()
+            .ok()
+            .and_then(|dir| Lockfile::find(&dir))
+    }) else {
+        return Ok(None);
+    };
+    let lockfile = Lockfile::load(&lockfile_path)?;
+    let Some(locked) = lockfile.recipes.get(name) else {
+        return Ok(None);
+    };
+    let store = RecipeStore::default_location()?;
+    locked_recipe_path(&store, name, locked, &lockfile_path)
+}
+
+fn locked_recipe_path(
+    store: &RecipeStore,
+    name: &str,
+    locked: &LockedRecipe,
+    lockfile_path: &Path,
+) -> Result<Option<PathBuf>> {
+    let Some(path) = store.recipe_path(name, &locked.version) else {
+        tracing::error!(
+            "Recipe {} {} is locked in {} but not installed; run `goose recipe install`",
+            name,
+            locked.version,
+            lockfile_path.display()
+        );
+        return Ok(None);
+    };
+    let checksum = sha256_hex(&fs::read(&path)?);
+    if checksum != locked.sha256 {
+        return Err(anyhow!(
+            "Checksum mismatch for recipe {} {}: {} has {}, but {} pins {}; run `goose recipe install` to restore it",
+            name,
+            locked.version,
+            path.display(),
+            checksum,
+            lockfile_path.display(),
+            locked.sha256
+        ));
+    }
+    Ok(Some(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_registry(dir: &Path) -> RegistryIndex {
+        let mut versions = Vec::new();
+        for version in ["1.0.0", "1.2.0", "2.0.0"] {
+            let path = format!("review/{}/recipe.yaml", version);
+            let content = format!(
+                "title: Review {}\ndescription: Review\ninstructions: Review\n",
+                version
+            );
+            fs::create_dir_all(dir.join(&path).parent().unwrap()).unwrap();
+            fs::write(dir.join(&path), &content).unwrap();
+            versions.push(IndexVersion {
+                version: Version::parse(version).unwrap(),
+                path,
+                sha256: sha256_hex(content.as_bytes()),
+            });
+        }
+        let index = RegistryIndex {
+            recipes: vec![IndexEntry {
+                name: "review".to_string(),
+                description: None,
+                versions,
+            }],
+        };
+        fs::write(
+            dir.join(INDEX_FILE_NAME),
+            serde_json::to_string(&index).unwrap(),
+        )
+        .unwrap();
+        index
+    }
+
+    #[test]
+    fn test_resolve_picks_newest_matching_version() {
+        let dir = TempDir::new().unwrap();
+        let index = write_registry(dir.path());
+
+        let resolved = index
+            .resolve("review", &VersionReq::parse("^1").unwrap())
+            .unwrap();
+        assert_eq!(resolved.version, Version::new(1, 2, 0));
+        let err = index
+            .resolve("review", &VersionReq::parse("^3").unwrap())
+            .unwrap_err();
+        assert!(err.to_string().contains("1.0.0, 1.2.0, 2.0.0"));
+        assert!(index.resolve("deploy", &VersionReq::STAR).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_install_from_directory_registry() {
+        let registry_dir = TempDir::new().unwrap();
+        write_registry(registry_dir.path());
+        let store_dir = TempDir::new().unwrap();
+        let store = RecipeStore::new(store_dir.path().to_path_buf());
+        let registry = Registry::from_location(&registry_dir.path().to_string_lossy()).unwrap();
+
+        let index = registry.index().await.unwrap();
+        let version = index.resolve("review", &VersionReq::STAR).unwrap();
+        let path = store.install(&registry, "review", version).await.unwrap();
+        assert!(fs::read_to_string(&path).unwrap().contains("Review 2.0.0"));
+
+        let installed = store.newest_matching("review", &VersionReq::STAR).unwrap();
+        assert_eq!(installed.unwrap().version, Version::new(2, 0, 0));
+
+        let mut tampered = version.clone();
+        tampered.sha256 = "0".repeat(64);
+        fs::remove_dir_all(store_dir.path().join("review")).unwrap();
+        let err = store
+            .install(&registry, "review", &tampered)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"));
+    }
+
+    #[test]
+    fn test_lockfile_round_trip_and_find() {
+        let dir = TempDir::new().unwrap();
+        let nested = dir.path().join("recipes").join("team");
+        fs::create_dir_all(&nested).unwrap();
+        let mut lockfile = Lockfile::default();
+        lockfile.recipes.insert(
+            "review".to_string(),
+            LockedRecipe {
+                version: Version::new(1, 2, 0),
+                requirement: VersionReq::parse("^1").unwrap(),
+                sha256: "abc".to_string(),
+                registry: "https://recipes.example.com/".to_string(),
+            },
+        );
+        lockfile.save(&dir.path().join(LOCKFILE_NAME)).unwrap();
+
+        let found = Lockfile::find(&nested).unwrap();
+        let loaded = Lockfile::load(&found).unwrap();
+        assert_eq!(loaded.recipes["review"].version, Version::new(1, 2, 0));
+        assert_eq!(loaded.recipes["review"].requirement.to_string(), "^1");
+    }
+
+    #[tokio::test]
+    async fn test_locked_recipe_path_checks_checksum() {
+        let registry_dir = TempDir::new().unwrap();
+        write_registry(registry_dir.path());
+        let store_dir = TempDir::new().unwrap();
+        let store = RecipeStore::new(store_dir.path().to_path_buf());
+        let registry = Registry::from_location(&registry_dir.path().to_string_lossy()).unwrap();
+        let index = registry.index().await.unwrap();
+        let version = index.resolve("review", &VersionReq::STAR).unwrap();
+        let path = store.install(&registry, "review", version).await.unwrap();
+
+        let locked = LockedRecipe {
+            version: version.version.clone(),
+            requirement: VersionReq::STAR,
+            sha256: version.sha256.clone(),
+            registry: registry.location(),
+        };
+        let lockfile_path = registry_dir.path().join(LOCKFILE_NAME);
+        let resolved = locked_recipe_path(&store, "review", &locked, &lockfile_path).unwrap();
+        assert_eq!(resolved, Some(path.clone()));
+
+        fs::write(
+            &path,
+            "title: Evil\ndescription: Evil\ninstructions: Evil\n",
+        )
+        .unwrap();
+        let err = locked_recipe_path(&store, "review", &locked, &lockfile_path).unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"));
+    }
+}
@@ -0,0 +1,428 @@
+//! Detached ed25519 signatures for recipes and the trust policy applied before running them
+//!
+//! A recipe file `review.yaml` is signed by `review.yaml.sig`, a small YAML document with the
+//! id of the signing key and the signature of the file's bytes. Deeplinks carry the signature
+//! after the encoded recipe, see `recipe_deeplink::encode_signed`. A recipe file is trusted only
+//! when the recipes it extends, its includes and the recipes it runs are signed too.
+//!
+//! Public keys trusted to sign recipes are listed in `GOOSE_RECIPE_TRUSTED_KEYS`.
+//! `GOOSE_RECIPE_SIGNATURE_POLICY` decides what happens to recipes that are not signed by a
+//! trusted key but declare extensions, and to recipes whose signature doesn't verify:
+//! `off`, `warn` (the default) or `block`.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config::Config;
+use crate::recipe::compose::{load_recipe_chain, resolve_fragment_path, resolve_recipe_reference};
+use crate::recipe::read_recipe_file_content::read_recipe_file;
+use crate::recipe::template_recipe::parse_recipe_content;
+use crate::recipe::Recipe;
+
+pub const GOOSE_RECIPE_TRUSTED_KEYS_CONFIG_KEY: &str = "GOOSE_RECIPE_TRUSTED_KEYS";
+pub const GOOSE_RECIPE_SIGNATURE_POLICY_CONFIG_KEY: &str = "GOOSE_RECIPE_SIGNATURE_POLICY";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecipeSignature {
+    pub key_id: String,
+    /// Base64 encoded ed25519 signature
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignaturePolicy {
+    Off,
+    #[default]
+    Warn,
+    Block,
+}
+
+impl SignaturePolicy {
+    pub fn configured() -> Self {
+        Config::global()
+            .get_param(GOOSE_RECIPE_SIGNATURE_POLICY_CONFIG_KEY)
+            .unwrap_or_default()
+    }
+}
+
+/// Outcome of checking a recipe's signature against the trusted keys
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verification {
+    Trusted { key_id: String },
+    Unsigned,
+    UntrustedKey { key_id: String },
+    Invalid { reason: String },
+}
+
+pub struct SigningKey {
+    key_pair: Ed25519KeyPair,
+}
+
+impl SigningKey {
+    /// Generate a key, returning it with its base64 encoded PKCS#8 form for saving
+    pub fn generate() -> Result<(Self, String)> {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .map_err(|_| anyhow!("Failed to generate signing key"))?;
+        let encoded = STANDARD.encode(pkcs8.as_ref());
+        Ok((Self::from_pkcs8_base64(&encoded)?, encoded))
+    }
+
+    pub fn from_pkcs8_base64(encoded: &str) -> Result<Self> {
+        let pkcs8 = STANDARD
+            .decode(encoded.trim())
+            .map_err(|e| anyhow!("Signing key is not valid base64: {}", e))?;
+        let key_pair = Ed25519KeyPair::from_pkcs8(&pkcs8)
+            .map_err(|e| anyhow!("Invalid ed25519 signing key: {}", e))?;
+        Ok(Self { key_pair })
+    }
+
+    /// Base64 encoded public key, the form listed in `GOOSE_RECIPE_TRUSTED_KEYS`
+    pub fn public_key(&self) -> String {
+        STANDARD.encode(self.key_pair.public_key().as_ref())
+    }
+
+    pub fn sign(&self, content: &[u8]) -> RecipeSignature {
+        RecipeSignature {
+            key_id: key_id(self.key_pair.public_key().as_ref()),
+            signature: STANDARD.encode(self.key_pair.sign(content).as_ref()),
+        }
+    }
+}
+
+/// Short id of a public key, used to find the key a signature was made with
+pub fn key_id(public_key: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(public_key))[..16].to_string()
+}
+
+pub fn trusted_keys() -> Vec<String> {
+    Config::global()
+        .get_param(GOOSE_RECIPE_TRUSTED_KEYS_CONFIG_KEY)
+        .unwrap_or_default()
+}
+
+pub fn verify(
+    content: &[u8],
+    signature: Option<&RecipeSignature>,
+    trusted_keys: &[String],
+) -> Verification {
+    let Some(signature) = signature else {
+        return Verification::Unsigned;
+    };
+    let public_key = trusted_keys
+        .iter()
+        .filter_map(|key| STANDARD.decode(key.trim()).ok())
+        .find(|key| key_id(key) == signature.key_id);
+    let Some(public_key) = public_key else {
+        return Verification::UntrustedKey {
+            key_id: signature.key_id.clone(),
+        };
+    };
+    let Ok(signature_bytes) = STANDARD.decode(&signature.signature) else {
+        return Verification::Invalid {
+            reason: "the signature is not valid base64".to_string(),
+        };
+    };
+    match UnparsedPublicKey::new(&ED25519, public_key).verify(content, &signature_bytes) {
+        Ok(()) => Verification::Trusted {
+            key_id: signature.key_id.clone(),
+        },
+        Err(_) => Verification::Invalid {
+            reason: format!(
+                "the content does not match the signature of key {}",
+                signature.key_id
+            ),
+        },
+    }
+}
+
+/// Apply `policy` to a verified recipe, returning a warning to show or an error if the recipe
+/// must not run
+pub fn check_trust(
+    verification: &Verification,
+    recipe: &Recipe,
+    policy: SignaturePolicy,
+) -> Result<Option<String>> {
+    let declares_extensions = recipe.extensions.as_ref().is_some_and(|e| !e.is_empty());
+    apply_policy(
+        trust_problem(verification, recipe, None, declares_extensions),
+        policy,
+    )
+}
+
+/// Describe why a recipe can't be trusted; `file` names the file the verification is for when
+/// it isn't the recipe's own file
+fn trust_problem(
+    verification: &Verification,
+    recipe: &Recipe,
+    file: Option<&Path>,
+    declares_extensions: bool,
+) -> Option<String> {
+    let built_from = file
+        .map(|path| format!(" {}, which it is built from,", path.display()))
+        .unwrap_or_default();
+    match verification {
+        Verification::Trusted { .. } => None,
+        Verification::Invalid { reason } => Some(format!(
+            "Recipe '{}' has an invalid signature{}: {}",
+            recipe.title,
+            file.map(|path| format!(" on {}", path.display()))
+                .unwrap_or_default(),
+            reason
+        )),
+        _ if !declares_extensions => None,
+        Verification::UntrustedKey { key_id } => Some(format!(
+            "Recipe '{}' declares extensions and{} is signed by untrusted key {}",
+            recipe.title, built_from, key_id
+        )),
+        Verification::Unsigned => Some(format!(
+            "Recipe '{}' declares extensions and{} is not signed",
+            recipe.title, built_from
+        )),
+    }
+}
+
+fn apply_policy(problem: Option<String>, policy: SignaturePolicy) -> Result<Option<String>> {
+    let Some(problem) = problem else {
+        return Ok(None);
+    };
+    match policy {
+        SignaturePolicy::Off => Ok(None),
+        SignaturePolicy::Warn => Ok(Some(problem)),
+        SignaturePolicy::Block => Err(anyhow!(
+            "{}; blocked by {}=block",
+            problem,
+            GOOSE_RECIPE_SIGNATURE_POLICY_CONFIG_KEY
+        )),
+    }
+}
+
+/// Path of the detached signature of a recipe file
+pub fn signature_path(recipe_path: &Path) -> PathBuf {
+    let mut path = recipe_path.as_os_str().to_owned();
+    path.push(".sig");
+    PathBuf::from(path)
+}
+
+pub fn read_signature(recipe_path: &Path) -> Result<Option<RecipeSignature>> {
+    let path = signature_path(recipe_path);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)?;
+    serde_yaml::from_str(&content)
+        .map(Some)
+        .map_err(|e| anyhow!("Invalid signature file {}: {}", path.display(), e))
+}
+
+pub fn write_signature(recipe_path: &Path, signature: &RecipeSignature) -> Result<PathBuf> {
+    let path = signature_path(recipe_path);
+    std::fs::write(&path, serde_yaml::to_string(signature)?)?;
+    Ok(path)
+}
+
+/// Verify a recipe file and every file it is built from with the configured trusted keys and
+/// policy, see `verify_recipe_files`
+pub fn check_recipe_file(recipe_path: &Path, recipe: &Recipe) -> Result<Option<String>> {
+    let policy = SignaturePolicy::configured();
+    if policy == SignaturePolicy::Off {
+        return Ok(None);
+    }
+    verify_recipe_files(recipe_path, recipe, &trusted_keys(), policy)
+}
+
+/// Verify the files `recipe` was built from: the recipe file, the recipes it extends, their
+/// `include` fragments, and the sub-recipes and step recipes they run, with the files those are
+/// built from. Every file must be signed by a trusted key when any of them declares extensions.
+pub fn verify_recipe_files(
+    recipe_path: &Path,
+    recipe: &Recipe,
+    trusted_keys: &[String],
+    policy: SignaturePolicy,
+) -> Result<Option<String>> {
+    let mut files = Vec::new();
+    let mut declares_extensions = recipe.extensions.as_ref().is_some_and(|e| !e.is_empty());
+    collect_recipe_files(recipe_path, &mut files, &mut declares_extensions)?;
+
+    let mut warnings = Vec::new();
+    for (index, path) in files.iter().enumerate() {
+        let content = std::fs::read(path)
+            .map_err(|e| anyhow!("Failed to read {} to verify it: {}", path.display(), e))?;
+        let verification = verify(&content, read_signature(path)?.as_ref(), trusted_keys);
+        let file = (index > 0).then_some(path.as_path());
+        let problem = trust_problem(&verification, recipe, file, declares_extensions);
+        warnings.extend(apply_policy(problem, policy)?);
+    }
+    Ok((!warnings.is_empty()).then(|| warnings.join("\n")))
+}
+
+fn collect_recipe_files(
+    recipe_path: &Path,
+    files: &mut Vec<PathBuf>,
+    declares_extensions: &mut bool,
+) -> Result<()> {
+    let chain = load_recipe_chain(read_recipe_file(recipe_path)?)?;
+    for recipe_file in chain {
+        if files.contains(&recipe_file.file_path) {
+            continue;
+        }
+        files.push(recipe_file.file_path.clone());
+        // Recipes that don't parse fail to build, so there is nothing more to verify
+        let Ok((raw_recipe, _)) = parse_recipe_content(
+            &recipe_file.content,
+            recipe_file.parent_dir.to_string_lossy().to_string(),
+        ) else {
+            continue;
+        };
+        *declares_extensions |= raw_recipe.extensions.is_some_and(|e| !e.is_empty());
+
+        for fragment in raw_recipe.include.iter().flatten() {
+            let path = resolve_fragment_path(fragment, &recipe_file.parent_dir);
+            if !files.contains(&path) {
+                files.push(path);
+            }
+        }
+
+        let sub_recipes = raw_recipe
+            .sub_recipes
+            .iter()
+            .flatten()
+            .map(|sub_recipe| sub_recipe.path.as_str());
+        let step_recipes = raw_recipe
+            .steps
+            .iter()
+            .flatten()
+            .filter_map(|step| step.recipe.as_deref());
+        for reference in sub_recipes.chain(step_recipes) {
+            let path = resolve_recipe_reference(reference, &recipe_file.parent_dir)?;
+            // Missing recipes fail when they are run and are never loaded
+            if path.is_file() {
+                collect_recipe_files(&path, files, declares_extensions)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recipe_with_extensions(with_extensions: bool) -> Recipe {
+        let extensions = if with_extensions {
+            "extensions:\n  - type: builtin\n    name: developer\n"
+        } else {
+            ""
+        };
+        Recipe::from_content(&format!(
+            "title: Signed\ndescription: Signed\ninstructions: Do it\n{}",
+            extensions
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let (_, encoded) = SigningKey::generate().unwrap();
+        let key = SigningKey::from_pkcs8_base64(&encoded).unwrap();
+        let trusted = vec![key.public_key()];
+        let content = b"title: Signed\n";
+        let signature = key.sign(content);
+
+        assert!(matches!(
+            verify(content, Some(&signature), &trusted),
+            Verification::Trusted { .. }
+        ));
+        assert!(matches!(
+            verify(b"title: Tampered\n", Some(&signature), &trusted),
+            Verification::Invalid { .. }
+        ));
+        assert!(matches!(
+            verify(content, Some(&signature), &[]),
+            Verification::UntrustedKey { .. }
+        ));
+        assert_eq!(verify(content, None, &trusted), Verification::Unsigned);
+    }
+
+    #[test]
+    fn test_check_trust_policy() {
+        let unsigned = Verification::Unsigned;
+        let with_extensions = recipe_with_extensions(true);
+        let without_extensions = recipe_with_extensions(false);
+
+        assert!(
+            check_trust(&unsigned, &without_extensions, SignaturePolicy::Block)
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            check_trust(&unsigned, &with_extensions, SignaturePolicy::Warn)
+                .unwrap()
+                .unwrap()
+                .contains("not signed")
+        );
+        assert!(check_trust(&unsigned, &with_extensions, SignaturePolicy::Block).is_err());
+        assert!(
+            check_trust(&unsigned, &with_extensions, SignaturePolicy::Off)
+                .unwrap()
+                .is_none()
+        );
+
+        let invalid = Verification::Invalid {
+            reason: "tampered".to_string(),
+        };
+        assert!(check_trust(&invalid, &without_extensions, SignaturePolicy::Block).is_err());
+    }
+
+    #[test]
+    fn test_signature_file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let recipe_path = dir.path().join("review.yaml");
+        let (key, _) = SigningKey::generate().unwrap();
+        let signature = key.sign(b"content");
+
+        let path = write_signature(&recipe_path, &signature).unwrap();
+        assert_eq!(path, dir.path().join("review.yaml.sig"));
+        assert_eq!(read_signature(&recipe_path).unwrap(), Some(signature));
+    }
+
+    #[test]
+    fn test_verify_recipe_files_checks_extended_recipes() {
+        let dir = tempfile::tempdir().unwrap();
+        let base_path = dir.path().join("base.yaml");
+        std::fs::write(
+            &base_path,
+            "title: Base\ndescription: Base\ninstructions: Base\nextensions:\n  - type: stdio\n    name: tool\n    cmd: run-tool\n    args: []\n",
+        )
+        .unwrap();
+        let child_path = dir.path().join("child.yaml");
+        let child_content =
+            "title: Child\ndescription: Child\ninstructions: Child\nextends: base\n";
+        std::fs::write(&child_path, child_content).unwrap();
+
+        let (key, _) = SigningKey::generate().unwrap();
+        let trusted = vec![key.public_key()];
+        write_signature(&child_path, &key.sign(child_content.as_bytes())).unwrap();
+        let recipe = Recipe::from_content(child_content).unwrap();
+
+        let err = verify_recipe_files(&child_path, &recipe, &trusted, SignaturePolicy::Block)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("base.yaml"), "{}", err);
+        assert!(err.contains("is not signed"), "{}", err);
+
+        let base_content = std::fs::read(&base_path).unwrap();
+        write_signature(&base_path, &key.sign(&base_content)).unwrap();
+        assert!(
+            verify_recipe_files(&child_path, &recipe, &trusted, SignaturePolicy::Block)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
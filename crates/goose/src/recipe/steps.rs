@@ -0,0 +1,423 @@
+//! Deterministic multi-step recipe workflows
+//!
+//! A recipe's `steps` form a DAG: each step runs either instructions in a subagent or another
+//! recipe, after all the steps it `depends_on` have finished. Steps can refer to the output of
+//! earlier steps in their templates as `{{ steps.<id>.output }}`; when a step declares a
+//! `response.json_schema`, its output is the validated JSON object, so individual fields can be
+//! used as `{{ steps.<id>.output.<field> }}`. The recipe's prompt can use step outputs as well.
+//!
+//! Completed steps are recorded in a [`StepRunState`] file after every wave of steps, so a run
+//! that failed can be resumed and only runs the steps that didn't finish or whose inputs changed.
+
+use anyhow::{anyhow, Context, Result};
+use etcetera::{choose_app_strategy, AppStrategy};
+use minijinja::{Environment, UndefinedBehavior};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use utoipa::ToSchema;
+
+use crate::config::APP_STRATEGY;
+use crate::recipe::{deserialize_value_map_as_string, Response};
+
+/// Name of the template variable holding the outputs of completed steps
+pub const STEPS_TEMPLATE_VAR: &str = "steps";
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct RecipeStep {
+    /// Identifier used in `depends_on` and in `{{ steps.<id>.output }}`
+    pub id: String,
+    /// Instructions for a subagent; set either this or `recipe`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+    /// Path to a recipe to run for this step, relative to this recipe
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipe: Option<String>,
+    /// Parameters passed to `recipe`; values may use step outputs
+    #[serde(
+        default,
+        deserialize_with = "deserialize_value_map_as_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub values: Option<HashMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    /// Schema the step's output must match
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<Response>,
+}
+
+impl RecipeStep {
+    fn json_schema(&self) -> Option<&Value> {
+        self.response
+            .as_ref()
+            .and_then(|response| response.json_schema.as_ref())
+    }
+
+    /// Task type and payload for the subagent executor, with step outputs rendered in
+    pub fn task_payload(&self, outputs: &HashMap<String, Value>) -> Result<(String, Value)> {
+        let context = json!({ STEPS_TEMPLATE_VAR: step_outputs_context(outputs) });
+
+        if let Some(instructions) = &self.instructions {
+            let mut text = render_step_template(instructions, &context)
+                .with_context(|| format!("Failed to render instructions of step '{}'", self.id))?;
+            if let Some(schema) = self.json_schema() {
+                text.push_str(&format!(
+                    "\n\nWhen you are done, reply with only a JSON object that matches this schema:\n{}",
+                    serde_json::to_string_pretty(schema)?
+                ));
+            }
+            return Ok((
+                "text_instruction".to_string(),
+                json!({ "text_instruction": text }),
+            ));
+        }
+
+        let recipe = self
+            .recipe
+            .as_ref()
+            .ok_or_else(|| anyhow!("Step '{}' has neither instructions nor recipe", self.id))?;
+        let mut parameters = serde_json::Map::new();
+        for (key, value) in self.values.iter().flatten() {
+            let rendered = render_step_template(value, &context).with_context(|| {
+                format!("Failed to render value '{}' of step '{}'", key, self.id)
+            })?;
+            parameters.insert(key.clone(), Value::String(rendered));
+        }
+        Ok((
+            "sub_recipe".to_string(),
+            json!({
+                "sub_recipe": {
+                    "name": self.id,
+                    "recipe_path": recipe,
+                    "command_parameters": parameters,
+                    "sequential_when_repeated": false,
+                }
+            }),
+        ))
+    }
+
+    /// Turn what the executor returned for this step into its output value
+    pub fn parse_output(&self, data: &Value) -> Result<Value> {
+        let text = match data {
+            Value::String(text) => text.clone(),
+            data => match data.get("result").and_then(Value::as_str) {
+                Some(text) => text.to_string(),
+                None => return Ok(data.clone()),
+            },
+        };
+
+        let Some(schema) = self.json_schema() else {
+            return Ok(match serde_json::from_str::<Value>(text.trim()) {
+                Ok(value) if value.is_object() || value.is_array() => value,
+                _ => Value::String(text.trim().to_string()),
+            });
+        };
+
+        let value = extract_json(&text)
+            .ok_or_else(|| anyhow!("Step '{}' did not produce a JSON object", self.id))?;
+        let validator = jsonschema::validator_for(schema)
+            .map_err(|e| anyhow!("Invalid response schema of step '{}': {}", self.id, e))?;
+        let errors: Vec<String> = validator
+            .iter_errors(&value)
+            .map(|error| format!("{}: {}", error.instance_path, error))
+            .collect();
+        if !errors.is_empty() {
+            return Err(anyhow!(
+                "Output of step '{}' does not match its schema: {}",
+                self.id,
+                errors.join("; ")
+            ));
+        }
+        Ok(value)
+    }
+}
+
+/// Check that steps form a DAG and return their ids in an order where every step comes after
+/// the steps it depends on
+pub fn validate_steps(steps: &[RecipeStep]) -> Result<Vec<String>> {
+    let mut ids = HashSet::new();
+    for step in steps {
+        if step.id.is_empty()
+            || step.id.starts_with(|c: char| c.is_ascii_digit())
+            || !step
+                .id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(anyhow!(
+                "Invalid step id '{}': use letters, digits and underscores, not starting with a digit",
+                step.id
+            ));
+        }
+        if !ids.insert(step.id.as_str()) {
+            return Err(anyhow!("Duplicate step id '{}'", step.id));
+        }
+        if step.instructions.is_some() == step.recipe.is_some() {
+            return Err(anyhow!(
+                "Step '{}' must have exactly one of instructions or recipe",
+                step.id
+            ));
+        }
+    }
+    for step in steps {
+        for dependency in &step.depends_on {
+            if !ids.contains(dependency.as_str()) {
+                return Err(anyhow!(
+                    "Step '{}' depends on unknown step '{}'",
+                    step.id,
+                    dependency
+                ));
+            }
+        }
+    }
+
+    let mut order = Vec::new();
+    let mut done = HashSet::new();
+    while order.len() < steps.len() {
+        let ready: Vec<&RecipeStep> = steps
+            .iter()
+            .filter(|step| !done.contains(step.id.as_str()))
+            .filter(|step| step.depends_on.iter().all(|d| done.contains(d.as_str())))
+            .collect();
+        if ready.is_empty() {
+            let remaining: Vec<&str> = steps
+                .iter()
+                .filter(|step| !done.contains(step.id.as_str()))
+                .map(|step| step.id.as_str())
+                .collect();
+            return Err(anyhow!(
+                "Steps have a dependency cycle among: {}",
+                remaining.join(", ")
+            ));
+        }
+        for step in ready {
+            done.insert(step.id.as_str());
+            order.push(step.id.clone());
+        }
+    }
+    Ok(order)
+}
+
+/// Template context for step outputs: `steps.<id>.output`
+pub fn step_outputs_context(outputs: &HashMap<String, Value>) -> Value {
+    Value::Object(
+        outputs
+            .iter()
+            .map(|(id, output)| (id.clone(), json!({ "output": output })))
+            .collect(),
+    )
+}
+
+/// Render a template that refers to step outputs, failing on references to missing steps
+pub fn render_step_template(template: &str, context: &Value) -> Result<String> {
+    let mut env = Environment::new();
+    env.set_undefined_behavior(UndefinedBehavior::Strict);
+    env.render_str(template, context)
+        .map_err(|e| anyhow!("Failed to render step template: {}", e))
+}
+
+/// Render the outputs of all steps into a recipe prompt
+pub fn render_with_step_outputs(
+    template: &str,
+    outputs: &HashMap<String, Value>,
+) -> Result<String> {
+    render_step_template(
+        template,
+        &json!({ STEPS_TEMPLATE_VAR: step_outputs_context(outputs) }),
+    )
+}
+
+pub(crate) fn extract_json(text: &str) -> Option<Value> {
+    let text = text.trim();
+    if let Ok(value) = serde_json::from_str::<Value>(text) {
+        return Some(value);
+    }
+    if let Some(start) = text.rfind("```json") {
+        let body = &text[start + "```json".len()..];
+        if let Some(end) = body.find("```") {
+            if let Ok(value) = serde_json::from_str::<Value>(body[..end].trim()) {
+                return Some(value);
+            }
+        }
+    }
+    let start = text.find('{')?;
+    let end = text.rfind('}')?;
+    serde_json::from_str::<Value>(&text[start..=end]).ok()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CompletedStep {
+    /// Hash of the rendered task, so a step reruns if its inputs change
+    pub input_hash: String,
+    pub output: Value,
+}
+
+/// Steps of a recipe run that completed, persisted so a failed run can resume
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct StepRunState {
+    pub steps: HashMap<String, CompletedStep>,
+}
+
+impl StepRunState {
+    /// File holding the state of the run identified by `run_key`, e.g. the recipe and its
+    /// parameters
+    pub fn path_for(run_key: &str) -> Result<PathBuf> {
+        let runs_dir = choose_app_strategy(APP_STRATEGY.clone())
+            .context("goose requires a home dir")?
+            .data_dir()
+            .join("recipe_runs");
+        fs::create_dir_all(&runs_dir)?;
+        let hash = blake3::hash(run_key.as_bytes()).to_hex();
+        Ok(runs_dir.join(format!("{}.json", &hash[..16])))
+    }
+
+    pub fn load(path: &Path) -> Self {
+        match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring unreadable step state {}: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    /// Output of a step that already completed with the same inputs
+    pub fn completed_output(&self, id: &str, input_hash: &str) -> Option<&Value> {
+        self.steps
+            .get(id)
+            .filter(|step| step.input_hash == input_hash)
+            .map(|step| &step.output)
+    }
+
+    pub fn record(&mut self, id: &str, input_hash: String, output: Value) {
+        self.steps
+            .insert(id.to_string(), CompletedStep { input_hash, output });
+    }
+}
+
+pub fn input_hash(task_type: &str, payload: &Value) -> String {
+    blake3::hash(format!("{}:{}", task_type, payload).as_bytes())
+        .to_hex()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(id: &str, depends_on: &[&str]) -> RecipeStep {
+        RecipeStep {
+            id: id.to_string(),
+            instructions: Some(format!("Do {}", id)),
+            recipe: None,
+            values: None,
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+            response: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_steps_orders_dependencies() {
+        let steps = vec![
+            step("verify", &["implement"]),
+            step("implement", &["plan"]),
+            step("plan", &["analyze"]),
+            step("analyze", &[]),
+        ];
+        assert_eq!(
+            validate_steps(&steps).unwrap(),
+            vec!["analyze", "plan", "implement", "verify"]
+        );
+
+        let cycle = vec![step("a", &["b"]), step("b", &["a"])];
+        assert!(validate_steps(&cycle)
+            .unwrap_err()
+            .to_string()
+            .contains("cycle"));
+
+        let unknown = vec![step("a", &["missing"])];
+        assert!(validate_steps(&unknown).is_err());
+
+        let duplicate = vec![step("a", &[]), step("a", &[])];
+        assert!(validate_steps(&duplicate).is_err());
+
+        assert!(validate_steps(&[step("not-valid", &[])]).is_err());
+    }
+
+    #[test]
+    fn test_task_payload_renders_step_outputs() {
+        let mut implement = step("implement", &["plan"]);
+        implement.instructions =
+            Some("Implement {{ steps.plan.output.summary }} in {{ steps.plan.output.files | join(', ') }}".to_string());
+
+        let outputs = HashMap::from([(
+            "plan".to_string(),
+            json!({"summary": "the parser", "files": ["a.rs", "b.rs"]}),
+        )]);
+        let (task_type, payload) = implement.task_payload(&outputs).unwrap();
+        assert_eq!(task_type, "text_instruction");
+        assert_eq!(
+            payload["text_instruction"],
+            "Implement the parser in a.rs, b.rs"
+        );
+
+        assert!(implement.task_payload(&HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_parse_output_validates_schema() {
+        let mut plan = step("plan", &[]);
+        plan.response = Some(Response {
+            json_schema: Some(json!({
+                "type": "object",
+                "properties": {"summary": {"type": "string"}},
+                "required": ["summary"]
+            })),
+        });
+
+        let output = plan
+            .parse_output(&json!({"result": "Here it is:\n```json\n{\"summary\": \"done\"}\n```"}))
+            .unwrap();
+        assert_eq!(output, json!({"summary": "done"}));
+
+        assert!(plan
+            .parse_output(&json!({"result": "{\"other\": 1}"}))
+            .is_err());
+
+        let analyze = step("analyze", &[]);
+        assert_eq!(
+            analyze
+                .parse_output(&json!({"result": " plain text "}))
+                .unwrap(),
+            json!("plain text")
+        );
+    }
+
+    #[test]
+    fn test_run_state_skips_unchanged_steps() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+
+        let mut state = StepRunState::load(&path);
+        state.record("analyze", "hash-1".to_string(), json!("findings"));
+        state.save(&path).unwrap();
+
+        let state = StepRunState::load(&path);
+        assert_eq!(
+            state.completed_output("analyze", "hash-1"),
+            Some(&json!("findings"))
+        );
+        assert_eq!(state.completed_output("analyze", "hash-2"), None);
+        assert_eq!(state.completed_output("plan", "hash-1"), None);
+    }
+}
@@ -3,6 +3,7 @@ use std::{
     path::Path,
 };
 
+use crate::recipe::steps::STEPS_TEMPLATE_VAR;
 use crate::recipe::{Recipe, BUILT_IN_RECIPE_DIR_PARAM};
 use anyhow::Result;
 use minijinja::{Environment, UndefinedBehavior};
@@ -15,7 +16,14 @@ const CLOSE_BRACE: &str = "}}";
 fn preprocess_template_variables(content: &str) -> Result<String> {
     let all_template_variables = extract_template_variables(content);
     let complex_template_variables = filter_complex_variables(&all_template_variables);
-    let unparsable_template_variables = filter_unparseable_variables(&complex_template_variables)?;
+    let mut unparsable_template_variables =
+        filter_unparseable_variables(&complex_template_variables)?;
+    // Step outputs only exist once the steps have run, so keep those references for later
+    for var in filter_step_output_variables(&all_template_variables) {
+        if !unparsable_template_variables.contains(&var) {
+            unparsable_template_variables.push(var);
+        }
+    }
     replace_unparseable_vars_with_raw(content, &unparsable_template_variables)
 }
 
@@ -37,6 +45,15 @@ fn filter_complex_variables(template_variables: &[String]) -> Vec<String> {
         .collect()
 }
 
+fn filter_step_output_variables(template_variables: &[String]) -> Vec<String> {
+    let step_var_re = Regex::new(&format!(r"^\s*{}\b", STEPS_TEMPLATE_VAR)).unwrap();
+    template_variables
+        .iter()
+        .filter(|var| step_var_re.is_match(var))
+        .cloned()
+        .collect()
+}
+
 fn filter_unparseable_variables(template_variables: &[String]) -> Result<Vec<String>> {
     let mut vars_to_convert = Vec::new();
 
@@ -280,6 +297,22 @@ mod tests {
             assert_eq!(result, "Hello and {{invalid var}}");
         }
 
+        #[test]
+        fn test_render_content_keeps_step_outputs() {
+            let content = "Plan for {{ name }}: {{ steps.plan.output.summary }}";
+            let params = HashMap::from([
+                ("recipe_dir".to_string(), "some_dir".to_string()),
+                ("name".to_string(), "parser".to_string()),
+            ]);
+            let result = render_recipe_content_with_params(content, &params).unwrap();
+            assert_eq!(result, "Plan for parser: {{ steps.plan.output.summary }}");
+
+            let content = "{{ steps.plan.output }} and again {{ steps.plan.output }}";
+            let params = HashMap::from([("recipe_dir".to_string(), "some_dir".to_string())]);
+            let result = render_recipe_content_with_params(content, &params).unwrap();
+            assert_eq!(result, content);
+        }
+
         #[test]
         fn test_empty_prompt() {
             let content = r#"
@@ -2,6 +2,7 @@ use anyhow::Result;
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 use thiserror::Error;
 
+use crate::recipe::signing::{self, RecipeSignature, SigningKey, Verification};
 use crate::recipe::Recipe;
 
 #[derive(Error, Debug)]
@@ -16,7 +17,43 @@ pub fn encode(recipe: &Recipe) -> Result<String, serde_json::Error> {
     Ok(encoded)
 }
 
+/// Encode a recipe followed by a `.` and the signature of its JSON
+pub fn encode_signed(recipe: &Recipe, key: &SigningKey) -> Result<String, serde_json::Error> {
+    let recipe_json = serde_json::to_string(recipe)?;
+    let signature_json = serde_json::to_string(&key.sign(recipe_json.as_bytes()))?;
+    Ok(format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(recipe_json.as_bytes()),
+        URL_SAFE_NO_PAD.encode(signature_json.as_bytes())
+    ))
+}
+
+/// Decode a deeplink and verify its signature, if any, against the trusted keys
+pub fn decode_verified(link: &str) -> Result<(Recipe, Verification), DecodeError> {
+    let recipe = decode(link)?;
+    let Some((recipe_part, signature_part)) = link.split_once('.') else {
+        return Ok((recipe, Verification::Unsigned));
+    };
+
+    let signature = URL_SAFE_NO_PAD
+        .decode(signature_part)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<RecipeSignature>(&bytes).ok());
+    let verification = match (URL_SAFE_NO_PAD.decode(recipe_part), signature) {
+        (Ok(recipe_json), Some(signature)) => {
+            signing::verify(&recipe_json, Some(&signature), &signing::trusted_keys())
+        }
+        _ => Verification::Invalid {
+            reason: "the deeplink signature could not be decoded".to_string(),
+        },
+    };
+    Ok((recipe, verification))
+}
+
 pub fn decode(link: &str) -> Result<Recipe, DecodeError> {
+    // Signed links carry the signature after a `.`, which no supported encoding uses
+    let link = link.split_once('.').map_or(link, |(recipe, _)| recipe);
+
     // Handle the current format: URL-safe Base64 without padding.
     if let Ok(decoded_bytes) = URL_SAFE_NO_PAD.decode(link) {
         if let Ok(recipe_json) = String::from_utf8(decoded_bytes) {
@@ -99,6 +136,23 @@ mod tests {
         assert_eq!(recipe.instructions, decoded_recipe.instructions);
     }
 
+    #[test]
+    fn test_signed_deeplink() {
+        let recipe = create_test_recipe();
+        let (key, _) = SigningKey::generate().unwrap();
+        let encoded = encode_signed(&recipe, &key).unwrap();
+
+        let decoded_recipe = decode(&encoded).expect("Failed to decode signed deeplink");
+        assert_eq!(recipe.title, decoded_recipe.title);
+
+        let (_, verification) = decode_verified(&encoded).unwrap();
+        assert!(matches!(verification, Verification::UntrustedKey { .. }));
+
+        let unsigned = encode(&recipe).unwrap();
+        let (_, verification) = decode_verified(&unsigned).unwrap();
+        assert_eq!(verification, Verification::Unsigned);
+    }
+
     #[test]
     fn test_decode_invalid_input() {
         let result = decode("invalid_base64!");
@@ -0,0 +1,356 @@
+//! Run history of scheduled jobs
+//!
+//! Every execution of a scheduled job appends a [`JobRun`] to a JSON lines file named after
+//! the job, next to the schedule storage file. The entry summarizes the session the run wrote:
+//! token usage, estimated cost, the end of the final reply and the collected artifacts.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::providers::pricing::{get_model_pricing, parse_model_id};
+use crate::session::storage::{self, Identifier};
+
+/// Runs kept in the history of each job, oldest dropped first
+pub const MAX_RUN_HISTORY: usize = 200;
+/// Characters of the final reply kept in a run's output excerpt
+const OUTPUT_EXCERPT_CHARS: usize = 500;
+
+/// What started a run
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RunTrigger {
+    /// The job's cron expression fired
+    Cron,
+    /// Made up for a cron run missed while goose was not running
+    CatchUp,
+    /// Started by hand with run now
+    Manual,
+    /// Files matching a watched pattern changed
+    FileWatch,
+    /// The job's webhook was called
+    Webhook,
+    /// A job it waits for finished
+    JobCompleted,
+}
+
+/// How a run ended
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Success,
+    Failed,
+    Killed,
+    TimedOut,
+}
+
+/// One execution of a scheduled job
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct JobRun {
+    pub job_id: String,
+    pub trigger: RunTrigger,
+    /// Attempt of the run this entry records; retries of a failed run count up from 1
+    #[serde(default = "first_attempt")]
+    pub attempt: u32,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub status: RunStatus,
+    #[serde(default)]
+    pub error: Option<String>,
+    /// Session written by the run, if it got far enough to start one
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub input_tokens: Option<i32>,
+    #[serde(default)]
+    pub output_tokens: Option<i32>,
+    #[serde(default)]
+    pub total_tokens: Option<i32>,
+    /// Estimated from the provider's pricing, when known
+    #[serde(default)]
+    pub cost_usd: Option<f64>,
+    /// The end of the final assistant reply
+    #[serde(default)]
+    pub output_excerpt: Option<String>,
+    /// Artifact paths relative to the run's working directory
+    #[serde(default)]
+    pub artifacts: Vec<String>,
+}
+
+fn first_attempt() -> u32 {
+    1
+}
+
+impl JobRun {
+    pub fn duration_seconds(&self) -> i64 {
+        (self.finished_at - self.started_at).num_seconds()
+    }
+}
+
+/// The parts of a run known to the scheduler; the rest is read from the run's session
+pub struct RunOutcome<'a> {
+    pub job_id: &'a str,
+    pub trigger: RunTrigger,
+    pub attempt: u32,
+    pub started_at: DateTime<Utc>,
+    pub status: RunStatus,
+    pub error: Option<String>,
+    pub session_id: Option<&'a str>,
+    /// Provider and model the run used, to estimate its cost
+    pub provider_model: Option<(String, String)>,
+}
+
+/// Build the history entry of a run that just finished
+pub async fn summarize_run(outcome: RunOutcome<'_>) -> JobRun {
+    let mut run = JobRun {
+        job_id: outcome.job_id.to_string(),
+        trigger: outcome.trigger,
+        attempt: outcome.attempt,
+        started_at: outcome.started_at,
+        finished_at: Utc::now(),
+        status: outcome.status,
+        error: outcome.error,
+        session_id: None,
+        input_tokens: None,
+        output_tokens: None,
+        total_tokens: None,
+        cost_usd: None,
+        output_excerpt: None,
+        artifacts: Vec::new(),
+    };
+
+    let session_file = outcome
+        .session_id
+        .and_then(|id| storage::get_path(Identifier::Name(id.to_string())).ok())
+        .filter(|path| path.is_file());
+    let Some(session_file) = session_file else {
+        return run;
+    };
+    run.session_id = outcome.session_id.map(str::to_string);
+    summarize_session(&mut run, &session_file);
+
+    if let (Some((provider, model)), Some(input), Some(output)) =
+        (outcome.provider_model, run.input_tokens, run.output_tokens)
+    {
+        run.cost_usd = estimate_cost_usd(&provider, &model, input, output).await;
+    }
+    run
+}
+
+/// Fill in the token usage, output excerpt and artifacts recorded in a session file
+pub fn summarize_session(run: &mut JobRun, session_file: &Path) {
+    match storage::read_metadata(session_file) {
+        Ok(metadata) => {
+            run.input_tokens = metadata.accumulated_input_tokens.or(metadata.input_tokens);
+            run.output_tokens = metadata
+                .accumulated_output_tokens
+                .or(metadata.output_tokens);
+            run.total_tokens = metadata.accumulated_total_tokens.or(metadata.total_tokens);
+            run.artifacts = metadata.artifacts.into_iter().map(|a| a.path).collect();
+        }
+        Err(e) => tracing::warn!(
+            "Failed to read metadata of session {}: {}",
+            session_file.display(),
+            e
+        ),
+    }
+    match storage::read_messages(session_file) {
+        Ok(messages) => {
+            run.output_excerpt = messages
+                .iter()
+                .rev()
+                .filter(|m| m.role == rmcp::model::Role::Assistant)
+                .map(|m| m.as_concat_text())
+                .find(|text| !text.trim().is_empty())
+                .map(|text| excerpt(&text));
+        }
+        Err(e) => tracing::warn!(
+            "Failed to read messages of session {}: {}",
+            session_file.display(),
+            e
+        ),
+    }
+}
+
+fn excerpt(text: &str) -> String {
+    let text = text.trim();
+    let count = text.chars().count();
+    if count <= OUTPUT_EXCERPT_CHARS {
+        return text.to_string();
+    }
+    let tail: String = text.chars().skip(count - OUTPUT_EXCERPT_CHARS).collect();
+    format!("…{}", tail)
+}
+
+async fn estimate_cost_usd(provider: &str, model: &str, input: i32, output: i32) -> Option<f64> {
+    let (provider, model) = match provider {
+        "openrouter" => parse_model_id(model)?,
+        _ => (provider.to_string(), model.to_string()),
+    };
+    let pricing = get_model_pricing(&provider, &model).await?;
+    Some(pricing.input_cost * input as f64 + pricing.output_cost * output as f64)
+}
+
+/// Directory holding the run history files, next to the schedule storage file
+pub fn history_dir(storage_path: &Path) -> PathBuf {
+    storage_path.with_file_name("schedule_history")
+}
+
+/// Job IDs are written by users, so only letters, digits, '-' and '_' are kept as they are and
+/// every other byte is escaped, which keeps the file inside the history directory
+fn history_file(storage_path: &Path, job_id: &str) -> PathBuf {
+    let mut name = String::with_capacity(job_id.len());
+    for byte in job_id.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => name.push(byte as char),
+            _ => name.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    history_dir(storage_path).join(format!("{}.jsonl", name))
+}
+
+/// Append a run to its job's history as a single line. Once the file holds twice
+/// [`MAX_RUN_HISTORY`] runs it is rewritten through a temporary file with only the newest ones
+pub fn append_run(storage_path: &Path, run: &JobRun) -> io::Result<()> {
+    let path = history_file(storage_path, &run.job_id);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_string(run)?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?
+        .write_all(line.as_bytes())?;
+
+    let content = fs::read_to_string(&path)?;
+    let lines: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
+    if lines.len() <= 2 * MAX_RUN_HISTORY {
+        return Ok(());
+    }
+    let mut kept = lines[lines.len() - MAX_RUN_HISTORY..].join("\n");
+    kept.push('\n');
+    let temp_path = path.with_extension("jsonl.tmp");
+    fs::write(&temp_path, kept)?;
+    fs::rename(&temp_path, &path)
+}
+
+/// The newest `limit` runs of a job, newest first, at most [`MAX_RUN_HISTORY`]
+pub fn read_runs(storage_path: &Path, job_id: &str, limit: usize) -> io::Result<Vec<JobRun>> {
+    let content = match fs::read_to_string(history_file(storage_path, job_id)) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    Ok(content
+        .lines()
+        .rev()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(run) => Some(run),
+            Err(e) => {
+                tracing::warn!("Skipping unreadable run of job '{}': {}", job_id, e);
+                None
+            }
+        })
+        .take(limit.min(MAX_RUN_HISTORY))
+        .collect())
+}
+
+/// Delete a job's run history
+pub fn remove_history(storage_path: &Path, job_id: &str) -> io::Result<()> {
+    match fs::remove_file(history_file(storage_path, job_id)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn run(job_id: &str, status: RunStatus) -> JobRun {
+        JobRun {
+            job_id: job_id.to_string(),
+            trigger: RunTrigger::Cron,
+            attempt: 1,
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+            status,
+            error: None,
+            session_id: None,
+            input_tokens: None,
+            output_tokens: None,
+            total_tokens: None,
+            cost_usd: None,
+            output_excerpt: None,
+            artifacts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_history_keeps_newest_runs() {
+        let dir = tempdir().unwrap();
+        let storage_path = dir.path().join("schedules.json");
+        assert!(read_runs(&storage_path, "nightly", 10).unwrap().is_empty());
+
+        for _ in 0..MAX_RUN_HISTORY {
+            append_run(&storage_path, &run("nightly", RunStatus::Success)).unwrap();
+        }
+        append_run(&storage_path, &run("nightly", RunStatus::Killed)).unwrap();
+        append_run(&storage_path, &run("other", RunStatus::Failed)).unwrap();
+
+        let runs = read_runs(&storage_path, "nightly", usize::MAX).unwrap();
+        assert_eq!(runs.len(), MAX_RUN_HISTORY);
+        assert_eq!(runs[0].status, RunStatus::Killed);
+        assert_eq!(read_runs(&storage_path, "nightly", 3).unwrap().len(), 3);
+
+        remove_history(&storage_path, "nightly").unwrap();
+        assert!(read_runs(&storage_path, "nightly", 10).unwrap().is_empty());
+        assert_eq!(read_runs(&storage_path, "other", 10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_history_file_stays_in_history_dir() {
+        let storage_path = Path::new("/data/schedules.json");
+        let path = history_file(storage_path, "../../etc/x");
+        assert_eq!(path.parent().unwrap(), history_dir(storage_path));
+        assert_eq!(path.file_name().unwrap(), "%2E%2E%2F%2E%2E%2Fetc%2Fx.jsonl");
+        assert_eq!(
+            history_file(storage_path, "nightly-report_2")
+                .file_name()
+                .unwrap(),
+            "nightly-report_2.jsonl"
+        );
+    }
+
+    #[test]
+    fn test_history_compacts_to_newest_runs() {
+        let dir = tempdir().unwrap();
+        let storage_path = dir.path().join("schedules.json");
+        for _ in 0..2 * MAX_RUN_HISTORY {
+            append_run(&storage_path, &run("nightly", RunStatus::Success)).unwrap();
+        }
+        append_run(&storage_path, &run("nightly", RunStatus::Killed)).unwrap();
+
+        let content = fs::read_to_string(history_file(&storage_path, "nightly")).unwrap();
+        assert_eq!(content.lines().count(), MAX_RUN_HISTORY);
+        let runs = read_runs(&storage_path, "nightly", 1).unwrap();
+        assert_eq!(runs[0].status, RunStatus::Killed);
+    }
+
+    #[test]
+    fn test_excerpt_keeps_end_of_reply() {
+        assert_eq!(excerpt("  done  "), "done");
+        let long = format!("{}end", "x".repeat(OUTPUT_EXCERPT_CHARS));
+        let short = excerpt(&long);
+        assert!(short.starts_with('…') && short.ends_with("end"));
+        assert_eq!(short.chars().count(), OUTPUT_EXCERPT_CHARS + 1);
+    }
+}
@@ -1,7 +1,8 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 
-use crate::scheduler::{ScheduledJob, SchedulerError};
+use crate::scheduler::{ScheduledJob, ScheduledJobUpdate, SchedulerError};
+use crate::scheduler_history::JobRun;
 use crate::session::storage::SessionMetadata;
 
 /// Common trait for all scheduler implementations
@@ -32,10 +33,43 @@ pub trait SchedulerTrait: Send + Sync {
         limit: usize,
     ) -> Result<Vec<(String, SessionMetadata)>, SchedulerError>;
 
+    /// Get the newest runs of a scheduled job, newest first
+    async fn run_history(
+        &self,
+        sched_id: &str,
+        limit: usize,
+    ) -> Result<Vec<JobRun>, SchedulerError>;
+
     /// Update a schedule's cron expression
     async fn update_schedule(&self, sched_id: &str, new_cron: String)
         -> Result<(), SchedulerError>;
 
+    /// Update a job's parameter values and provider, model, working directory and extension
+    /// overrides
+    async fn update_job(
+        &self,
+        sched_id: &str,
+        _update: ScheduledJobUpdate,
+    ) -> Result<(), SchedulerError> {
+        Err(SchedulerError::SchedulerInternalError(format!(
+            "Editing the settings of job '{}' is not supported by this scheduler",
+            sched_id
+        )))
+    }
+
+    /// Run a job from its webhook trigger, passing the request body to the recipe
+    async fn trigger_webhook(
+        &self,
+        sched_id: &str,
+        _secret: &str,
+        _body: String,
+    ) -> Result<(), SchedulerError> {
+        Err(SchedulerError::SchedulerInternalError(format!(
+            "Webhook triggers of job '{}' are not supported by this scheduler",
+            sched_id
+        )))
+    }
+
     /// Kill a running job
     async fn kill_running_job(&self, sched_id: &str) -> Result<(), SchedulerError>;
 
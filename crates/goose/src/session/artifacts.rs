@@ -0,0 +1,164 @@
+//! Output files declared by a recipe's `artifacts`
+//!
+//! After a recipe run, files in the working directory matching the recipe's globs are copied
+//! to `artifacts/<session id>/` next to the session files and listed in the session metadata,
+//! keeping their path relative to the working directory.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::session::storage::update_metadata_with;
+
+/// An output file collected for a session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SessionArtifact {
+    /// Path relative to the working directory of the run
+    pub path: String,
+    /// Size in bytes
+    pub size: u64,
+}
+
+/// Directory holding the artifacts of the session stored in `session_file`
+pub fn artifacts_dir(session_file: &Path) -> PathBuf {
+    let session_id = session_file
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    session_file
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join("artifacts")
+        .join(session_id)
+}
+
+/// Copy the files in `working_dir` matching `patterns` to `destination`
+pub fn collect_artifacts(
+    patterns: &[String],
+    working_dir: &Path,
+    destination: &Path,
+) -> Result<Vec<SessionArtifact>> {
+    let working_dir = working_dir.canonicalize()?;
+    let mut artifacts: Vec<SessionArtifact> = Vec::new();
+    for pattern in patterns {
+        let full_pattern = format!(
+            "{}/{}",
+            glob::Pattern::escape(&working_dir.to_string_lossy()),
+            pattern
+        );
+        let paths = glob::glob(&full_pattern)
+            .map_err(|e| anyhow!("Invalid artifact glob {}: {}", pattern, e))?;
+        let mut matched = false;
+        for path in paths.filter_map(|path| path.ok()) {
+            if !path.is_file() {
+                continue;
+            }
+            let Some(relative) = path
+                .canonicalize()
+                .ok()
+                .and_then(|path| path.strip_prefix(&working_dir).ok().map(Path::to_path_buf))
+            else {
+                tracing::warn!(
+                    "Skipping artifact {} outside the working directory",
+                    path.display()
+                );
+                continue;
+            };
+            matched = true;
+            let relative_str = relative.to_string_lossy().replace('\\', "/");
+            if artifacts
+                .iter()
+                .any(|artifact| artifact.path == relative_str)
+            {
+                continue;
+            }
+
+            let target = destination.join(&relative);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let size = fs::copy(&path, &target)?;
+            artifacts.push(SessionArtifact {
+                path: relative_str,
+                size,
+            });
+        }
+        if !matched {
+            tracing::warn!("No files matched artifact glob {}", pattern);
+        }
+    }
+    Ok(artifacts)
+}
+
+/// Collect the artifacts of a run and record them in the session metadata
+pub async fn record_artifacts(
+    session_file: &Path,
+    patterns: &[String],
+    working_dir: &Path,
+) -> Result<Vec<SessionArtifact>> {
+    let artifacts = collect_artifacts(patterns, working_dir, &artifacts_dir(session_file))?;
+    update_metadata_with(session_file, |metadata| {
+        metadata.artifacts = artifacts.clone();
+        Ok(())
+    })?;
+    Ok(artifacts)
+}
+
+/// Location of a collected artifact, rejecting paths that leave the artifact directory
+pub fn artifact_path(session_file: &Path, relative: &str) -> Result<PathBuf> {
+    let relative = Path::new(relative);
+    if !relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Err(anyhow!("Invalid artifact path {}", relative.display()));
+    }
+    let path = artifacts_dir(session_file).join(relative);
+    if !path.is_file() {
+        return Err(anyhow!("Artifact {} not found", relative.display()));
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_collect_artifacts() {
+        let working_dir = TempDir::new().unwrap();
+        let reports = working_dir.path().join("reports");
+        fs::create_dir_all(&reports).unwrap();
+        fs::write(reports.join("summary.md"), "# Summary").unwrap();
+        fs::write(reports.join("data.csv"), "a,b").unwrap();
+        fs::write(working_dir.path().join("notes.txt"), "ignored").unwrap();
+        let destination = TempDir::new().unwrap();
+
+        let patterns = vec!["reports/*.md".to_string(), "reports/**/*".to_string()];
+        let artifacts =
+            collect_artifacts(&patterns, working_dir.path(), destination.path()).unwrap();
+
+        let paths: Vec<&str> = artifacts.iter().map(|a| a.path.as_str()).collect();
+        assert_eq!(paths, vec!["reports/summary.md", "reports/data.csv"]);
+        assert_eq!(artifacts[0].size, 9);
+        assert!(destination.path().join("reports/data.csv").is_file());
+    }
+
+    #[test]
+    fn test_artifact_path_rejects_traversal() {
+        let dir = TempDir::new().unwrap();
+        let session_file = dir.path().join("20250101_120000.jsonl");
+        let artifact = artifacts_dir(&session_file).join("out.txt");
+        fs::create_dir_all(artifact.parent().unwrap()).unwrap();
+        fs::write(&artifact, "done").unwrap();
+
+        assert_eq!(artifact_path(&session_file, "out.txt").unwrap(), artifact);
+        assert!(artifact_path(&session_file, "../20250101_120000.jsonl").is_err());
+        assert!(artifact_path(&session_file, "/etc/passwd").is_err());
+        assert!(artifact_path(&session_file, "missing.txt").is_err());
+    }
+}
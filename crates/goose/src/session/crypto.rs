@@ -0,0 +1,411 @@
+// Optional encryption at rest for session files.
+//
+// Each line of a session file (the metadata line and every message line) is encrypted on its
+// own, so the file stays line-oriented and the journal, the append path and metadata-only reads
+// keep working unchanged. Encrypted lines carry a prefix; lines without it are read as plain
+// JSON, which keeps existing sessions readable until they are migrated. Each encrypted line is
+// bound to its session and its line number, so lines can't be moved within a session or
+// between sessions without failing to decrypt.
+//
+// The key is either a random key held in the system keyring through `Config` secret storage,
+// or derived from a passphrase with PBKDF2.
+
+use crate::config::Config;
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use once_cell::sync::Lazy;
+use once_cell::unsync::OnceCell;
+use rand::RngCore;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::num::NonZeroU32;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Config parameter selecting how session files are encrypted
+pub const ENCRYPTION_CONFIG_KEY: &str = "GOOSE_SESSION_ENCRYPTION";
+/// Secret holding the keyring-managed session key
+pub const KEY_SECRET: &str = "GOOSE_SESSION_KEY";
+/// Environment variable (or secret) holding the passphrase
+pub const PASSPHRASE_SECRET: &str = "GOOSE_SESSION_PASSPHRASE";
+/// Config parameter holding the salt used to derive a key from the passphrase
+pub const SALT_CONFIG_KEY: &str = "GOOSE_SESSION_KEY_SALT";
+
+const ENCRYPTED_LINE_PREFIX: &str = "goose-enc-v2:";
+const KEY_LEN: usize = 32;
+const PBKDF2_ITERATIONS: u32 = 600_000;
+
+/// Where the session encryption key comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EncryptionMode {
+    /// Session files are written in plaintext
+    Off,
+    /// Random key stored in the system keyring
+    Keyring,
+    /// Key derived from GOOSE_SESSION_PASSPHRASE
+    Passphrase,
+}
+
+/// Key used to encrypt and decrypt session lines
+#[derive(Clone)]
+pub struct SessionKey([u8; KEY_LEN]);
+
+impl SessionKey {
+    pub fn generate() -> Self {
+        let mut bytes = [0u8; KEY_LEN];
+        rand::rngs::OsRng.fill_bytes(&mut bytes);
+        Self(bytes)
+    }
+
+    pub fn from_passphrase(passphrase: &str, salt: &[u8]) -> Self {
+        let mut bytes = [0u8; KEY_LEN];
+        pbkdf2::derive(
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            NonZeroU32::new(PBKDF2_ITERATIONS).expect("iterations are non-zero"),
+            salt,
+            passphrase.as_bytes(),
+            &mut bytes,
+        );
+        Self(bytes)
+    }
+
+    fn aead_key(&self) -> LessSafeKey {
+        LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &self.0).expect("key length is valid"))
+    }
+}
+
+static SESSION_KEYS: Lazy<Mutex<HashMap<EncryptionMode, SessionKey>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// The encryption mode configured through GOOSE_SESSION_ENCRYPTION, off by default
+pub fn encryption_mode() -> EncryptionMode {
+    Config::global()
+        .get_param::<EncryptionMode>(ENCRYPTION_CONFIG_KEY)
+        .unwrap_or(EncryptionMode::Off)
+}
+
+pub fn set_encryption_mode(mode: EncryptionMode) -> Result<()> {
+    Config::global().set_param(ENCRYPTION_CONFIG_KEY, serde_json::to_value(mode)?)?;
+    Ok(())
+}
+
+/// Use a key derived from `passphrase` for this process, e.g. after prompting for it
+pub fn unlock_with_passphrase(passphrase: &str) -> Result<()> {
+    let key = SessionKey::from_passphrase(passphrase, &passphrase_salt(true)?);
+    SESSION_KEYS
+        .lock()
+        .unwrap()
+        .insert(EncryptionMode::Passphrase, key);
+    Ok(())
+}
+
+fn passphrase_salt(create: bool) -> Result<Vec<u8>> {
+    let config = Config::global();
+    if let Ok(salt) = config.get_param::<String>(SALT_CONFIG_KEY) {
+        return Ok(STANDARD.decode(salt)?);
+    }
+    if !create {
+        return Err(anyhow::anyhow!("No session passphrase salt configured"));
+    }
+    let mut salt = vec![0u8; 16];
+    rand::rngs::OsRng.fill_bytes(&mut salt);
+    config.set_param(SALT_CONFIG_KEY, STANDARD.encode(&salt).into())?;
+    Ok(salt)
+}
+
+fn load_key(mode: EncryptionMode, create: bool) -> Result<SessionKey> {
+    if let Some(key) = SESSION_KEYS.lock().unwrap().get(&mode) {
+        return Ok(key.clone());
+    }
+
+    let config = Config::global();
+    let key = match mode {
+        EncryptionMode::Off => return Err(anyhow::anyhow!("Session encryption is off")),
+        EncryptionMode::Keyring => match config.get_secret::<String>(KEY_SECRET) {
+            Ok(encoded) => {
+                let bytes: [u8; KEY_LEN] = STANDARD
+                    .decode(encoded)?
+                    .try_into()
+                    .map_err(|_| anyhow::anyhow!("Session key has the wrong length"))?;
+                SessionKey(bytes)
+            }
+            Err(_) if create => {
+                let key = SessionKey::generate();
+                config.set_secret(KEY_SECRET, STANDARD.encode(key.0).into())?;
+                key
+            }
+            Err(_) => return Err(anyhow::anyhow!("No session key found in the keyring")),
+        },
+        EncryptionMode::Passphrase => {
+            let passphrase = config
+                .get_secret::<String>(PASSPHRASE_SECRET)
+                .map_err(|_| anyhow::anyhow!("{} is not set", PASSPHRASE_SECRET))?;
+            SessionKey::from_passphrase(&passphrase, &passphrase_salt(create)?)
+        }
+    };
+
+    SESSION_KEYS.lock().unwrap().insert(mode, key.clone());
+    Ok(key)
+}
+
+/// Every key available for reading, whatever the configured mode. Sessions written before
+/// encryption was switched off or to another mode (or by an interrupted migration) stay
+/// readable as long as their key can still be found.
+fn read_keys() -> Vec<SessionKey> {
+    [EncryptionMode::Keyring, EncryptionMode::Passphrase]
+        .into_iter()
+        .filter_map(|mode| load_key(mode, false).ok())
+        .collect()
+}
+
+pub fn is_encrypted_line(line: &str) -> bool {
+    line.starts_with(ENCRYPTED_LINE_PREFIX)
+}
+
+/// The session a file belongs to: its name up to the first dot, which is shared by the
+/// session file, its journal and its backups
+pub fn session_id_for(path: &Path) -> String {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    match name.split_once('.') {
+        Some((id, _)) => id.to_string(),
+        None => name,
+    }
+}
+
+/// Where a line sits, bound into its ciphertext
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinePosition {
+    /// Line of the session file, the metadata line being 0
+    Line(usize),
+    /// The single line of the session's journal
+    Journal,
+}
+
+fn line_aad(session_id: &str, position: LinePosition) -> Vec<u8> {
+    match position {
+        LinePosition::Line(index) => format!("{}:{}", session_id, index),
+        LinePosition::Journal => format!("{}:journal", session_id),
+    }
+    .into_bytes()
+}
+
+/// Encrypt a single line (without its trailing newline) of session `session_id`
+pub fn encrypt_line(
+    plaintext: &str,
+    key: &SessionKey,
+    session_id: &str,
+    position: LinePosition,
+) -> Result<String> {
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    rand::rngs::OsRng.fill_bytes(&mut nonce_bytes);
+
+    let mut sealed = plaintext.as_bytes().to_vec();
+    key.aead_key()
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce_bytes),
+            Aad::from(line_aad(session_id, position)),
+            &mut sealed,
+        )
+        .map_err(|_| anyhow::anyhow!("Failed to encrypt session line"))?;
+
+    let mut payload = nonce_bytes.to_vec();
+    payload.extend(sealed);
+    Ok(format!(
+        "{}{}",
+        ENCRYPTED_LINE_PREFIX,
+        STANDARD.encode(payload)
+    ))
+}
+
+/// Decrypt a line produced by `encrypt_line` for the same session and position
+pub fn decrypt_line(
+    line: &str,
+    key: &SessionKey,
+    session_id: &str,
+    position: LinePosition,
+) -> Result<String> {
+    let line = line.trim_end();
+    let encoded = line
+        .strip_prefix(ENCRYPTED_LINE_PREFIX)
+        .ok_or_else(|| anyhow::anyhow!("Line is not encrypted"))?;
+    let payload = STANDARD.decode(encoded)?;
+    if payload.len() < NONCE_LEN {
+        return Err(anyhow::anyhow!("Encrypted session line is too short"));
+    }
+
+    let (nonce_bytes, sealed) = payload.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce_bytes)
+        .map_err(|_| anyhow::anyhow!("Invalid nonce in session line"))?;
+    let mut sealed = sealed.to_vec();
+    let plaintext = key
+        .aead_key()
+        .open_in_place(
+            nonce,
+            Aad::from(line_aad(session_id, position)),
+            &mut sealed,
+        )
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "Failed to decrypt session line; the key may be wrong or the line was moved"
+            )
+        })?;
+    Ok(String::from_utf8(plaintext.to_vec())?)
+}
+
+/// Prepares serialized lines of one session for writing, encrypting them if session
+/// encryption is on. Resolve one per write so the config and keyring are only consulted once.
+pub struct LineEncoder {
+    key: Option<SessionKey>,
+    session_id: String,
+}
+
+impl LineEncoder {
+    pub fn from_config(session_file: &Path) -> Result<Self> {
+        Self::for_mode(encryption_mode(), session_file)
+    }
+
+    /// Encoder for `mode`, creating its key if there is none yet
+    pub fn for_mode(mode: EncryptionMode, session_file: &Path) -> Result<Self> {
+        let key = match mode {
+            EncryptionMode::Off => None,
+            mode => Some(load_key(mode, true)?),
+        };
+        Ok(Self {
+            key,
+            session_id: session_id_for(session_file),
+        })
+    }
+
+    pub fn encode(&self, position: LinePosition, plaintext: String) -> Result<String> {
+        match &self.key {
+            Some(key) => encrypt_line(&plaintext, key, &self.session_id, position),
+            None => Ok(plaintext),
+        }
+    }
+}
+
+/// Turns lines read from a session file back into JSON, decrypting them if needed. The key is
+/// only loaded once an encrypted line shows up, so plaintext sessions never touch the keyring.
+pub struct LineDecoder {
+    keys: OnceCell<Vec<SessionKey>>,
+    session_id: String,
+}
+
+impl LineDecoder {
+    pub fn new(session_file: &Path) -> Self {
+        Self {
+            keys: OnceCell::new(),
+            session_id: session_id_for(session_file),
+        }
+    }
+
+    pub fn decode(&self, position: LinePosition, line: String) -> io::Result<String> {
+        if !is_encrypted_line(&line) {
+            return Ok(line);
+        }
+        let keys = self.keys.get_or_init(read_keys);
+        keys.iter()
+            .find_map(|key| decrypt_line(&line, key, &self.session_id, position).ok())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "no available key decrypts this session line; if it was encrypted with a passphrase, set {}",
+                        PASSPHRASE_SECRET
+                    ),
+                )
+            })
+    }
+}
+
+/// Fail early if the session file is encrypted with a key we don't have, instead of treating
+/// every line as corrupted
+pub fn ensure_readable(session_file: &Path) -> Result<()> {
+    if !session_file.exists() {
+        return Ok(());
+    }
+    let mut first_line = String::new();
+    BufReader::new(File::open(session_file)?).read_line(&mut first_line)?;
+    LineDecoder::new(session_file)
+        .decode(LinePosition::Line(0), first_line)
+        .map_err(|e| {
+            tracing::error!("Failed to decrypt session file {:?}: {}", session_file, e);
+            anyhow::anyhow!(
+                "Session file is encrypted and could not be decrypted: {}",
+                e
+            )
+        })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIRST: LinePosition = LinePosition::Line(1);
+
+    #[test]
+    fn test_encrypted_line_round_trip() -> Result<()> {
+        let key = SessionKey::generate();
+        let line = r#"{"role":"user","content":[{"type":"text","text":"my api key is hunter2"}]}"#;
+
+        let encrypted = encrypt_line(line, &key, "20250101_1", FIRST)?;
+        assert!(is_encrypted_line(&encrypted));
+        assert!(!encrypted.contains("hunter2"));
+        assert!(!encrypted.contains('\n'));
+        assert_eq!(
+            decrypt_line(&format!("{}\n", encrypted), &key, "20250101_1", FIRST)?,
+            line
+        );
+
+        // Same plaintext encrypts differently each time
+        assert_ne!(encrypt_line(line, &key, "20250101_1", FIRST)?, encrypted);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wrong_key_is_rejected() -> Result<()> {
+        let salt = b"0123456789abcdef";
+        let key = SessionKey::from_passphrase("correct horse", salt);
+        let encrypted = encrypt_line("{}", &key, "session", FIRST)?;
+
+        let same = SessionKey::from_passphrase("correct horse", salt);
+        assert_eq!(decrypt_line(&encrypted, &same, "session", FIRST)?, "{}");
+
+        let wrong = SessionKey::from_passphrase("battery staple", salt);
+        assert!(decrypt_line(&encrypted, &wrong, "session", FIRST).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_moved_lines_are_rejected() -> Result<()> {
+        let key = SessionKey::generate();
+        let encrypted = encrypt_line("{}", &key, "session", FIRST)?;
+
+        assert!(decrypt_line(&encrypted, &key, "session", LinePosition::Line(2)).is_err());
+        assert!(decrypt_line(&encrypted, &key, "session", LinePosition::Journal).is_err());
+        assert!(decrypt_line(&encrypted, &key, "other", FIRST).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_id_is_shared_by_sidecars() {
+        let id = session_id_for(Path::new("/sessions/20250101_1.jsonl"));
+        assert_eq!(id, "20250101_1");
+        for sidecar in [
+            "20250101_1.journal",
+            "20250101_1.backup",
+            "20250101_1.repair.bak",
+        ] {
+            assert_eq!(session_id_for(&Path::new("/sessions").join(sidecar)), id);
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use crate::conversation::message::{Message, MessageContent};
+use crate::conversation::Conversation;
+use crate::session::stats::MessageStats;
+use rmcp::model::Role;
+use serde::Serialize;
+use serde_json::Value;
+
+/// A tool call made during a turn
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolCallSummary {
+    pub name: String,
+    pub arguments: Value,
+}
+
+/// Everything that happened between one user prompt and the next
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TurnSummary {
+    pub prompt: String,
+    pub tool_calls: Vec<ToolCallSummary>,
+    pub tool_outputs: Vec<String>,
+    pub final_answer: Option<String>,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+}
+
+/// Two turns at the same position, along with which parts of them differ
+#[derive(Debug, Clone, Serialize)]
+pub struct TurnDiff {
+    pub index: usize,
+    pub a: Option<TurnSummary>,
+    pub b: Option<TurnSummary>,
+    pub prompt_differs: bool,
+    pub tool_calls_differ: bool,
+    pub outputs_differ: bool,
+    pub answer_differs: bool,
+    pub tokens_differ: bool,
+}
+
+impl TurnDiff {
+    /// Token counts vary between otherwise identical runs, so they don't make a turn differ
+    pub fn has_differences(&self) -> bool {
+        self.prompt_differs || self.tool_calls_differ || self.outputs_differ || self.answer_differs
+    }
+}
+
+/// Totals for one side of a diff
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DiffTotals {
+    pub turns: usize,
+    pub tool_calls: usize,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+}
+
+/// Turn-by-turn comparison of two sessions
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionDiff {
+    pub a: String,
+    pub b: String,
+    pub a_totals: DiffTotals,
+    pub b_totals: DiffTotals,
+    pub turns: Vec<TurnDiff>,
+}
+
+fn is_user_prompt(message: &Message) -> bool {
+    message.role == Role::User
+        && message
+            .content
+            .iter()
+            .any(|c| matches!(c, MessageContent::Text(_)))
+        && !message.is_tool_response()
+}
+
+/// Split a conversation into turns, each starting at a user prompt. Usage records are
+/// attributed to the turn during which they were created.
+pub fn summarize_turns(conversation: &Conversation, stats: &[MessageStats]) -> Vec<TurnSummary> {
+    let mut turns: Vec<TurnSummary> = Vec::new();
+    let mut turn_starts: Vec<i64> = Vec::new();
+
+    for message in conversation.iter() {
+        if is_user_prompt(message) || turns.is_empty() {
+            turns.push(TurnSummary {
+                prompt: message.as_concat_text(),
+                ..Default::default()
+            });
+            turn_starts.push(message.created);
+            if is_user_prompt(message) {
+                continue;
+            }
+        }
+
+        let turn = turns.last_mut().expect("a turn was just pushed");
+        for content in &message.content {
+            match content {
+                MessageContent::ToolRequest(request) => {
+                    if let Ok(call) = &request.tool_call {
+                        turn.tool_calls.push(ToolCallSummary {
+                            name: call.name.clone(),
+                            arguments: call.arguments.clone(),
+                        });
+                    }
+                }
+                MessageContent::ToolResponse(_) => {
+                    if let Some(text) = content.as_tool_response_text() {
+                        turn.tool_outputs.push(text);
+                    }
+                }
+                MessageContent::Text(text) if message.role == Role::Assistant => {
+                    if !text.text.trim().is_empty() {
+                        turn.final_answer = Some(text.text.clone());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    for record in stats {
+        let position = turn_starts
+            .iter()
+            .rposition(|start| *start <= record.created)
+            .unwrap_or(0);
+        if let Some(turn) = turns.get_mut(position) {
+            turn.input_tokens += record.input_tokens.unwrap_or(0) as i64;
+            turn.output_tokens += record.output_tokens.unwrap_or(0) as i64;
+        }
+    }
+
+    turns
+}
+
+fn totals(turns: &[TurnSummary]) -> DiffTotals {
+    DiffTotals {
+        turns: turns.len(),
+        tool_calls: turns.iter().map(|t| t.tool_calls.len()).sum(),
+        input_tokens: turns.iter().map(|t| t.input_tokens).sum(),
+        output_tokens: turns.iter().map(|t| t.output_tokens).sum(),
+    }
+}
+
+/// Compare two sessions turn by turn
+pub fn diff_sessions(
+    a_name: &str,
+    a: &Conversation,
+    a_stats: &[MessageStats],
+    b_name: &str,
+    b: &Conversation,
+    b_stats: &[MessageStats],
+) -> SessionDiff {
+    let a_turns = summarize_turns(a, a_stats);
+    let b_turns = summarize_turns(b, b_stats);
+
+    let turns = (0..a_turns.len().max(b_turns.len()))
+        .map(|index| {
+            let a = a_turns.get(index).cloned();
+            let b = b_turns.get(index).cloned();
+            match (&a, &b) {
+                (Some(ta), Some(tb)) => TurnDiff {
+                    index,
+                    prompt_differs: ta.prompt != tb.prompt,
+                    tool_calls_differ: ta.tool_calls != tb.tool_calls,
+                    outputs_differ: ta.tool_outputs != tb.tool_outputs,
+                    answer_differs: ta.final_answer != tb.final_answer,
+                    tokens_differ: ta.input_tokens != tb.input_tokens
+                        || ta.output_tokens != tb.output_tokens,
+                    a,
+                    b,
+                },
+                _ => TurnDiff {
+                    index,
+                    prompt_differs: true,
+                    tool_calls_differ: true,
+                    outputs_differ: true,
+                    answer_differs: true,
+                    tokens_differ: true,
+                    a,
+                    b,
+                },
+            }
+        })
+        .collect();
+
+    SessionDiff {
+        a: a_name.to_string(),
+        b: b_name.to_string(),
+        a_totals: totals(&a_turns),
+        b_totals: totals(&b_turns),
+        turns,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_core::ToolCall;
+    use rmcp::model::Content;
+    use serde_json::json;
+
+    fn run(command: &str, answer: &str) -> Conversation {
+        Conversation::new_unvalidated(vec![
+            Message::user().with_text("list the files"),
+            Message::assistant().with_tool_request(
+                "call_1",
+                Ok(ToolCall::new(
+                    "developer__shell",
+                    json!({"command": command}),
+                )),
+            ),
+            Message::user().with_tool_response("call_1", Ok(vec![Content::text("a.txt")])),
+            Message::assistant().with_text(answer),
+        ])
+    }
+
+    #[test]
+    fn test_summarize_turns_groups_tool_calls_under_prompt() {
+        let turns = summarize_turns(&run("ls", "There is one file"), &[]);
+
+        assert_eq!(turns.len(), 1);
+        assert_eq!(turns[0].prompt, "list the files");
+        assert_eq!(turns[0].tool_calls[0].name, "developer__shell");
+        assert_eq!(turns[0].tool_outputs, vec!["a.txt".to_string()]);
+        assert_eq!(turns[0].final_answer.as_deref(), Some("There is one file"));
+    }
+
+    #[test]
+    fn test_diff_sessions_flags_changed_arguments() {
+        let diff = diff_sessions(
+            "a",
+            &run("ls", "There is one file"),
+            &[],
+            "b",
+            &run("ls -la", "There is one file"),
+            &[],
+        );
+
+        assert_eq!(diff.turns.len(), 1);
+        let turn = &diff.turns[0];
+        assert!(!turn.prompt_differs);
+        assert!(turn.tool_calls_differ);
+        assert!(!turn.outputs_differ);
+        assert!(!turn.answer_differs);
+        assert!(!turn.tokens_differ);
+    }
+}
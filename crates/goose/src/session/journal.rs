@@ -0,0 +1,402 @@
+// Crash-safe incremental writes for session files.
+//
+// Appending new messages happens in two steps: the messages are first written to a journal
+// next to the session file, then copied onto the end of the session file. If the writer dies
+// between the two steps, the journal is replayed the next time the session is opened, so a
+// partially written line never ends up in the history. Writes to a session file happen while
+// holding an exclusive lock on a sidecar `.lock` file, and reads while holding a shared one,
+// which keeps two goose processes resuming the same session (e.g. the CLI and a scheduled
+// job) from clobbering each other.
+//
+// The metadata line is written with some trailing padding so that the usual updates (a new
+// message count, token totals) can overwrite it in place instead of rewriting the file.
+
+use crate::conversation::message::Message;
+use crate::session::crypto::{LineDecoder, LineEncoder, LinePosition};
+use anyhow::Result;
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Spaces written after the metadata line, leaving room for it to grow in place
+const METADATA_PADDING: usize = 64;
+
+/// Lock on a session file, released when dropped
+pub struct SessionLock {
+    file: File,
+}
+
+impl SessionLock {
+    /// Block until no other process is reading or writing the session file
+    pub fn acquire(session_file: &Path) -> Result<Self> {
+        Self::lock(session_file, true)
+    }
+
+    /// Block until no other process is writing the session file. Any number of shared locks
+    /// can be held at once.
+    pub fn acquire_shared(session_file: &Path) -> Result<Self> {
+        Self::lock(session_file, false)
+    }
+
+    fn lock(session_file: &Path, exclusive: bool) -> Result<Self> {
+        if let Some(parent) = session_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let path = lock_path(session_file);
+        loop {
+            let file = OpenOptions::new()
+                .create(true)
+                .write(true)
+                .truncate(false)
+                .open(&path)
+                .map_err(|e| {
+                    tracing::error!("Failed to open session lock file: {}", e);
+                    anyhow::anyhow!("Failed to open session lock file")
+                })?;
+            let locked = if exclusive {
+                file.lock_exclusive()
+            } else {
+                file.lock_shared()
+            };
+            locked.map_err(|e| {
+                tracing::error!("Failed to lock session file: {}", e);
+                anyhow::anyhow!("Failed to lock session file")
+            })?;
+            // Deleting a session removes its lock file while holding the lock. Whoever was
+            // waiting on the removed file must lock the one now at the path instead.
+            if is_same_file(&file, &path) {
+                return Ok(Self { file });
+            }
+        }
+    }
+}
+
+impl Drop for SessionLock {
+    fn drop(&mut self) {
+        let _ = fs2::FileExt::unlock(&self.file);
+    }
+}
+
+#[cfg(unix)]
+fn is_same_file(file: &File, path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (file.metadata(), fs::metadata(path)) {
+        (Ok(opened), Ok(current)) => opened.dev() == current.dev() && opened.ino() == current.ino(),
+        _ => false,
+    }
+}
+
+// Windows doesn't remove a file that is still open, so the lock file can't change under us
+#[cfg(not(unix))]
+fn is_same_file(_file: &File, path: &Path) -> bool {
+    path.exists()
+}
+
+#[derive(Serialize, Deserialize)]
+struct JournalEntry {
+    /// Number of complete lines (metadata included) the session file had before the append
+    base_lines: usize,
+    /// Serialized metadata replacing the first line of the session file
+    #[serde(default)]
+    metadata: Option<String>,
+    messages: Vec<Message>,
+}
+
+pub fn lock_path(session_file: &Path) -> PathBuf {
+    session_file.with_extension("lock")
+}
+
+pub fn journal_path(session_file: &Path) -> PathBuf {
+    session_file.with_extension("journal")
+}
+
+/// Append messages to the end of a session file through the journal, replacing its metadata
+/// line with `metadata` when given.
+///
+/// The caller must hold the session lock exclusively.
+pub(crate) fn append_with_journal(
+    session_file: &Path,
+    metadata: Option<String>,
+    messages: &[Message],
+) -> Result<()> {
+    replay_journal(session_file)?;
+
+    let encoder = LineEncoder::from_config(session_file)?;
+    let entry = JournalEntry {
+        base_lines: count_complete_lines(session_file)?,
+        metadata,
+        messages: messages.to_vec(),
+    };
+
+    let journal = journal_path(session_file);
+    {
+        let mut file = File::create(&journal)?;
+        write!(
+            file,
+            "{}",
+            encoder.encode(LinePosition::Journal, serde_json::to_string(&entry)?)?
+        )?;
+        file.sync_all()?;
+    }
+
+    apply_entry(session_file, &entry, &encoder)?;
+    fs::remove_file(&journal)?;
+    Ok(())
+}
+
+/// Finish an append that was interrupted, if any.
+///
+/// A journal that can't be parsed was never completely written, which means the session file
+/// was not touched yet and the journal can be discarded. The caller must hold the session lock
+/// exclusively.
+pub(crate) fn replay_journal(session_file: &Path) -> Result<()> {
+    let journal = journal_path(session_file);
+    if !journal.exists() {
+        return Ok(());
+    }
+
+    match fs::read_to_string(&journal)
+        .ok()
+        .and_then(|s| {
+            LineDecoder::new(session_file)
+                .decode(LinePosition::Journal, s)
+                .ok()
+        })
+        .and_then(|s| serde_json::from_str::<JournalEntry>(&s).ok())
+    {
+        Some(entry) => {
+            tracing::warn!(
+                "[SESSION] Replaying interrupted write of {} messages to {:?}",
+                entry.messages.len(),
+                session_file
+            );
+            apply_entry(
+                session_file,
+                &entry,
+                &LineEncoder::from_config(session_file)?,
+            )?;
+        }
+        None => {
+            tracing::warn!("[SESSION] Discarding incomplete journal {:?}", journal);
+        }
+    }
+
+    fs::remove_file(&journal)?;
+    Ok(())
+}
+
+/// Discard a pending journal because the session file is about to be fully rewritten.
+pub(crate) fn discard_journal(session_file: &Path) {
+    let _ = fs::remove_file(journal_path(session_file));
+}
+
+fn count_complete_lines(session_file: &Path) -> Result<usize> {
+    if !session_file.exists() {
+        return Ok(0);
+    }
+    let mut reader = BufReader::new(File::open(session_file)?);
+    let mut count = 0;
+    let mut line = Vec::new();
+    while reader.read_until(b'\n', &mut line)? > 0 {
+        if line.ends_with(b"\n") {
+            count += 1;
+        }
+        line.clear();
+    }
+    Ok(count)
+}
+
+/// Cut the session file back to `base_lines` complete lines and write the journaled messages
+/// after them. Running this twice produces the same file, which makes replay safe.
+fn apply_entry(session_file: &Path, entry: &JournalEntry, encoder: &LineEncoder) -> Result<()> {
+    let mut offset = 0u64;
+    let mut first_line_len = 0u64;
+    let mut lines = 0;
+    if session_file.exists() {
+        let mut reader = BufReader::new(File::open(session_file)?);
+        let mut line = Vec::new();
+        while lines < entry.base_lines {
+            let read = reader.read_until(b'\n', &mut line)?;
+            if read == 0 || !line.ends_with(b"\n") {
+                break;
+            }
+            if lines == 0 {
+                first_line_len = read as u64;
+            }
+            offset += read as u64;
+            lines += 1;
+            line.clear();
+        }
+    }
+
+    let Some(metadata) = &entry.metadata else {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(false)
+            .open(session_file)?;
+        file.set_len(offset)?;
+        file.seek(SeekFrom::End(0))?;
+        write_messages(&file, &entry.messages, lines, encoder)?;
+        return Ok(());
+    };
+
+    // Overwrite the old metadata line in place when the new one fits in it, padding it out
+    // to the same length so no other byte of the file moves
+    let metadata_line = encoder.encode(LinePosition::Line(0), metadata.clone())?;
+    if lines > 0 && metadata_line.len() < first_line_len as usize {
+        let mut file = OpenOptions::new().write(true).open(session_file)?;
+        file.set_len(offset)?;
+        file.seek(SeekFrom::Start(0))?;
+        write!(
+            file,
+            "{:width$}",
+            metadata_line,
+            width = first_line_len as usize - 1
+        )?;
+        file.seek(SeekFrom::End(0))?;
+        write_messages(&file, &entry.messages, lines, encoder)?;
+        return Ok(());
+    }
+
+    // Otherwise the file is copied behind the new metadata line and moved into place
+    let temp_file = session_file.with_extension("tmp");
+    {
+        let file = File::create(&temp_file)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&temp_file, fs::Permissions::from_mode(0o600))?;
+        }
+        let mut writer = BufWriter::new(&file);
+        writeln!(writer, "{}", pad_metadata_line(metadata_line))?;
+        if offset > first_line_len {
+            let mut existing = File::open(session_file)?;
+            existing.seek(SeekFrom::Start(first_line_len))?;
+            std::io::copy(&mut existing.take(offset - first_line_len), &mut writer)?;
+        }
+        writer.flush()?;
+        drop(writer);
+        write_messages(&file, &entry.messages, lines.max(1), encoder)?;
+    }
+    fs::rename(&temp_file, session_file)?;
+    Ok(())
+}
+
+/// Pad an encoded metadata line so later, slightly longer versions of it can be written over
+/// it in place. Both JSON and encrypted lines ignore trailing whitespace.
+pub(crate) fn pad_metadata_line(line: String) -> String {
+    let width = line.len() + METADATA_PADDING;
+    format!("{:width$}", line, width = width)
+}
+
+/// Write `messages` as the lines of the session file starting at line `first_line`
+fn write_messages(
+    file: &File,
+    messages: &[Message],
+    first_line: usize,
+    encoder: &LineEncoder,
+) -> Result<()> {
+    {
+        let mut writer = BufWriter::new(file);
+        for (i, message) in messages.iter().enumerate() {
+            writeln!(
+                writer,
+                "{}",
+                encoder.encode(
+                    LinePosition::Line(first_line + i),
+                    serde_json::to_string(message)?
+                )?
+            )?;
+        }
+        writer.flush()?;
+    }
+    file.sync_all()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_replay_drops_partial_line_and_applies_journal() -> Result<()> {
+        let dir = tempdir()?;
+        let session_file = dir.path().join("session.jsonl");
+        let first = Message::user().with_text("first");
+        let second = Message::assistant().with_text("second");
+
+        fs::write(
+            &session_file,
+            format!(
+                "{{\"description\":\"\",\"message_count\":1}}\n{}\n{{\"role\":\"assis",
+                serde_json::to_string(&first)?
+            ),
+        )?;
+        let entry = JournalEntry {
+            base_lines: 2,
+            metadata: None,
+            messages: vec![second.clone()],
+        };
+        fs::write(journal_path(&session_file), serde_json::to_string(&entry)?)?;
+
+        replay_journal(&session_file)?;
+        replay_journal(&session_file)?;
+
+        let contents = fs::read_to_string(&session_file)?;
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[2], serde_json::to_string(&second)?);
+        assert!(!journal_path(&session_file).exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_incomplete_journal_is_discarded() -> Result<()> {
+        let dir = tempdir()?;
+        let session_file = dir.path().join("session.jsonl");
+        let original = "{\"description\":\"\",\"message_count\":0}\n";
+        fs::write(&session_file, original)?;
+        fs::write(journal_path(&session_file), "{\"base_lines\":1,\"messa")?;
+
+        replay_journal(&session_file)?;
+
+        assert_eq!(fs::read_to_string(&session_file)?, original);
+        assert!(!journal_path(&session_file).exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_metadata_fitting_the_padding_is_written_in_place() -> Result<()> {
+        let dir = tempdir()?;
+        let session_file = dir.path().join("session.jsonl");
+        let first = Message::user().with_text("first");
+        let second = Message::assistant().with_text("second");
+        let metadata = "{\"description\":\"\",\"message_count\":1}".to_string();
+        fs::write(
+            &session_file,
+            format!(
+                "{}\n{}\n",
+                pad_metadata_line(metadata.clone()),
+                serde_json::to_string(&first)?
+            ),
+        )?;
+        let first_line_len = metadata.len() + METADATA_PADDING;
+
+        let updated = "{\"description\":\"greeting\",\"message_count\":2}".to_string();
+        append_with_journal(&session_file, Some(updated.clone()), &[second.clone()])?;
+
+        let contents = fs::read_to_string(&session_file)?;
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].len(), first_line_len);
+        assert_eq!(lines[0].trim_end(), updated);
+        assert_eq!(lines[1], serde_json::to_string(&first)?);
+        assert_eq!(lines[2], serde_json::to_string(&second)?);
+        assert!(!session_file.with_extension("tmp").exists());
+        Ok(())
+    }
+}
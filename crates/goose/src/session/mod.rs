@@ -1,12 +1,21 @@
+pub mod artifacts;
+pub mod crypto;
+pub mod diff;
 pub mod info;
+pub mod journal;
+pub mod stats;
 pub mod storage;
 
 // Re-export common session types and functions
 pub use storage::{
-    ensure_session_dir, generate_description, generate_description_with_schedule_id,
-    generate_session_id, get_most_recent_session, get_path, list_sessions, persist_messages,
-    persist_messages_with_schedule_id, read_messages, read_metadata, update_metadata, Identifier,
-    SessionMetadata,
+    delete_session, diagnose_session, ensure_session_dir, generate_description,
+    generate_description_with_schedule_id, generate_session_id, get_most_recent_session, get_path,
+    list_sessions, migrate_session_encryption, persist_messages, persist_messages_with_schedule_id,
+    read_messages, read_metadata, repair_session, repair_session_with_policy,
+    save_messages_with_metadata, set_message_pinned, update_metadata, update_metadata_with,
+    Identifier, SessionMetadata,
 };
 
+pub use artifacts::SessionArtifact;
 pub use info::{get_valid_sorted_sessions, SessionInfo};
+pub use stats::{MessageStats, SessionStats, ToolCallStats};
@@ -0,0 +1,220 @@
+use crate::providers::base::ProviderUsage;
+use crate::session::journal::SessionLock;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Usage and timing for a single provider call, stored one per line in a stats file next to
+/// the session. Keeping them out of the metadata line means a long session's stats neither
+/// grow that line past its size limit nor get rewritten on every turn.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageStats {
+    /// ID of the assistant message produced by this call, if the provider assigned one
+    pub message_id: Option<String>,
+    /// Unix timestamp (seconds) at which the request was sent
+    pub created: i64,
+    /// Model that served the request
+    pub model: String,
+    pub input_tokens: Option<i32>,
+    pub output_tokens: Option<i32>,
+    pub total_tokens: Option<i32>,
+    /// Time spent waiting on the provider, in milliseconds
+    pub latency_ms: u64,
+    /// Execution time of each tool call requested in this turn
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCallStats>,
+}
+
+/// Execution time of a single tool call, keyed by the id of its ToolResponse
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCallStats {
+    pub id: String,
+    pub name: String,
+    pub duration_ms: u64,
+}
+
+impl MessageStats {
+    pub fn new(
+        message_id: Option<String>,
+        created: i64,
+        usage: &ProviderUsage,
+        latency_ms: u64,
+        tool_calls: Vec<ToolCallStats>,
+    ) -> Self {
+        Self {
+            message_id,
+            created,
+            model: usage.model.clone(),
+            input_tokens: usage.usage.input_tokens,
+            output_tokens: usage.usage.output_tokens,
+            total_tokens: usage.usage.total_tokens,
+            latency_ms,
+            tool_calls,
+        }
+    }
+}
+
+/// File holding the stats of a session
+pub fn stats_path(session_file: &Path) -> PathBuf {
+    session_file.with_extension("stats")
+}
+
+/// Append the stats of one provider call to the session's stats file
+pub fn append_message_stats(session_file: &Path, stats: &MessageStats) -> Result<()> {
+    let line = format!("{}\n", serde_json::to_string(stats)?);
+    let _lock = SessionLock::acquire(session_file)?;
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(stats_path(session_file))?
+        .write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// Read the stats recorded for a session, skipping lines that can't be parsed (e.g. one cut
+/// short by a crash)
+pub fn read_message_stats(session_file: &Path) -> Result<Vec<MessageStats>> {
+    let contents = match fs::read_to_string(stats_path(session_file)) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Aggregated totals for one model or tool
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StatsBucket {
+    pub calls: usize,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub duration_ms: u64,
+}
+
+/// Breakdown of where time and tokens went in a session
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SessionStats {
+    pub provider_calls: usize,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub provider_ms: u64,
+    pub tool_ms: u64,
+    pub by_model: BTreeMap<String, StatsBucket>,
+    pub by_tool: BTreeMap<String, StatsBucket>,
+    pub turns: Vec<MessageStats>,
+}
+
+impl SessionStats {
+    pub fn from_records(records: &[MessageStats]) -> Self {
+        let mut stats = SessionStats {
+            turns: records.to_vec(),
+            ..Default::default()
+        };
+
+        for record in records {
+            let input = record.input_tokens.unwrap_or(0) as i64;
+            let output = record.output_tokens.unwrap_or(0) as i64;
+
+            stats.provider_calls += 1;
+            stats.input_tokens += input;
+            stats.output_tokens += output;
+            stats.provider_ms += record.latency_ms;
+
+            let model = stats.by_model.entry(record.model.clone()).or_default();
+            model.calls += 1;
+            model.input_tokens += input;
+            model.output_tokens += output;
+            model.duration_ms += record.latency_ms;
+
+            for tool_call in &record.tool_calls {
+                stats.tool_ms += tool_call.duration_ms;
+                let tool = stats.by_tool.entry(tool_call.name.clone()).or_default();
+                tool.calls += 1;
+                tool.duration_ms += tool_call.duration_ms;
+            }
+        }
+
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::base::Usage;
+
+    #[test]
+    fn test_from_records_aggregates_models_and_tools() {
+        let usage = ProviderUsage::new(
+            "model-a".to_string(),
+            Usage::new(Some(100), Some(20), Some(120)),
+        );
+        let other = ProviderUsage::new("model-b".to_string(), Usage::new(Some(50), None, Some(50)));
+        let records = vec![
+            MessageStats::new(
+                Some("msg_1".to_string()),
+                0,
+                &usage,
+                1500,
+                vec![
+                    ToolCallStats {
+                        id: "call_1".to_string(),
+                        name: "developer__shell".to_string(),
+                        duration_ms: 300,
+                    },
+                    ToolCallStats {
+                        id: "call_2".to_string(),
+                        name: "developer__shell".to_string(),
+                        duration_ms: 200,
+                    },
+                ],
+            ),
+            MessageStats::new(None, 1, &other, 500, vec![]),
+        ];
+
+        let stats = SessionStats::from_records(&records);
+
+        assert_eq!(stats.provider_calls, 2);
+        assert_eq!(stats.input_tokens, 150);
+        assert_eq!(stats.output_tokens, 20);
+        assert_eq!(stats.provider_ms, 2000);
+        assert_eq!(stats.tool_ms, 500);
+        assert_eq!(stats.by_model["model-a"].calls, 1);
+        assert_eq!(stats.by_model["model-b"].input_tokens, 50);
+        assert_eq!(stats.by_tool["developer__shell"].calls, 2);
+        assert_eq!(stats.by_tool["developer__shell"].duration_ms, 500);
+    }
+
+    #[test]
+    fn test_stats_file_round_trip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let session_file = dir.path().join("session.jsonl");
+        let usage = ProviderUsage::new("model-a".to_string(), Usage::new(Some(10), Some(5), None));
+
+        assert!(read_message_stats(&session_file)?.is_empty());
+        append_message_stats(
+            &session_file,
+            &MessageStats::new(None, 0, &usage, 100, vec![]),
+        )?;
+        append_message_stats(
+            &session_file,
+            &MessageStats::new(None, 1, &usage, 200, vec![]),
+        )?;
+
+        let records = read_message_stats(&session_file)?;
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].latency_ms, 200);
+        Ok(())
+    }
+}
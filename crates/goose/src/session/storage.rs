@@ -5,9 +5,13 @@
 // - Backup creation
 // Additional debug logging can be added if needed for troubleshooting.
 
-use crate::conversation::message::Message;
-use crate::conversation::Conversation;
+use crate::conversation::message::{Message, MessageContent};
+use crate::conversation::{repair_conversation, Conversation, ConversationIssue, RepairPolicy};
 use crate::providers::base::Provider;
+use crate::session::artifacts::{self, SessionArtifact};
+use crate::session::crypto;
+use crate::session::journal::{self, SessionLock};
+use crate::session::stats;
 use crate::utils::safe_truncate;
 use anyhow::Result;
 use chrono::Local;
@@ -65,6 +69,9 @@ pub struct SessionMetadata {
     pub accumulated_input_tokens: Option<i32>,
     /// The number of output tokens used in the session. Accumulated across all messages.
     pub accumulated_output_tokens: Option<i32>,
+    /// Output files collected from the working directory after a recipe run
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<SessionArtifact>,
 }
 
 // Custom deserializer to handle old sessions without working_dir
@@ -86,6 +93,8 @@ impl<'de> Deserialize<'de> for SessionMetadata {
             accumulated_input_tokens: Option<i32>,
             accumulated_output_tokens: Option<i32>,
             working_dir: Option<PathBuf>,
+            #[serde(default)]
+            artifacts: Vec<SessionArtifact>,
         }
 
         let helper = Helper::deserialize(deserializer)?;
@@ -108,6 +117,7 @@ impl<'de> Deserialize<'de> for SessionMetadata {
             accumulated_input_tokens: helper.accumulated_input_tokens,
             accumulated_output_tokens: helper.accumulated_output_tokens,
             working_dir,
+            artifacts: helper.artifacts,
         })
     }
 }
@@ -133,6 +143,7 @@ impl SessionMetadata {
             accumulated_total_tokens: None,
             accumulated_input_tokens: None,
             accumulated_output_tokens: None,
+            artifacts: Vec::new(),
         }
     }
 }
@@ -365,6 +376,21 @@ pub fn get_most_recent_session() -> Result<PathBuf> {
     Ok(entries[0].path())
 }
 
+/// Delete a session file together with its artifacts and the lock, journal, stats and backup
+/// files kept next to it
+pub fn delete_session(session_file: &Path) -> Result<()> {
+    let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
+    let _lock = SessionLock::acquire(&secure_path)?;
+    fs::remove_file(&secure_path)?;
+    journal::discard_journal(&secure_path);
+    let _ = fs::remove_file(secure_path.with_extension("backup"));
+    let _ = fs::remove_file(stats::stats_path(&secure_path));
+    let _ = fs::remove_dir_all(artifacts::artifacts_dir(&secure_path));
+    // Removed while still locked: anyone waiting on it notices and locks a fresh lock file
+    let _ = fs::remove_file(journal::lock_path(&secure_path));
+    Ok(())
+}
+
 /// List all available session files
 pub fn list_sessions() -> Result<Vec<(String, PathBuf)>> {
     let session_dir = ensure_session_dir()?;
@@ -430,14 +456,31 @@ pub fn read_messages_with_truncation(
     session_file: &Path,
     max_content_size: Option<usize>,
 ) -> Result<Conversation> {
-    // Security check: file size limit
-    if session_file.exists() {
-        let metadata = fs::metadata(session_file)?;
-        if metadata.len() > MAX_FILE_SIZE {
-            tracing::warn!("Session file exceeds size limit: {} bytes", metadata.len());
-            return Err(anyhow::anyhow!("Session file too large"));
+    loop {
+        if needs_recovery(session_file) {
+            let _lock = SessionLock::acquire(session_file)?;
+            recover_session_file(session_file)?;
+        }
+        // A shared lock keeps writers out, so we never observe another process's half-written
+        // append, while letting any number of readers in
+        let _lock = SessionLock::acquire_shared(session_file)?;
+        if !needs_recovery(session_file) {
+            return read_messages_locked(session_file, max_content_size);
         }
     }
+}
+
+/// Whether a crashed writer left a journal behind or the session file is missing
+fn needs_recovery(session_file: &Path) -> bool {
+    journal::journal_path(session_file).exists() || !session_file.exists()
+}
+
+/// Finish any append that a crashed writer left behind, and restore a missing session file
+/// from its backup, or create an empty one when there is none.
+///
+/// The caller must hold the session lock exclusively.
+fn recover_session_file(session_file: &Path) -> Result<()> {
+    journal::replay_journal(session_file)?;
 
     // Check if there's a backup file we should restore from
     let backup_file = session_file.with_extension("backup");
@@ -456,16 +499,38 @@ pub fn read_messages_with_truncation(
         }
     }
 
-    // Open the file with appropriate options
-    let file = fs::OpenOptions::new()
-        .read(true)
+    fs::OpenOptions::new()
         .write(true)
         .create(true)
         .truncate(false)
         .open(session_file)?;
+    Ok(())
+}
+
+/// See `read_messages_with_truncation`. The caller must hold the session lock and have
+/// recovered the session file with `recover_session_file`.
+fn read_messages_locked(
+    session_file: &Path,
+    max_content_size: Option<usize>,
+) -> Result<Conversation> {
+    crypto::ensure_readable(session_file)?;
+
+    // Security check: file size limit
+    if session_file.exists() {
+        let metadata = fs::metadata(session_file)?;
+        if metadata.len() > MAX_FILE_SIZE {
+            tracing::warn!("Session file exceeds size limit: {} bytes", metadata.len());
+            return Err(anyhow::anyhow!("Session file too large"));
+        }
+    }
+
+    let file = fs::File::open(session_file)?;
 
     let reader = io::BufReader::new(file);
-    let mut lines = reader.lines();
+    let decoder = crypto::LineDecoder::new(session_file);
+    let mut lines = reader.lines().enumerate().map(|(index, line)| {
+        line.and_then(|l| decoder.decode(crypto::LinePosition::Line(index), l))
+    });
     let mut messages = Vec::new();
     let mut corrupted_lines = Vec::new();
     let mut line_number = 1;
@@ -606,6 +671,7 @@ pub fn read_messages_with_truncation(
         );
 
         // Create a backup of the original file
+        let backup_file = session_file.with_extension("backup");
         if !backup_file.exists() {
             if let Err(e) = fs::copy(session_file, &backup_file) {
                 println!("[SESSION] Failed to create backup file: {}", e);
@@ -686,7 +752,6 @@ fn parse_message_with_truncation(
 
 /// Truncate content within a message in place
 fn truncate_message_content_in_place(message: &mut Message, max_content_size: usize) {
-    use crate::conversation::message::MessageContent;
     use rmcp::model::{RawContent, ResourceContents};
 
     for content in &mut message.content {
@@ -1001,19 +1066,24 @@ fn truncate_json_string(json_str: &str, max_content_size: usize) -> String {
 pub fn read_metadata(session_file: &Path) -> Result<SessionMetadata> {
     // Validate the path for security
     let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
+    Ok(read_stored_metadata(&secure_path)?.unwrap_or_default())
+}
 
+/// Read the metadata line of a session file, or None when the file is missing, empty or
+/// doesn't start with metadata
+fn read_stored_metadata(secure_path: &Path) -> Result<Option<SessionMetadata>> {
     if !secure_path.exists() {
-        return Ok(SessionMetadata::default());
+        return Ok(None);
     }
 
     // Security check: file size
-    let file_metadata = fs::metadata(&secure_path)?;
+    let file_metadata = fs::metadata(secure_path)?;
     if file_metadata.len() > MAX_FILE_SIZE {
         tracing::warn!("Session file exceeds size limit during metadata read");
         return Err(anyhow::anyhow!("Session file too large"));
     }
 
-    let file = fs::File::open(&secure_path).map_err(|e| {
+    let file = fs::File::open(secure_path).map_err(|e| {
         tracing::error!("Failed to open session file for metadata read: {}", e);
         anyhow::anyhow!("Failed to access session file")
     })?;
@@ -1027,19 +1097,21 @@ pub fn read_metadata(session_file: &Path) -> Result<SessionMetadata> {
             tracing::warn!("Metadata line exceeds length limit");
             return Err(anyhow::anyhow!("Metadata line too long"));
         }
+        let first_line = crypto::LineDecoder::new(secure_path)
+            .decode(crypto::LinePosition::Line(0), first_line)?;
 
         // Try to parse as metadata
         match serde_json::from_str::<SessionMetadata>(&first_line) {
-            Ok(metadata) => Ok(metadata),
+            Ok(metadata) => Ok(Some(metadata)),
             Err(e) => {
-                // If the first line isn't metadata, return default
+                // If the first line isn't metadata, return None
                 tracing::debug!("Metadata parse error: {}", e);
-                Ok(SessionMetadata::default())
+                Ok(None)
             }
         }
     } else {
-        // Empty file, return default
-        Ok(SessionMetadata::default())
+        // Empty file
+        Ok(None)
     }
 }
 
@@ -1104,6 +1176,24 @@ pub async fn persist_messages_with_schedule_id(
             .await
         }
         _ => {
+            // Held until the file is written so no other process writes in between
+            let _lock = SessionLock::acquire(&secure_path)?;
+            journal::replay_journal(&secure_path)?;
+
+            // If only new messages were added, append them instead of rewriting the file
+            if secure_path.exists() {
+                let existing = read_metadata(&secure_path)?;
+                let metadata_unchanged = working_dir
+                    .as_ref()
+                    .is_none_or(|dir| *dir == existing.working_dir)
+                    && schedule_id
+                        .as_ref()
+                        .is_none_or(|id| existing.schedule_id.as_ref() == Some(id));
+                if metadata_unchanged && append_locked(&secure_path, messages)? {
+                    return Ok(());
+                }
+            }
+
             // Read existing metadata or create new with proper working_dir
             let mut metadata = if secure_path.exists() {
                 read_metadata(&secure_path)?
@@ -1122,9 +1212,10 @@ pub async fn persist_messages_with_schedule_id(
             if schedule_id.is_some() {
                 metadata.schedule_id = schedule_id;
             }
+            metadata.message_count = messages.len();
 
             // Write the file with metadata and messages
-            save_messages_with_metadata(&secure_path, &metadata, messages)
+            write_session_file(&secure_path, &metadata, messages)
         }
     }
 }
@@ -1132,9 +1223,9 @@ pub async fn persist_messages_with_schedule_id(
 /// Write messages to a session file with the provided metadata using secure atomic operations
 ///
 /// This function uses atomic file operations to prevent corruption:
-/// 1. Writes to a temporary file first with secure permissions
-/// 2. Uses fs2 file locking to prevent concurrent writes
-/// 3. Atomically moves the temp file to the final location
+/// 1. Holds the session lock so other goose processes can't write at the same time
+/// 2. Writes to a temporary file first with secure permissions
+/// 3. Atomically moves the temp file to the final location, superseding any pending journal
 /// 4. Includes comprehensive error handling and recovery
 ///
 /// Security features:
@@ -1147,10 +1238,20 @@ pub fn save_messages_with_metadata(
     metadata: &SessionMetadata,
     messages: &Conversation,
 ) -> Result<()> {
-    use fs2::FileExt;
-
     // Validate the path for security
     let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
+    let _lock = SessionLock::acquire(&secure_path)?;
+    write_session_file(&secure_path, metadata, messages)
+}
+
+/// Rewrite a session file, see `save_messages_with_metadata`. The caller must hold the
+/// session lock.
+fn write_session_file(
+    secure_path: &Path,
+    metadata: &SessionMetadata,
+    messages: &Conversation,
+) -> Result<()> {
+    use fs2::FileExt;
 
     // Security check: message count limit
     if messages.len() > MAX_MESSAGE_COUNT {
@@ -1205,20 +1306,32 @@ pub fn save_messages_with_metadata(
     {
         let mut writer = io::BufWriter::new(&file);
 
+        let encoder = crypto::LineEncoder::from_config(secure_path)?;
+
         // Write metadata as the first line
-        serde_json::to_writer(&mut writer, &metadata).map_err(|e| {
+        let metadata_line = serde_json::to_string(&metadata).map_err(|e| {
             tracing::error!("Failed to serialize metadata: {}", e);
             anyhow::anyhow!("Failed to write session metadata")
         })?;
-        writeln!(writer)?;
+        writeln!(
+            writer,
+            "{}",
+            journal::pad_metadata_line(
+                encoder.encode(crypto::LinePosition::Line(0), metadata_line)?
+            )
+        )?;
 
         // Write all messages with progress tracking
         for (i, message) in messages.iter().enumerate() {
-            serde_json::to_writer(&mut writer, &message).map_err(|e| {
+            let message_line = serde_json::to_string(&message).map_err(|e| {
                 tracing::error!("Failed to serialize message {}: {}", i, e);
                 anyhow::anyhow!("Failed to write session message")
             })?;
-            writeln!(writer)?;
+            writeln!(
+                writer,
+                "{}",
+                encoder.encode(crypto::LinePosition::Line(i + 1), message_line)?
+            )?;
         }
 
         // Ensure all data is written to disk
@@ -1241,17 +1354,226 @@ pub fn save_messages_with_metadata(
     })?;
 
     // Atomically move the temporary file to the final location
-    fs::rename(&temp_file, &secure_path).map_err(|e| {
+    fs::rename(&temp_file, secure_path).map_err(|e| {
         // Clean up temp file on failure
         tracing::error!("Failed to move temporary file: {}", e);
         let _ = fs::remove_file(&temp_file);
         anyhow::anyhow!("Failed to finalize session file")
     })?;
+    journal::discard_journal(secure_path);
 
     tracing::debug!("Successfully saved session file: {:?}", secure_path);
     Ok(())
 }
 
+/// Append the messages that are not yet in the session file, if the file holds a prefix of
+/// the conversation
+///
+/// Returns false when the file's messages are not a prefix of `messages` (e.g. after the
+/// history was compacted or the last message was extended), in which case the caller needs
+/// to rewrite the whole file. New messages go through the write-ahead journal, so a crash
+/// mid-append is repaired on the next read. The metadata line is updated with the new
+/// message count, in place when it still fits.
+pub fn append_new_messages(session_file: &Path, messages: &Conversation) -> Result<bool> {
+    let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
+    let _lock = SessionLock::acquire(&secure_path)?;
+    append_locked(&secure_path, messages)
+}
+
+/// See `append_new_messages`. The caller must hold the session lock.
+fn append_locked(secure_path: &Path, messages: &Conversation) -> Result<bool> {
+    if messages.len() > MAX_MESSAGE_COUNT {
+        tracing::warn!(
+            "Message count exceeds limit during append: {}",
+            messages.len()
+        );
+        return Err(anyhow::anyhow!("Too many messages to save"));
+    }
+
+    journal::replay_journal(secure_path)?;
+
+    let file = fs::File::open(secure_path)?;
+    let decoder = crypto::LineDecoder::new(secure_path);
+    let lines = io::BufReader::new(file)
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            line.and_then(|l| decoder.decode(crypto::LinePosition::Line(index), l))
+        })
+        .collect::<Result<Vec<String>, _>>()?;
+
+    // The first line must be metadata for the file to be appendable
+    let mut metadata = match lines.first() {
+        Some(first) => match serde_json::from_str::<SessionMetadata>(first) {
+            Ok(metadata) => metadata,
+            Err(_) => return Ok(false),
+        },
+        None => return Ok(false),
+    };
+
+    let persisted = lines.len() - 1;
+    if persisted > messages.len() {
+        return Ok(false);
+    }
+    for (line, message) in lines[1..].iter().zip(messages.iter()) {
+        if *line != serde_json::to_string(message)? {
+            return Ok(false);
+        }
+    }
+
+    let new_messages = &messages.messages()[persisted..];
+    if !new_messages.is_empty() {
+        // Only the new lines are written; the metadata line is only touched when it changes,
+        // which usually happens in place
+        let stored_count = metadata.message_count;
+        metadata.message_count = messages.len();
+        let metadata =
+            (metadata.message_count != stored_count).then(|| serde_json::to_string(&metadata));
+        journal::append_with_journal(secure_path, metadata.transpose()?, new_messages)?;
+    }
+    Ok(true)
+}
+
+/// Switch session encryption to `mode` and rewrite every existing session file to match
+///
+/// Lines are decrypted with whatever keys are available and re-encoded as they are, so this
+/// encrypts plaintext sessions, re-encrypts or decrypts sessions written under a previous
+/// mode, and never loses content to parsing. Backups kept next to a session are rewritten
+/// too. The configured mode only changes once every file is rewritten, so a migration that
+/// fails part way leaves the configuration as it was; the files already rewritten stay
+/// readable because reads try every available key. Returns the number of sessions rewritten.
+pub fn migrate_session_encryption(mode: crypto::EncryptionMode) -> Result<usize> {
+    let mut migrated = 0;
+    for (id, path) in list_sessions()? {
+        let _lock = SessionLock::acquire(&path)?;
+        journal::replay_journal(&path)?;
+
+        for file in [
+            path.clone(),
+            path.with_extension("backup"),
+            path.with_extension("repair.bak"),
+        ] {
+            if file.exists() {
+                reencode_session_file(&file, mode).map_err(|e| {
+                    tracing::error!("Failed to migrate session {}: {}", id, e);
+                    anyhow::anyhow!("Failed to migrate session {}: {}", id, e)
+                })?;
+            }
+        }
+        migrated += 1;
+    }
+
+    crypto::set_encryption_mode(mode)?;
+    Ok(migrated)
+}
+
+fn reencode_session_file(file: &Path, mode: crypto::EncryptionMode) -> Result<()> {
+    let encoder = crypto::LineEncoder::for_mode(mode, file)?;
+    let decoder = crypto::LineDecoder::new(file);
+    let temp_file = file.with_extension("migrate.tmp");
+    {
+        let reader = io::BufReader::new(fs::File::open(file)?);
+        let output = fs::File::create(&temp_file)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&temp_file, fs::Permissions::from_mode(0o600))?;
+        }
+        let mut writer = io::BufWriter::new(output);
+        for (index, line) in reader.lines().enumerate() {
+            let position = crypto::LinePosition::Line(index);
+            let line = decoder.decode(position, line?)?;
+            let line = encoder.encode(position, line.trim_end().to_string())?;
+            if index == 0 {
+                writeln!(writer, "{}", journal::pad_metadata_line(line))?;
+            } else {
+                writeln!(writer, "{}", line)?;
+            }
+        }
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+    }
+    fs::rename(&temp_file, file).inspect_err(|_| {
+        let _ = fs::remove_file(&temp_file);
+    })?;
+    Ok(())
+}
+
+/// Run stored messages through `repair_conversation`, keeping the trailing assistant reply a
+/// stored session legitimately ends with
+fn repair_stored_messages(
+    messages: Conversation,
+    policy: &RepairPolicy,
+) -> (Conversation, Vec<ConversationIssue>) {
+    let policy = RepairPolicy {
+        keep_trailing_assistant: true,
+        ..*policy
+    };
+    repair_conversation(messages, &policy)
+}
+
+/// Check a session file for conversation issues without changing it
+pub fn diagnose_session(
+    session_file: &Path,
+    policy: &RepairPolicy,
+) -> Result<Vec<ConversationIssue>> {
+    let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
+    if !secure_path.exists() {
+        return Err(anyhow::anyhow!("Session file not found"));
+    }
+
+    let messages = read_messages(&secure_path)?;
+    if messages.is_empty() {
+        return Ok(Vec::new());
+    }
+    let (_fixed, issues) = repair_stored_messages(messages, policy);
+    Ok(issues)
+}
+
+/// Rebuild a valid conversation from a damaged session file
+///
+/// Replays any interrupted write, recovers what it can from corrupted lines and then runs
+/// the conversation through `fix_conversation`. The original file is kept next to the
+/// session with a `.repair.bak` extension. Returns the list of fixes that were applied.
+pub fn repair_session(session_file: &Path) -> Result<Vec<String>> {
+    let issues = repair_session_with_policy(session_file, &RepairPolicy::default())?;
+    Ok(issues.iter().map(|issue| issue.to_string()).collect())
+}
+
+/// Same as `repair_session`, but with control over how orphaned tool requests and
+/// consecutive messages are repaired
+pub fn repair_session_with_policy(
+    session_file: &Path,
+    policy: &RepairPolicy,
+) -> Result<Vec<ConversationIssue>> {
+    let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
+    if !secure_path.exists() {
+        return Err(anyhow::anyhow!("Session file not found"));
+    }
+
+    // Hold the lock from the read to the write so no message appended meanwhile is lost
+    let _lock = SessionLock::acquire(&secure_path)?;
+    recover_session_file(&secure_path)?;
+    let messages = read_messages_locked(&secure_path, Some(50000))?;
+    if messages.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let (fixed, issues) = repair_stored_messages(messages, policy);
+
+    let backup = secure_path.with_extension("repair.bak");
+    fs::copy(&secure_path, &backup).map_err(|e| {
+        tracing::error!("Failed to back up session before repair: {}", e);
+        anyhow::anyhow!("Failed to back up session file")
+    })?;
+
+    let mut metadata = read_metadata(&secure_path)?;
+    metadata.message_count = fixed.len();
+    write_session_file(&secure_path, &metadata, &fixed)?;
+
+    Ok(issues)
+}
+
 /// Generate a description for the session using the provider
 ///
 /// This function is called when appropriate to generate a short description
@@ -1304,6 +1626,8 @@ pub async fn generate_description_with_schedule_id(
             anyhow::anyhow!("Failed to generate session description")
         })?;
 
+    let _lock = SessionLock::acquire(&secure_path)?;
+
     // Create metadata with proper working_dir or read existing and update
     let mut metadata = if secure_path.exists() {
         read_metadata(&secure_path)?
@@ -1325,7 +1649,7 @@ pub async fn generate_description_with_schedule_id(
     }
 
     // Update the file with the new metadata and existing messages
-    save_messages_with_metadata(&secure_path, &metadata, messages)
+    write_session_file(&secure_path, &metadata, messages)
 }
 
 /// Update only the metadata in a session file, preserving all messages
@@ -1334,20 +1658,77 @@ pub async fn generate_description_with_schedule_id(
 /// - Validates file paths to prevent directory traversal
 /// - Uses secure file operations for reading and writing
 pub async fn update_metadata(session_file: &Path, metadata: &SessionMetadata) -> Result<()> {
-    // Validate the path for security
+    update_metadata_with(session_file, |stored| {
+        *stored = metadata.clone();
+        Ok(())
+    })
+}
+
+/// Read, change and write back the metadata of a session file, preserving all messages
+///
+/// The session lock is held from the read to the write, so neither messages appended nor
+/// metadata changed by another process in between are lost. Nothing is written when `update`
+/// leaves the metadata as it was.
+pub fn update_metadata_with<T>(
+    session_file: &Path,
+    update: impl FnOnce(&mut SessionMetadata) -> Result<T>,
+) -> Result<T> {
     let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
+    let _lock = SessionLock::acquire(&secure_path)?;
+    recover_session_file(&secure_path)?;
+
+    let stored = read_stored_metadata(&secure_path)?;
+    let mut metadata = stored.clone().unwrap_or_default();
+    let result = update(&mut metadata)?;
+    let metadata_line = serde_json::to_string(&metadata)?;
+
+    match stored {
+        Some(stored) if serde_json::to_string(&stored)? == metadata_line => {}
+        Some(_) => journal::append_with_journal(&secure_path, Some(metadata_line), &[])?,
+        None => {
+            // The file has no metadata line to replace yet
+            let messages = read_messages_locked(&secure_path, None)?;
+            write_session_file(&secure_path, &metadata, &messages)?;
+        }
+    }
+    Ok(result)
+}
 
-    // Read all messages from the file
-    let messages = read_messages(&secure_path)?;
+/// Pin or unpin the message at `index` in a session file, returning the updated message or
+/// None when the session has no message at `index`
+///
+/// The whole file is rewritten, since appending only picks up new messages. The session lock
+/// is held from the read to the write, so no message written in between is lost.
+pub fn set_message_pinned(
+    session_file: &Path,
+    index: usize,
+    pinned: bool,
+) -> Result<Option<Message>> {
+    let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
+    let _lock = SessionLock::acquire(&secure_path)?;
+    recover_session_file(&secure_path)?;
 
-    // Rewrite the file with the new metadata and existing messages
-    save_messages_with_metadata(&secure_path, metadata, &messages)
+    let mut messages = read_messages_locked(&secure_path, None)?.messages().clone();
+    let Some(message) = messages.get_mut(index) else {
+        return Ok(None);
+    };
+    message.pinned = pinned;
+    let updated = message.clone();
+
+    let metadata = read_metadata(&secure_path)?;
+    write_session_file(
+        &secure_path,
+        &metadata,
+        &Conversation::new_unvalidated(messages),
+    )?;
+    Ok(Some(updated))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::conversation::message::{Message, MessageContent};
+    use serde_json::json;
     use tempfile::tempdir;
 
     #[test]
@@ -1971,4 +2352,97 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_persist_appends_new_messages() -> Result<()> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("append.jsonl");
+
+        let mut messages = Conversation::new_unvalidated(vec![
+            Message::user().with_text("Hello"),
+            Message::assistant().with_text("Hi there"),
+        ]);
+        persist_messages(&file_path, &messages, None, None).await?;
+
+        messages.push(Message::user().with_text("Another question"));
+        assert!(append_new_messages(&file_path, &messages)?);
+        assert!(!journal::journal_path(&file_path).exists());
+
+        let read_back = read_messages(&file_path)?;
+        assert_eq!(read_back.len(), 3);
+        assert_eq!(read_back.messages()[2].as_concat_text(), "Another question");
+        assert_eq!(read_metadata(&file_path)?.message_count, 3);
+
+        // A conversation that no longer starts with the persisted messages can't be appended
+        let replaced = Conversation::new_unvalidated(vec![Message::user().with_text("Summary")]);
+        assert!(!append_new_messages(&file_path, &replaced)?);
+        let mut edited = messages.messages().clone();
+        edited[1] = Message::assistant().with_text("Edited");
+        edited.push(Message::assistant().with_text("Answer"));
+        assert!(!append_new_messages(
+            &file_path,
+            &Conversation::new_unvalidated(edited)
+        )?);
+
+        let artifacts_dir = artifacts::artifacts_dir(&file_path);
+        fs::create_dir_all(&artifacts_dir)?;
+        fs::write(artifacts_dir.join("report.md"), "report")?;
+
+        delete_session(&file_path)?;
+        assert!(!file_path.exists());
+        assert!(!journal::lock_path(&file_path).exists());
+        assert!(!artifacts_dir.exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_repair_session_keeps_trailing_assistant_reply() -> Result<()> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("repair.jsonl");
+
+        let messages = Conversation::new_unvalidated(vec![
+            Message::user().with_text("Hello"),
+            Message::assistant()
+                .with_tool_request("orphan", Ok(mcp_core::ToolCall::new("search", json!({})))),
+            Message::assistant().with_text("Done"),
+        ]);
+        save_messages_with_metadata(&file_path, &SessionMetadata::default(), &messages)?;
+
+        let issues = repair_session(&file_path)?;
+        assert!(issues
+            .iter()
+            .any(|i| i.contains("Removed orphaned tool request")));
+
+        let repaired = read_messages(&file_path)?;
+        assert_eq!(repaired.len(), 2);
+        assert_eq!(repaired.messages()[1].as_concat_text(), "Done");
+        assert!(file_path.with_extension("repair.bak").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_metadata_with_keeps_appended_messages() -> Result<()> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("metadata.jsonl");
+
+        let mut messages = Conversation::new_unvalidated(vec![Message::user().with_text("Hello")]);
+        save_messages_with_metadata(&file_path, &SessionMetadata::default(), &messages)?;
+        messages.push(Message::assistant().with_text("Hi"));
+        assert!(append_new_messages(&file_path, &messages)?);
+
+        update_metadata_with(&file_path, |metadata| {
+            metadata.description = "Greetings".to_string();
+            Ok(())
+        })?;
+
+        let metadata = read_metadata(&file_path)?;
+        assert_eq!(metadata.description, "Greetings");
+        assert_eq!(metadata.message_count, 2);
+        assert_eq!(read_messages(&file_path)?.len(), 2);
+        assert!(!journal::journal_path(&file_path).exists());
+
+        Ok(())
+    }
 }
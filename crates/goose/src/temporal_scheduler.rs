@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::process::Command;
 use std::sync::Arc;
 use std::time::Duration;
@@ -9,7 +10,12 @@ use serde::{Deserialize, Serialize};
 use tokio::time::sleep;
 use tracing::{info, warn};
 
-use crate::scheduler::{normalize_cron_expression, ScheduledJob, SchedulerError};
+use crate::config::Config;
+use crate::scheduler::{
+    normalize_cron_expression, validate_job_id, CatchUpPolicy, OverlapPolicy, ScheduledJob,
+    SchedulerError,
+};
+use crate::scheduler_history::JobRun;
 use crate::scheduler_trait::SchedulerTrait;
 use crate::session::storage::SessionMetadata;
 
@@ -27,6 +33,19 @@ struct JobRequest {
     cron: Option<String>,
     recipe_path: Option<String>,
     execution_mode: Option<String>,
+    #[serde(flatten)]
+    policies: Option<SchedulePolicies>,
+}
+
+/// Overlap, catch-up and runtime policies of a schedule, mapped onto the Temporal schedule
+/// options by the service
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+struct SchedulePolicies {
+    overlap_policy: OverlapPolicy,
+    catch_up: CatchUpPolicy,
+    catch_up_window_seconds: Option<u64>,
+    max_runtime_seconds: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -48,6 +67,8 @@ struct TemporalJobStatus {
     paused: bool,
     created_at: String,
     execution_mode: Option<String>,
+    #[serde(flatten)]
+    policies: SchedulePolicies,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -309,6 +330,11 @@ impl TemporalScheduler {
         command
             .current_dir(working_dir)
             .env("PORT", self.port_config.http_port.to_string());
+        if let Ok(max_jobs) =
+            Config::global().get_param::<usize>("GOOSE_SCHEDULER_MAX_CONCURRENT_JOBS")
+        {
+            command.env("GOOSE_SCHEDULER_MAX_CONCURRENT_JOBS", max_jobs.to_string());
+        }
 
         // Platform-specific process configuration based on Electron app approach
         #[cfg(windows)]
@@ -546,6 +572,39 @@ impl TemporalScheduler {
             job.id
         );
 
+        validate_job_id(&job.id)?;
+
+        // The Temporal service only stores the recipe, cron and execution mode
+        if !job.parameters.is_empty()
+            || job.provider.is_some()
+            || job.model.is_some()
+            || job.working_dir.is_some()
+            || !job.extensions.is_empty()
+        {
+            return Err(SchedulerError::SchedulerInternalError(format!(
+                "Job '{}' sets parameters, provider, model, working directory or extensions, which the Temporal scheduler does not support",
+                job.id
+            )));
+        }
+        if !job.triggers.is_empty() {
+            return Err(SchedulerError::SchedulerInternalError(format!(
+                "Job '{}' has triggers, which the Temporal scheduler does not support; it only runs jobs on cron",
+                job.id
+            )));
+        }
+        if job.retry.is_some() {
+            return Err(SchedulerError::SchedulerInternalError(format!(
+                "Job '{}' has a retry policy, which the Temporal scheduler does not support",
+                job.id
+            )));
+        }
+        if job.catch_up == CatchUpPolicy::Latest {
+            return Err(SchedulerError::SchedulerInternalError(format!(
+                "Job '{}' catches up on the latest missed run only, which the Temporal scheduler does not support; use none or all",
+                job.id
+            )));
+        }
+
         // Normalize the cron expression to ensure it's 6-field format
         let normalized_cron = normalize_cron_expression(&job.cron);
         if normalized_cron != job.cron {
@@ -562,6 +621,12 @@ impl TemporalScheduler {
             cron: Some(normalized_cron.clone()),
             recipe_path: Some(job.source.clone()),
             execution_mode: job.execution_mode.clone(),
+            policies: Some(SchedulePolicies {
+                overlap_policy: job.overlap,
+                catch_up: job.catch_up,
+                catch_up_window_seconds: job.catch_up_window_seconds,
+                max_runtime_seconds: job.max_runtime_seconds,
+            }),
         };
 
         let response = self.make_request(request).await?;
@@ -582,6 +647,7 @@ impl TemporalScheduler {
             cron: None,
             recipe_path: None,
             execution_mode: None,
+            policies: None,
         };
 
         let response = self.make_request(request).await?;
@@ -601,6 +667,17 @@ impl TemporalScheduler {
                         current_session_id: None, // Not provided by Temporal service
                         process_start_time: None, // Not provided by Temporal service
                         execution_mode: tj.execution_mode,
+                        parameters: HashMap::new(),
+                        provider: None,
+                        model: None,
+                        working_dir: None,
+                        extensions: Vec::new(),
+                        triggers: Vec::new(),
+                        overlap: tj.policies.overlap_policy,
+                        catch_up: tj.policies.catch_up,
+                        catch_up_window_seconds: tj.policies.catch_up_window_seconds,
+                        max_runtime_seconds: tj.policies.max_runtime_seconds,
+                        retry: None,
                     }
                 })
                 .collect();
@@ -617,6 +694,7 @@ impl TemporalScheduler {
             cron: None,
             recipe_path: None,
             execution_mode: None,
+            policies: None,
         };
 
         let response = self.make_request(request).await?;
@@ -636,6 +714,7 @@ impl TemporalScheduler {
             cron: None,
             recipe_path: None,
             execution_mode: None,
+            policies: None,
         };
 
         let response = self.make_request(request).await?;
@@ -655,6 +734,7 @@ impl TemporalScheduler {
             cron: None,
             recipe_path: None,
             execution_mode: None,
+            policies: None,
         };
 
         let response = self.make_request(request).await?;
@@ -675,6 +755,7 @@ impl TemporalScheduler {
             cron: None,
             recipe_path: None,
             execution_mode: None,
+            policies: None,
         };
 
         let response = self.make_request(request).await?;
@@ -699,8 +780,19 @@ impl TemporalScheduler {
         }
     }
 
-    // Note: This method fetches sessions from the session storage directly
-    // since Temporal service doesn't track session metadata
+    /// The Temporal service does not report how runs went or what started them, so there is no
+    /// run history to return; sessions of the job are available through `sessions`
+    pub async fn run_history(
+        &self,
+        sched_id: &str,
+        _limit: usize,
+    ) -> Result<Vec<JobRun>, SchedulerError> {
+        Err(SchedulerError::Unsupported(format!(
+            "Run history of job '{}' is not available from the Temporal scheduler",
+            sched_id
+        )))
+    }
+
     pub async fn sessions(
         &self,
         sched_id: &str,
@@ -775,6 +867,7 @@ impl TemporalScheduler {
             cron: Some(normalized_cron),
             recipe_path: None,
             execution_mode: None,
+            policies: None,
         };
 
         let response = self.make_request(request).await?;
@@ -799,6 +892,7 @@ impl TemporalScheduler {
             cron: None,
             recipe_path: None,
             execution_mode: None,
+            policies: None,
         };
 
         let response = self.make_request(request).await?;
@@ -825,6 +919,7 @@ impl TemporalScheduler {
                     cron: None,
                     recipe_path: None,
                     execution_mode: None,
+                    policies: None,
                 };
 
                 match self.make_request(request).await {
@@ -907,6 +1002,7 @@ impl TemporalScheduler {
                         cron: None,
                         recipe_path: None,
                         execution_mode: None,
+                        policies: None,
                     };
 
                     if let Err(e) = self.make_request(request).await {
@@ -954,6 +1050,7 @@ impl TemporalScheduler {
             cron: None,
             recipe_path: None,
             execution_mode: None,
+            policies: None,
         };
 
         let response = self.make_request(request).await?;
@@ -1216,6 +1313,14 @@ impl SchedulerTrait for TemporalScheduler {
         self.sessions(sched_id, limit).await
     }
 
+    async fn run_history(
+        &self,
+        sched_id: &str,
+        limit: usize,
+    ) -> Result<Vec<JobRun>, SchedulerError> {
+        self.run_history(sched_id, limit).await
+    }
+
     async fn update_schedule(
         &self,
         sched_id: &str,
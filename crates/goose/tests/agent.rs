@@ -357,6 +357,7 @@ mod schedule_tool_tests {
     use chrono::{DateTime, Utc};
     use goose::agents::platform_tools::PLATFORM_MANAGE_SCHEDULE_TOOL_NAME;
     use goose::scheduler::{ScheduledJob, SchedulerError};
+    use goose::scheduler_history::JobRun;
     use goose::scheduler_trait::SchedulerTrait;
     use goose::session::storage::SessionMetadata;
     use std::sync::Arc;
@@ -417,6 +418,14 @@ mod schedule_tool_tests {
             Ok(vec![])
         }
 
+        async fn run_history(
+            &self,
+            _sched_id: &str,
+            _limit: usize,
+        ) -> Result<Vec<JobRun>, SchedulerError> {
+            Ok(vec![])
+        }
+
         async fn update_schedule(
             &self,
             _sched_id: &str,
@@ -10,7 +10,8 @@ use tempfile::TempDir;
 use tokio::sync::Mutex;
 
 use goose::agents::Agent;
-use goose::scheduler::{ScheduledJob, SchedulerError};
+use goose::scheduler::{CatchUpPolicy, OverlapPolicy, ScheduledJob, SchedulerError};
+use goose::scheduler_history::JobRun;
 use goose::scheduler_trait::SchedulerTrait;
 use goose::session::storage::SessionMetadata;
 
@@ -222,6 +223,26 @@ impl SchedulerTrait for ConfigurableMockScheduler {
         }
     }
 
+    async fn run_history(
+        &self,
+        sched_id: &str,
+        _limit: usize,
+    ) -> Result<Vec<JobRun>, SchedulerError> {
+        self.log_call("run_history").await;
+
+        match self.get_behavior("run_history").await {
+            MockBehavior::NotFound(job_id) => Err(SchedulerError::JobNotFound(job_id)),
+            MockBehavior::InternalError(msg) => Err(SchedulerError::SchedulerInternalError(msg)),
+            _ => {
+                if self.jobs.lock().await.contains_key(sched_id) {
+                    Ok(vec![])
+                } else {
+                    Err(SchedulerError::JobNotFound(sched_id.to_string()))
+                }
+            }
+        }
+    }
+
     async fn update_schedule(
         &self,
         sched_id: &str,
@@ -363,6 +384,17 @@ impl ScheduleToolTestBuilder {
             current_session_id: None,
             process_start_time: None,
             execution_mode: Some("background".to_string()),
+            parameters: HashMap::new(),
+            provider: None,
+            model: None,
+            working_dir: None,
+            extensions: Vec::new(),
+            triggers: Vec::new(),
+            overlap: OverlapPolicy::default(),
+            catch_up: CatchUpPolicy::default(),
+            catch_up_window_seconds: None,
+            max_runtime_seconds: None,
+            retry: None,
         };
         {
             let mut jobs = self.scheduler.jobs.lock().await;
@@ -412,5 +444,6 @@ pub fn create_test_session_metadata(message_count: usize, working_dir: &str) ->
         accumulated_total_tokens: Some(100),
         accumulated_input_tokens: Some(50),
         accumulated_output_tokens: Some(50),
+        artifacts: Vec::new(),
     }
 }
@@ -27,13 +27,35 @@ type Recipe struct {
 	Prompt       *string `json:"prompt" yaml:"prompt"`
 }
 
+const (
+	// defaultActivityTimeout bounds a job run when the schedule has no max runtime
+	defaultActivityTimeout = 2 * time.Hour
+
+	// heartbeatInterval is how often a running job reports to Temporal, which is also how
+	// quickly it notices that it was cancelled
+	heartbeatInterval = 10 * time.Second
+
+	// heartbeatTimeout is how long Temporal waits for a heartbeat before it fails the run
+	heartbeatTimeout = 3 * heartbeatInterval
+)
+
+// activityTimeout is the schedule's max runtime, which the workflow gets as its execution
+// timeout, or the default when the schedule has none
+func activityTimeout(workflowTimeout time.Duration) time.Duration {
+	if workflowTimeout > 0 {
+		return workflowTimeout
+	}
+	return defaultActivityTimeout
+}
+
 // Workflow definition for executing Goose recipes
 func GooseJobWorkflow(ctx workflow.Context, jobID, recipePath string) (string, error) {
 	logger := workflow.GetLogger(ctx)
 	logger.Info("Starting Goose job workflow", "jobID", jobID, "recipePath", recipePath)
 
 	ao := workflow.ActivityOptions{
-		StartToCloseTimeout: 2 * time.Hour, // Allow up to 2 hours for job execution
+		StartToCloseTimeout: activityTimeout(workflow.GetInfo(ctx).WorkflowExecutionTimeout),
+		HeartbeatTimeout:    heartbeatTimeout,
 		RetryPolicy: &temporal.RetryPolicy{
 			InitialInterval:        time.Second,
 			BackoffCoefficient:     2.0,
@@ -90,14 +112,23 @@ func ExecuteGooseRecipe(ctx context.Context, jobID, recipePath string) (string,
 	subCtx, cancel := context.WithCancel(ctx)
 	defer cancel()
 
-	// Monitor for activity cancellation
+	// Heartbeat while the job runs; Temporal only delivers cancellation to activities that
+	// heartbeat, so this is also how a cancelled or timed out run kills its process
 	go func() {
-		select {
-		case <-ctx.Done():
-			logger.Info("Activity cancelled, killing process for job", "jobID", jobID)
-			globalProcessManager.KillProcess(jobID)
-		case <-subCtx.Done():
-			// Normal completion
+		ticker := time.NewTicker(heartbeatInterval)
+		defer ticker.Stop()
+		for {
+			select {
+			case <-ctx.Done():
+				logger.Info("Activity cancelled, killing process for job", "jobID", jobID)
+				globalProcessManager.KillProcess(jobID)
+				return
+			case <-subCtx.Done():
+				// Normal completion
+				return
+			case <-ticker.C:
+				activity.RecordHeartbeat(ctx, jobID)
+			}
 		}
 	}()
 
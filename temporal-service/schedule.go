@@ -10,9 +10,13 @@ import (
 	"strings"
 	"time"
 
+	enumspb "go.temporal.io/api/enums/v1"
 	"go.temporal.io/sdk/client"
 )
 
+// Temporal rejects catch-up windows shorter than this, so "none" uses it
+const minCatchupWindow = 10 * time.Second
+
 type JobStatus struct {
 	ID               string    `json:"id"`
 	CronExpr         string    `json:"cron"`
@@ -24,6 +28,16 @@ type JobStatus struct {
 	CreatedAt        time.Time `json:"created_at"`
 	ExecutionMode    *string   `json:"execution_mode,omitempty"`  // "foreground" or "background"
 	LastManualRun    *string   `json:"last_manual_run,omitempty"` // Track manual runs separately
+	SchedulePolicies
+}
+
+// SchedulePolicies controls overlapping runs, runs missed while the service was down and
+// how long a run may take
+type SchedulePolicies struct {
+	OverlapPolicy        string `json:"overlap_policy,omitempty"` // "skip", "queue" or "kill_previous"
+	CatchUp              string `json:"catch_up,omitempty"`       // "none" or "all"
+	CatchupWindowSeconds int64  `json:"catch_up_window_seconds,omitempty"`
+	MaxRuntimeSeconds    int64  `json:"max_runtime_seconds,omitempty"`
 }
 
 // Request/Response types for HTTP API
@@ -33,6 +47,7 @@ type JobRequest struct {
 	CronExpr      string `json:"cron"`
 	RecipePath    string `json:"recipe_path"`
 	ExecutionMode string `json:"execution_mode,omitempty"` // "foreground" or "background"
+	SchedulePolicies
 }
 
 type JobResponse struct {
@@ -63,6 +78,15 @@ func (ts *TemporalService) createSchedule(req JobRequest) JobResponse {
 		return JobResponse{Success: false, Message: fmt.Sprintf("Failed to store recipe: %v", err)}
 	}
 
+	overlap, err := overlapPolicy(req.OverlapPolicy)
+	if err != nil {
+		return JobResponse{Success: false, Message: err.Error()}
+	}
+	catchupWindow, err := catchupWindow(req.SchedulePolicies)
+	if err != nil {
+		return JobResponse{Success: false, Message: err.Error()}
+	}
+
 	scheduleID := fmt.Sprintf("goose-job-%s", req.JobID)
 
 	// Prepare metadata to store with the schedule as a JSON string in the Note field
@@ -78,6 +102,7 @@ func (ts *TemporalService) createSchedule(req JobRequest) JobResponse {
 		"original_path":  req.RecipePath,    // Keep original for reference
 		"execution_mode": executionMode,
 		"created_at":     time.Now().Format(time.RFC3339),
+		"policies":       req.SchedulePolicies,
 	}
 
 	// For small recipes, embed content directly in metadata
@@ -100,12 +125,15 @@ func (ts *TemporalService) createSchedule(req JobRequest) JobResponse {
 			CronExpressions: []string{req.CronExpr},
 		},
 		Action: &client.ScheduleWorkflowAction{
-			ID:        fmt.Sprintf("workflow-%s-{{.ScheduledTime.Unix}}", req.JobID),
-			Workflow:  GooseJobWorkflow,
-			Args:      []interface{}{req.JobID, req.RecipePath},
-			TaskQueue: TaskQueueName,
+			ID:                       fmt.Sprintf("workflow-%s-{{.ScheduledTime.Unix}}", req.JobID),
+			Workflow:                 GooseJobWorkflow,
+			Args:                     []interface{}{req.JobID, req.RecipePath},
+			TaskQueue:                TaskQueueName,
+			WorkflowExecutionTimeout: time.Duration(req.MaxRuntimeSeconds) * time.Second, // Zero means no limit
 		},
-		Note: string(metadataJSON), // Store metadata as JSON in the Note field
+		Overlap:       overlap,
+		CatchupWindow: catchupWindow,        // Zero keeps Temporal's default of one year
+		Note:          string(metadataJSON), // Store metadata as JSON in the Note field
 	}
 
 	ctx, cancel := context.WithTimeout(context.Background(), 10*time.Second)
@@ -125,6 +153,7 @@ func (ts *TemporalService) createSchedule(req JobRequest) JobResponse {
 		Paused:           false,
 		CreatedAt:        time.Now(),
 		ExecutionMode:    &executionMode,
+		SchedulePolicies: req.SchedulePolicies,
 	}
 	ts.scheduleJobs[req.JobID] = jobStatus
 
@@ -132,6 +161,36 @@ func (ts *TemporalService) createSchedule(req JobRequest) JobResponse {
 	return JobResponse{Success: true, Message: "Schedule created successfully"}
 }
 
+// overlapPolicy maps a goose overlap policy onto Temporal's
+func overlapPolicy(policy string) (enumspb.ScheduleOverlapPolicy, error) {
+	switch policy {
+	case "", "skip":
+		return enumspb.SCHEDULE_OVERLAP_POLICY_SKIP, nil
+	case "queue":
+		return enumspb.SCHEDULE_OVERLAP_POLICY_BUFFER_ONE, nil
+	case "kill_previous":
+		return enumspb.SCHEDULE_OVERLAP_POLICY_TERMINATE_OTHER, nil
+	default:
+		return enumspb.SCHEDULE_OVERLAP_POLICY_UNSPECIFIED, fmt.Errorf("invalid overlap policy '%s', expected skip, queue or kill_previous", policy)
+	}
+}
+
+// catchupWindow returns how far back Temporal starts runs missed while the service was down
+func catchupWindow(policies SchedulePolicies) (time.Duration, error) {
+	switch policies.CatchUp {
+	case "", "none":
+		return minCatchupWindow, nil
+	case "all":
+		window := time.Duration(policies.CatchupWindowSeconds) * time.Second
+		if window > 0 && window < minCatchupWindow {
+			window = minCatchupWindow
+		}
+		return window, nil
+	default:
+		return 0, fmt.Errorf("invalid catch-up policy '%s', expected none or all", policies.CatchUp)
+	}
+}
+
 // deleteSchedule handles the deletion of a schedule
 func (ts *TemporalService) deleteSchedule(req JobRequest) JobResponse {
 	if req.JobID == "" {
@@ -335,6 +394,13 @@ func (ts *TemporalService) listSchedules() JobResponse {
 						jobStatus.ExecutionMode = &executionMode
 					}
 
+					// Extract run policies
+					if policies, ok := metadata["policies"]; ok {
+						if policiesJSON, err := json.Marshal(policies); err == nil {
+							json.Unmarshal(policiesJSON, &jobStatus.SchedulePolicies)
+						}
+					}
+
 					// Extract creation time
 					if createdAtStr, ok := metadata["created_at"].(string); ok {
 						if createdAt, err := time.Parse(time.RFC3339, createdAtStr); err == nil {
@@ -434,8 +500,9 @@ func (ts *TemporalService) runNow(req JobRequest) JobResponse {
 
 	// Execute workflow immediately
 	workflowOptions := client.StartWorkflowOptions{
-		ID:        fmt.Sprintf("manual-%s-%d", req.JobID, now.Unix()),
-		TaskQueue: TaskQueueName,
+		ID:                       fmt.Sprintf("manual-%s-%d", req.JobID, now.Unix()),
+		TaskQueue:                TaskQueueName,
+		WorkflowExecutionTimeout: time.Duration(job.MaxRuntimeSeconds) * time.Second, // Zero means no limit
 	}
 
 	ctx, cancel := context.WithTimeout(context.Background(), 10*time.Second)
@@ -7,6 +7,7 @@ import (
 	"log"
 	"net/http"
 	"os"
+	"strconv"
 	"time"
 
 	"go.temporal.io/sdk/client"
@@ -64,7 +65,7 @@ func NewTemporalService() (*TemporalService, error) {
 	}
 
 	// Create worker
-	w := worker.New(c, TaskQueueName, worker.Options{})
+	w := worker.New(c, TaskQueueName, workerOptions())
 	w.RegisterWorkflow(GooseJobWorkflow)
 	w.RegisterActivity(ExecuteGooseRecipe)
 
@@ -280,4 +281,18 @@ func (ts *TemporalService) parseRecipeContent(content []byte) (*Recipe, error) {
 	}
 
 	return &recipe, nil
-}
\ No newline at end of file
+}
+
+// workerOptions limits the goose jobs running at once when GOOSE_SCHEDULER_MAX_CONCURRENT_JOBS is set
+func workerOptions() worker.Options {
+	options := worker.Options{}
+	if maxJobs := os.Getenv("GOOSE_SCHEDULER_MAX_CONCURRENT_JOBS"); maxJobs != "" {
+		if parsed, err := strconv.Atoi(maxJobs); err == nil && parsed > 0 {
+			options.MaxConcurrentActivityExecutionSize = parsed
+			log.Printf("Running at most %d jobs at once", parsed)
+		} else {
+			log.Printf("Ignoring invalid GOOSE_SCHEDULER_MAX_CONCURRENT_JOBS: %s", maxJobs)
+		}
+	}
+	return options
+}
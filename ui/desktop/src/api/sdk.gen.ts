@@ -1,7 +1,7 @@
 // This file is auto-generated by @hey-api/openapi-ts
 
 import type { Options as ClientOptions, TDataShape, Client } from '@hey-api/client-fetch';
-import type { AddSubRecipesData, AddSubRecipesResponse2, GetToolsData, GetToolsResponse, ReadAllConfigData, ReadAllConfigResponse, BackupConfigData, BackupConfigResponse, GetExtensionsData, GetExtensionsResponse, AddExtensionData, AddExtensionResponse, RemoveExtensionData, RemoveExtensionResponse, InitConfigData, InitConfigResponse, UpsertPermissionsData, UpsertPermissionsResponse, ProvidersData, ProvidersResponse2, ReadConfigData, RecoverConfigData, RecoverConfigResponse, RemoveConfigData, RemoveConfigResponse, UpsertConfigData, UpsertConfigResponse, ValidateConfigData, ValidateConfigResponse, ConfirmPermissionData, ManageContextData, ManageContextResponse, CreateRecipeData, CreateRecipeResponse2, DecodeRecipeData, DecodeRecipeResponse2, EncodeRecipeData, EncodeRecipeResponse2, CreateScheduleData, CreateScheduleResponse, DeleteScheduleData, DeleteScheduleResponse, ListSchedulesData, ListSchedulesResponse2, UpdateScheduleData, UpdateScheduleResponse, InspectRunningJobData, InspectRunningJobResponse, KillRunningJobData, PauseScheduleData, PauseScheduleResponse, RunNowHandlerData, RunNowHandlerResponse, SessionsHandlerData, SessionsHandlerResponse, UnpauseScheduleData, UnpauseScheduleResponse, ListSessionsData, ListSessionsResponse, GetSessionHistoryData, GetSessionHistoryResponse } from './types.gen';
+import type { AddSubRecipesData, AddSubRecipesResponse2, GetToolsData, GetToolsResponse, ReadAllConfigData, ReadAllConfigResponse, BackupConfigData, BackupConfigResponse, GetExtensionsData, GetExtensionsResponse, AddExtensionData, AddExtensionResponse, RemoveExtensionData, RemoveExtensionResponse, InitConfigData, InitConfigResponse, UpsertPermissionsData, UpsertPermissionsResponse, ProvidersData, ProvidersResponse2, ReadConfigData, RecoverConfigData, RecoverConfigResponse, RemoveConfigData, RemoveConfigResponse, UpsertConfigData, UpsertConfigResponse, ValidateConfigData, ValidateConfigResponse, ConfirmPermissionData, ContextBudgetData, ContextBudgetResponse, ManageContextData, ManageContextResponse, ListStrategiesData, ListStrategiesResponse, ValidateContextData, ValidateContextResponse, CreateRecipeData, CreateRecipeResponse2, DecodeRecipeData, DecodeRecipeResponse2, EncodeRecipeData, EncodeRecipeResponse2, ValidateRecipeParamsData, ValidateRecipeParamsResponse2, CreateScheduleData, CreateScheduleResponse, DeleteScheduleData, DeleteScheduleResponse, ListSchedulesData, ListSchedulesResponse2, UpdateScheduleData, UpdateScheduleResponse, RunHistoryHandlerData, RunHistoryHandlerResponse, InspectRunningJobData, InspectRunningJobResponse, KillRunningJobData, PauseScheduleData, PauseScheduleResponse, RunNowHandlerData, RunNowHandlerResponse, SessionsHandlerData, SessionsHandlerResponse, UnpauseScheduleData, UnpauseScheduleResponse, WebhookHandlerData, ListSessionsData, ListSessionsResponse, GetSessionHistoryData, GetSessionHistoryResponse, ListSessionArtifactsData, ListSessionArtifactsResponse, GetSessionArtifactData, SetMessagePinnedData, SetMessagePinnedResponse } from './types.gen';
 import { client as _heyApiClient } from './client.gen';
 
 export type Options<TData extends TDataShape = TDataShape, ThrowOnError extends boolean = boolean> = ClientOptions<TData, ThrowOnError> & {
@@ -158,6 +158,17 @@ export const confirmPermission = <ThrowOnError extends boolean = false>(options:
     });
 };
 
+export const contextBudget = <ThrowOnError extends boolean = false>(options: Options<ContextBudgetData, ThrowOnError>) => {
+    return (options.client ?? _heyApiClient).post<ContextBudgetResponse, unknown, ThrowOnError>({
+        url: '/context/budget',
+        ...options,
+        headers: {
+            'Content-Type': 'application/json',
+            ...options?.headers
+        }
+    });
+};
+
 export const manageContext = <ThrowOnError extends boolean = false>(options: Options<ManageContextData, ThrowOnError>) => {
     return (options.client ?? _heyApiClient).post<ManageContextResponse, unknown, ThrowOnError>({
         url: '/context/manage',
@@ -169,6 +180,24 @@ export const manageContext = <ThrowOnError extends boolean = false>(options: Opt
     });
 };
 
+export const listStrategies = <ThrowOnError extends boolean = false>(options?: Options<ListStrategiesData, ThrowOnError>) => {
+    return (options?.client ?? _heyApiClient).get<ListStrategiesResponse, unknown, ThrowOnError>({
+        url: '/context/strategies',
+        ...options
+    });
+};
+
+export const validateContext = <ThrowOnError extends boolean = false>(options: Options<ValidateContextData, ThrowOnError>) => {
+    return (options.client ?? _heyApiClient).post<ValidateContextResponse, unknown, ThrowOnError>({
+        url: '/context/validate',
+        ...options,
+        headers: {
+            'Content-Type': 'application/json',
+            ...options?.headers
+        }
+    });
+};
+
 /**
  * Create a Recipe configuration from the current session
  */
@@ -205,6 +234,20 @@ export const encodeRecipe = <ThrowOnError extends boolean = false>(options: Opti
     });
 };
 
+/**
+ * Check parameter values against the types and constraints declared by a recipe
+ */
+export const validateRecipeParams = <ThrowOnError extends boolean = false>(options: Options<ValidateRecipeParamsData, ThrowOnError>) => {
+    return (options.client ?? _heyApiClient).post<ValidateRecipeParamsResponse2, unknown, ThrowOnError>({
+        url: '/recipes/validate_params',
+        ...options,
+        headers: {
+            'Content-Type': 'application/json',
+            ...options?.headers
+        }
+    });
+};
+
 export const createSchedule = <ThrowOnError extends boolean = false>(options: Options<CreateScheduleData, ThrowOnError>) => {
     return (options.client ?? _heyApiClient).post<CreateScheduleResponse, unknown, ThrowOnError>({
         url: '/schedule/create',
@@ -241,6 +284,13 @@ export const updateSchedule = <ThrowOnError extends boolean = false>(options: Op
     });
 };
 
+export const runHistoryHandler = <ThrowOnError extends boolean = false>(options: Options<RunHistoryHandlerData, ThrowOnError>) => {
+    return (options.client ?? _heyApiClient).get<RunHistoryHandlerResponse, unknown, ThrowOnError>({
+        url: '/schedule/{id}/history',
+        ...options
+    });
+};
+
 export const inspectRunningJob = <ThrowOnError extends boolean = false>(options: Options<InspectRunningJobData, ThrowOnError>) => {
     return (options.client ?? _heyApiClient).get<InspectRunningJobResponse, unknown, ThrowOnError>({
         url: '/schedule/{id}/inspect',
@@ -283,6 +333,18 @@ export const unpauseSchedule = <ThrowOnError extends boolean = false>(options: O
     });
 };
 
+export const webhookHandler = <ThrowOnError extends boolean = false>(options: Options<WebhookHandlerData, ThrowOnError>) => {
+    return (options.client ?? _heyApiClient).post<unknown, unknown, ThrowOnError>({
+        bodySerializer: null,
+        url: '/schedule/{id}/webhook',
+        ...options,
+        headers: {
+            'Content-Type': 'text/plain',
+            ...options?.headers
+        }
+    });
+};
+
 export const listSessions = <ThrowOnError extends boolean = false>(options?: Options<ListSessionsData, ThrowOnError>) => {
     return (options?.client ?? _heyApiClient).get<ListSessionsResponse, unknown, ThrowOnError>({
         url: '/sessions',
@@ -295,4 +357,29 @@ export const getSessionHistory = <ThrowOnError extends boolean = false>(options:
         url: '/sessions/{session_id}',
         ...options
     });
+};
+
+export const listSessionArtifacts = <ThrowOnError extends boolean = false>(options: Options<ListSessionArtifactsData, ThrowOnError>) => {
+    return (options.client ?? _heyApiClient).get<ListSessionArtifactsResponse, unknown, ThrowOnError>({
+        url: '/sessions/{session_id}/artifacts',
+        ...options
+    });
+};
+
+export const getSessionArtifact = <ThrowOnError extends boolean = false>(options: Options<GetSessionArtifactData, ThrowOnError>) => {
+    return (options.client ?? _heyApiClient).get<unknown, unknown, ThrowOnError>({
+        url: '/sessions/{session_id}/artifacts/{path}',
+        ...options
+    });
+};
+
+export const setMessagePinned = <ThrowOnError extends boolean = false>(options: Options<SetMessagePinnedData, ThrowOnError>) => {
+    return (options.client ?? _heyApiClient).put<SetMessagePinnedResponse, unknown, ThrowOnError>({
+        url: '/sessions/{session_id}/messages/{message_index}/pinned',
+        ...options,
+        headers: {
+            'Content-Type': 'application/json',
+            ...options?.headers
+        }
+    });
 };
\ No newline at end of file
@@ -26,6 +26,21 @@ export type AuthorRequest = {
     metadata?: string | null;
 };
 
+/**
+ * Which cron runs missed while goose was not running are made up for on startup
+ */
+export type CatchUpPolicy = 'none' | 'latest' | 'all';
+
+export type CompactionStrategyMetadata = {
+    description: string;
+    name: string;
+};
+
+/**
+ * Which outcomes of the upstream job start a [`JobTrigger::JobCompleted`] job
+ */
+export type CompletionFilter = 'success' | 'failure' | 'any';
+
 /**
  * Configuration key metadata for provider setup
  */
@@ -62,8 +77,51 @@ export type ConfigResponse = {
     config: {};
 };
 
+/**
+ * How to repair consecutive messages with the same role
+ */
+export type ConsecutiveMessageRepair = 'merge' | 'drop';
+
 export type Content = RawTextContent | RawImageContent | RawEmbeddedResource | Annotated;
 
+/**
+ * Breakdown of the token cost of the next request to the provider
+ */
+export type ContextBudget = {
+    /**
+     * Suggestions for reducing the size of the request
+     */
+    advice: Array<string>;
+    contextLimit: number;
+    /**
+     * `.goosehints` included in the system prompt through extension instructions
+     */
+    hints: Array<TokenCost>;
+    messages: Array<MessageCost>;
+    /**
+     * The system prompt split into the base prompt and each extension's instructions
+     */
+    systemPrompt: Array<TokenCost>;
+    /**
+     * Tool schemas grouped by the extension that provides them, most expensive first
+     */
+    tools: Array<ExtensionToolsCost>;
+    /**
+     * Tokens of the whole request, as counted by `count_everything`
+     */
+    totalTokens: number;
+};
+
+/**
+ * Request payload for the context budget
+ */
+export type ContextBudgetRequest = {
+    /**
+     * Messages that would be sent with the next request
+     */
+    messages: Array<Message>;
+};
+
 export type ContextLengthExceeded = {
     msg: string;
 };
@@ -73,7 +131,8 @@ export type ContextLengthExceeded = {
  */
 export type ContextManageRequest = {
     /**
-     * Operation to perform: "truncation" or "summarize"
+     * Operation to perform: "truncation", "summarize", or the name of a compaction strategy
+     * from /context/strategies
      */
     manageAction: string;
     /**
@@ -94,6 +153,50 @@ export type ContextManageResponse = {
      * Token counts for each processed message
      */
     tokenCounts: Array<number>;
+    /**
+     * Total tokens of the messages after the operation
+     */
+    tokensAfter: number;
+    /**
+     * Total tokens of the messages before the operation
+     */
+    tokensBefore: number;
+};
+
+/**
+ * Request payload for conversation validation
+ */
+export type ContextValidateRequest = {
+    /**
+     * Messages to validate
+     */
+    messages: Array<Message>;
+    policy?: RepairPolicy;
+};
+
+/**
+ * Response from conversation validation
+ */
+export type ContextValidateResponse = {
+    /**
+     * Issues found, with the index of the message each one was found in
+     */
+    issues: Array<ConversationIssue>;
+    /**
+     * Messages after applying the repair policy
+     */
+    messages: Array<Message>;
+};
+
+/**
+ * A problem found in a conversation, the position of the message it was found in
+ * (an index into the conversation as it was passed in) and how it was repaired
+ */
+export type ConversationIssue = {
+    action: RepairAction;
+    index?: number | null;
+    kind: IssueKind;
+    severity: IssueSeverity;
 };
 
 export type CreateRecipeRequest = {
@@ -110,10 +213,26 @@ export type CreateRecipeResponse = {
 };
 
 export type CreateScheduleRequest = {
-    cron: string;
+    catch_up?: CatchUpPolicy;
+    catch_up_window_seconds?: number | null;
+    /**
+     * May be empty when the job has triggers
+     */
+    cron?: string;
     execution_mode?: string | null;
+    extensions?: Array<ExtensionConfig>;
     id: string;
+    max_runtime_seconds?: number | null;
+    model?: string | null;
+    overlap?: OverlapPolicy;
+    parameters?: {
+        [key: string]: string;
+    };
+    provider?: string | null;
     recipe_source: string;
+    retry?: JobRetryPolicy | null;
+    triggers?: Array<JobTrigger>;
+    working_dir?: string | null;
 };
 
 export type DecodeRecipeRequest = {
@@ -122,6 +241,10 @@ export type DecodeRecipeRequest = {
 
 export type DecodeRecipeResponse = {
     recipe: Recipe;
+    /**
+     * Set when the recipe is not signed by a trusted key but is allowed by the signature policy
+     */
+    warning?: string | null;
 };
 
 export type EmbeddedResource = {
@@ -266,6 +389,15 @@ export type ExtensionResponse = {
     extensions: Array<ExtensionEntry>;
 };
 
+/**
+ * Token cost of the tool schemas one extension contributes
+ */
+export type ExtensionToolsCost = {
+    extension: string;
+    tokens: number;
+    toolCount: number;
+};
+
 export type FrontendToolRequest = {
     id: string;
     toolCall: {
@@ -287,6 +419,131 @@ export type InspectJobResponse = {
     sessionId?: string | null;
 };
 
+/**
+ * What went wrong in a conversation
+ */
+export type IssueKind = {
+    type: 'emptyMessage';
+} | {
+    id: string;
+    type: 'toolRequestInUserMessage';
+} | {
+    id: string;
+    type: 'toolConfirmationRequestInUserMessage';
+} | {
+    type: 'thinkingInUserMessage';
+} | {
+    id: string;
+    type: 'orphanedToolResponse';
+} | {
+    id: string;
+    type: 'toolResponseInAssistantMessage';
+} | {
+    id: string;
+    type: 'frontendToolRequestInAssistantMessage';
+} | {
+    id: string;
+    type: 'orphanedToolRequest';
+} | {
+    role: string;
+    type: 'consecutiveMessages';
+} | {
+    type: 'leadingAssistantMessage';
+} | {
+    type: 'trailingAssistantMessage';
+} | {
+    type: 'emptyConversation';
+};
+
+/**
+ * How serious a conversation issue is
+ */
+export type IssueSeverity = 'warning' | 'error';
+
+/**
+ * How failed runs of a job are retried. Each attempt waits about `backoff_multiplier` times
+ * longer than the previous one, up to `max_backoff_seconds`, with the same jitter as provider
+ * retries.
+ */
+export type JobRetryPolicy = {
+    backoff_multiplier?: number;
+    /**
+     * Wait before the first retry
+     */
+    initial_backoff_seconds?: number;
+    /**
+     * Attempts per run, the first one included
+     */
+    max_attempts: number;
+    max_backoff_seconds?: number;
+    /**
+     * Failures worth another attempt
+     */
+    retry_on?: Array<RetryableFailure>;
+};
+
+/**
+ * One execution of a scheduled job
+ */
+export type JobRun = {
+    /**
+     * Artifact paths relative to the run's working directory
+     */
+    artifacts?: Array<string>;
+    /**
+     * Attempt of the run this entry records; retries of a failed run count up from 1
+     */
+    attempt?: number;
+    /**
+     * Estimated from the provider's pricing, when known
+     */
+    cost_usd?: number | null;
+    error?: string | null;
+    finished_at: string;
+    input_tokens?: number | null;
+    job_id: string;
+    /**
+     * The end of the final assistant reply
+     */
+    output_excerpt?: string | null;
+    output_tokens?: number | null;
+    /**
+     * Session written by the run, if it got far enough to start one
+     */
+    session_id?: string | null;
+    started_at: string;
+    status: RunStatus;
+    total_tokens?: number | null;
+    trigger: RunTrigger;
+};
+
+/**
+ * An event that runs a scheduled job
+ */
+export type JobTrigger = {
+    debounce_seconds?: number;
+    path: string;
+    type: 'file_watch';
+} | {
+    /**
+     * Recipe parameter receiving the request body
+     */
+    parameter?: string | null;
+    /**
+     * Given when the trigger is created; only its hash is kept
+     */
+    secret?: string | null;
+    /**
+     * Hex encoded SHA-256 of the secret
+     */
+    secret_sha256?: string | null;
+    type: 'webhook';
+} | {
+    job_id: string;
+    on?: CompletionFilter;
+    type: 'job_completed';
+};
+
 export type KillJobResponse = {
     message: string;
 };
@@ -302,6 +559,10 @@ export type Message = {
     content: Array<MessageContent>;
     created?: number;
     id?: string | null;
+    /**
+     * Pinned messages are never summarized or truncated away during compaction
+     */
+    pinned?: boolean;
     role: Role;
 };
 
@@ -330,6 +591,20 @@ export type MessageContent = (TextContent & {
     type: 'summarizationRequested';
 });
 
+/**
+ * Token cost of one message in the conversation
+ */
+export type MessageCost = {
+    index: number;
+    pinned: boolean;
+    /**
+     * Start of the message text, or the tool it calls or answers
+     */
+    preview: string;
+    role: string;
+    tokens: number;
+};
+
 /**
  * Information about a model's capabilities
  */
@@ -360,6 +635,16 @@ export type ModelInfo = {
     supports_cache_control?: boolean | null;
 };
 
+/**
+ * How to repair tool requests that never got a response
+ */
+export type OrphanedRequestRepair = 'drop' | 'synthesizeErrorResponse';
+
+/**
+ * What to do when a job is started while a run of it is still going
+ */
+export type OverlapPolicy = 'skip' | 'queue' | 'kill_previous';
+
 export type PermissionConfirmationRequest = {
     action: string;
     id: string;
@@ -371,6 +656,11 @@ export type PermissionConfirmationRequest = {
  */
 export type PermissionLevel = 'always_allow' | 'ask_before' | 'never_allow';
 
+/**
+ * Part of a recipe whose message is pinned, so it survives context compaction word for word
+ */
+export type PinnedSection = 'prompt' | 'context';
+
 export type PrincipalType = 'Extension' | 'Tool';
 
 export type ProviderDetails = {
@@ -452,6 +742,11 @@ export type RawTextContent = {
  * * `parameters` - Additional parameters for the Recipe
  * * `response` - Response configuration including JSON schema validation
  * * `retry` - Retry configuration for automated validation and recovery
+ * * `pinned` - Parts of the recipe (`prompt`, `context`) kept verbatim through context compaction
+ * * `steps` - Steps run as a DAG before the prompt, each with instructions or a recipe
+ * * `extends` - Path or name of a recipe this one builds on, see [`compose`]
+ * * `include` - Files whose content is put in front of the instructions
+ * * `artifacts` - Globs of output files collected into the session after a run
  * # Example
  *
  *
@@ -481,21 +776,31 @@ export type RawTextContent = {
  * response: None,
  * sub_recipes: None,
  * retry: None,
+ * pinned: None,
+ * steps: None,
+ * extends: None,
+ * include: None,
+ * artifacts: None,
  * };
  *
  */
 export type Recipe = {
     activities?: Array<string> | null;
+    artifacts?: Array<string> | null;
     author?: Author | null;
     context?: Array<string> | null;
     description: string;
+    extends?: string | null;
     extensions?: Array<ExtensionConfig> | null;
+    include?: Array<string> | null;
     instructions?: string | null;
     parameters?: Array<RecipeParameter> | null;
+    pinned?: Array<PinnedSection> | null;
     prompt?: string | null;
     response?: Response | null;
     retry?: RetryConfig | null;
     settings?: Settings | null;
+    steps?: Array<RecipeStep> | null;
     sub_recipes?: Array<SubRecipe> | null;
     title: string;
     version?: string;
@@ -504,9 +809,29 @@ export type Recipe = {
 export type RecipeParameter = {
     default?: string | null;
     description: string;
+    /**
+     * Pass the content of a `file` parameter to the recipe instead of its path
+     */
+    inline?: boolean | null;
     input_type: RecipeParameterInputType;
     key: string;
+    /**
+     * Largest allowed value of a `number` parameter
+     */
+    max?: number | null;
+    /**
+     * Largest allowed size in bytes of a `file` parameter
+     */
+    max_size?: number | null;
+    /**
+     * Smallest allowed value of a `number` parameter
+     */
+    min?: number | null;
     options?: Array<string> | null;
+    /**
+     * Regular expression the value has to match
+     */
+    pattern?: string | null;
     requirement: RecipeParameterRequirement;
 };
 
@@ -514,10 +839,46 @@ export type RecipeParameterInputType = 'string' | 'number' | 'boolean' | 'date'
 
 export type RecipeParameterRequirement = 'required' | 'optional' | 'user_prompt';
 
+export type RecipeStep = {
+    depends_on?: Array<string>;
+    /**
+     * Identifier used in `depends_on` and in `{{ steps.<id>.output }}`
+     */
+    id: string;
+    /**
+     * Instructions for a subagent; set either this or `recipe`
+     */
+    instructions?: string | null;
+    /**
+     * Path to a recipe to run for this step, relative to this recipe
+     */
+    recipe?: string | null;
+    response?: Response | null;
+    /**
+     * Parameters passed to `recipe`; values may use step outputs
+     */
+    values?: {
+        [key: string]: string;
+    } | null;
+};
+
 export type RedactedThinkingContent = {
     data: string;
 };
 
+/**
+ * What was done about an issue
+ */
+export type RepairAction = 'removed' | 'merged' | 'synthesizedErrorResponse' | 'addedPlaceholder';
+
+/**
+ * Policies used when repairing a conversation
+ */
+export type RepairPolicy = {
+    consecutiveMessages?: ConsecutiveMessageRepair;
+    orphanedToolRequests?: OrphanedRequestRepair;
+};
+
 export type ResourceContents = {
     mimeType?: string;
     text: string;
@@ -558,28 +919,126 @@ export type RetryConfig = {
     timeout_seconds?: number | null;
 };
 
+/**
+ * A kind of failure a [`JobRetryPolicy`] can retry
+ */
+export type RetryableFailure = 'provider' | 'success_check';
+
 export type Role = string;
 
 export type RunNowResponse = {
     session_id: string;
 };
 
+/**
+ * How a run ended
+ */
+export type RunStatus = 'success' | 'failed' | 'killed' | 'timed_out';
+
+/**
+ * What started a run
+ */
+export type RunTrigger = 'cron' | 'catch_up' | 'manual' | 'file_watch' | 'webhook' | 'job_completed';
+
 export type ScheduledJob = {
+    catch_up?: CatchUpPolicy;
+    /**
+     * Only missed runs this recent are made up for; all of them when unset
+     */
+    catch_up_window_seconds?: number | null;
     cron: string;
     current_session_id?: string | null;
     currently_running?: boolean;
     execution_mode?: string | null;
+    /**
+     * Extensions added to the ones declared by the recipe
+     */
+    extensions?: Array<ExtensionConfig>;
     id: string;
     last_run?: string | null;
+    /**
+     * Runs going longer than this are killed and recorded as timed out
+     */
+    max_runtime_seconds?: number | null;
+    /**
+     * Model to run the job with instead of `GOOSE_MODEL`
+     */
+    model?: string | null;
+    overlap?: OverlapPolicy;
+    /**
+     * Values for the recipe's parameters
+     */
+    parameters?: {
+        [key: string]: string;
+    };
     paused?: boolean;
     process_start_time?: string | null;
+    /**
+     * Provider to run the job with instead of `GOOSE_PROVIDER`
+     */
+    provider?: string | null;
+    retry?: JobRetryPolicy | null;
     source: string;
+    /**
+     * Events that run the job besides its cron expression, which may be empty when there
+     * are triggers
+     */
+    triggers?: Array<JobTrigger>;
+    /**
+     * Working directory recorded for the job's sessions and used to collect their artifacts
+     */
+    working_dir?: string | null;
+};
+
+/**
+ * Changes to the run settings of a scheduled job; fields left out are kept, an empty
+ * `provider`, `model` or `working_dir` removes the override, a zero
+ * `catch_up_window_seconds` or `max_runtime_seconds` removes the limit and `clear_retry`
+ * removes the retry policy
+ */
+export type ScheduledJobUpdate = {
+    catch_up?: CatchUpPolicy | null;
+    catch_up_window_seconds?: number | null;
+    clear_retry?: boolean;
+    extensions?: Array<ExtensionConfig> | null;
+    max_runtime_seconds?: number | null;
+    model?: string | null;
+    overlap?: OverlapPolicy | null;
+    parameters?: {
+        [key: string]: string;
+    } | null;
+    provider?: string | null;
+    retry?: JobRetryPolicy | null;
+    triggers?: Array<JobTrigger> | null;
+    working_dir?: string | null;
+};
+
+/**
+ * An output file collected for a session
+ */
+export type SessionArtifact = {
+    /**
+     * Path relative to the working directory of the run
+     */
+    path: string;
+    /**
+     * Size in bytes
+     */
+    size: number;
+};
+
+export type SessionArtifactsResponse = {
+    /**
+     * Output files collected after the recipe run of the session
+     */
+    artifacts: Array<SessionArtifact>;
 };
 
 export type SessionDisplayInfo = {
     accumulatedInputTokens?: number | null;
     accumulatedOutputTokens?: number | null;
     accumulatedTotalTokens?: number | null;
+    artifacts: Array<SessionArtifact>;
     createdAt: string;
     id: string;
     inputTokens?: number | null;
@@ -633,6 +1092,10 @@ export type SessionMetadata = {
      * The total number of tokens used in the session. Accumulated across all messages (useful for tracking cost over an entire session).
      */
     accumulated_total_tokens?: number | null;
+    /**
+     * Output files collected from the working directory after a recipe run
+     */
+    artifacts?: Array<SessionArtifact>;
     /**
      * A short description of the session, typically 3 words or less
      */
@@ -671,7 +1134,19 @@ export type SessionsQuery = {
     limit?: number;
 };
 
+export type SetMessagePinnedRequest = {
+    /**
+     * Whether the message is kept verbatim through context compaction
+     */
+    pinned: boolean;
+};
+
 export type Settings = {
+    /**
+     * Compaction strategy to use when the conversation outgrows the context window, see
+     * `context_mgmt::strategy::strategies()` for the available names
+     */
+    compaction_strategy?: string | null;
     goose_model?: string | null;
     goose_provider?: string | null;
     temperature?: number | null;
@@ -688,7 +1163,7 @@ export type SubRecipe = {
 };
 
 /**
- * Execute a shell command and check its exit status
+ * A single success check to validate recipe completion
  */
 export type SuccessCheck = {
     /**
@@ -696,6 +1171,52 @@ export type SuccessCheck = {
      */
     command: string;
     type: 'Shell';
+} | {
+    /**
+     * Path or glob pattern, relative to the working directory
+     */
+    path: string;
+    type: 'FileExists';
+} | {
+    /**
+     * Path or glob pattern; the check passes if any matching file matches
+     */
+    path: string;
+    /**
+     * Regular expression to search for in the file content
+     */
+    pattern: string;
+    type: 'FileMatches';
+} | {
+    /**
+     * The JSON schema the output must validate against
+     */
+    schema: {
+        [key: string]: unknown;
+    };
+    type: 'JsonOutput';
+} | {
+    /**
+     * Expected status code (default: 200)
+     */
+    status?: number | null;
+    type: 'Http';
+    /**
+     * The URL to request
+     */
+    url: string;
+} | {
+    /**
+     * What a successful result looks like
+     */
+    rubric: string;
+    type: 'LlmJudge';
+} | {
+    checks: Array<SuccessCheck>;
+    type: 'All';
+} | {
+    checks: Array<SuccessCheck>;
+    type: 'Any';
 };
 
 export type SummarizationRequested = {
@@ -714,6 +1235,14 @@ export type ThinkingContent = {
     thinking: string;
 };
 
+/**
+ * Token cost of a named part of the request
+ */
+export type TokenCost = {
+    name: string;
+    tokens: number;
+};
+
 export type Tool = {
     annotations?: ToolAnnotations | {
         [key: string]: unknown;
@@ -772,8 +1301,8 @@ export type ToolResponse = {
     };
 };
 
-export type UpdateScheduleRequest = {
-    cron: string;
+export type UpdateScheduleRequest = ScheduledJobUpdate & {
+    cron?: string | null;
 };
 
 export type UpsertConfigQuery = {
@@ -786,6 +1315,23 @@ export type UpsertPermissionsQuery = {
     tool_permissions: Array<ToolPermission>;
 };
 
+export type ValidateRecipeParamsRequest = {
+    params?: {
+        [key: string]: string;
+    };
+    recipe: Recipe;
+};
+
+export type ValidateRecipeParamsResponse = {
+    errors: Array<string>;
+    /**
+     * Parameter values after defaults and coercion, as they would be passed to the recipe
+     */
+    values: {
+        [key: string]: string;
+    };
+};
+
 export type AddSubRecipesData = {
     body: AddSubRecipesRequest;
     path?: never;
@@ -1171,6 +1717,37 @@ export type ConfirmPermissionResponses = {
     200: unknown;
 };
 
+export type ContextBudgetData = {
+    body: ContextBudgetRequest;
+    path?: never;
+    query?: never;
+    url: '/context/budget';
+};
+
+export type ContextBudgetErrors = {
+    /**
+     * Unauthorized - Invalid or missing API key
+     */
+    401: unknown;
+    /**
+     * Precondition failed - Agent not available
+     */
+    412: unknown;
+    /**
+     * Internal server error
+     */
+    500: unknown;
+};
+
+export type ContextBudgetResponses = {
+    /**
+     * Token breakdown of the next request
+     */
+    200: ContextBudget;
+};
+
+export type ContextBudgetResponse = ContextBudgetResponses[keyof ContextBudgetResponses];
+
 export type ManageContextData = {
     body: ContextManageRequest;
     path?: never;
@@ -1179,6 +1756,10 @@ export type ManageContextData = {
 };
 
 export type ManageContextErrors = {
+    /**
+     * Unknown manage action
+     */
+    400: unknown;
     /**
      * Unauthorized - Invalid or missing API key
      */
@@ -1202,6 +1783,52 @@ export type ManageContextResponses = {
 
 export type ManageContextResponse = ManageContextResponses[keyof ManageContextResponses];
 
+export type ListStrategiesData = {
+    body?: never;
+    path?: never;
+    query?: never;
+    url: '/context/strategies';
+};
+
+export type ListStrategiesErrors = {
+    /**
+     * Unauthorized - Invalid or missing API key
+     */
+    401: unknown;
+};
+
+export type ListStrategiesResponses = {
+    /**
+     * Available compaction strategies
+     */
+    200: Array<CompactionStrategyMetadata>;
+};
+
+export type ListStrategiesResponse = ListStrategiesResponses[keyof ListStrategiesResponses];
+
+export type ValidateContextData = {
+    body: ContextValidateRequest;
+    path?: never;
+    query?: never;
+    url: '/context/validate';
+};
+
+export type ValidateContextErrors = {
+    /**
+     * Unauthorized - Invalid or missing API key
+     */
+    401: unknown;
+};
+
+export type ValidateContextResponses = {
+    /**
+     * Conversation validated successfully
+     */
+    200: ContextValidateResponse;
+};
+
+export type ValidateContextResponse = ValidateContextResponses[keyof ValidateContextResponses];
+
 export type CreateRecipeData = {
     body: CreateRecipeRequest;
     path?: never;
@@ -1242,9 +1869,13 @@ export type DecodeRecipeData = {
 
 export type DecodeRecipeErrors = {
     /**
-     * Bad request
+     * Bad request, or the recipe has steps, which only run from the goose CLI
      */
     400: unknown;
+    /**
+     * Recipe blocked by the signature policy
+     */
+    403: unknown;
 };
 
 export type DecodeRecipeResponses = {
@@ -1279,6 +1910,29 @@ export type EncodeRecipeResponses = {
 
 export type EncodeRecipeResponse2 = EncodeRecipeResponses[keyof EncodeRecipeResponses];
 
+export type ValidateRecipeParamsData = {
+    body: ValidateRecipeParamsRequest;
+    path?: never;
+    query?: never;
+    url: '/recipes/validate_params';
+};
+
+export type ValidateRecipeParamsErrors = {
+    /**
+     * Missing or invalid parameter values
+     */
+    400: ValidateRecipeParamsResponse;
+};
+
+export type ValidateRecipeParamsResponses = {
+    /**
+     * Parameter values are valid
+     */
+    200: ValidateRecipeParamsResponse;
+};
+
+export type ValidateRecipeParamsResponse2 = ValidateRecipeParamsResponses[keyof ValidateRecipeParamsResponses];
+
 export type CreateScheduleData = {
     body: CreateScheduleRequest;
     path?: never;
@@ -1288,7 +1942,7 @@ export type CreateScheduleData = {
 
 export type CreateScheduleErrors = {
     /**
-     * Invalid cron expression or recipe file
+     * Invalid cron expression, recipe file or job settings
      */
     400: unknown;
     /**
@@ -1401,6 +2055,44 @@ export type UpdateScheduleResponses = {
 
 export type UpdateScheduleResponse = UpdateScheduleResponses[keyof UpdateScheduleResponses];
 
+export type RunHistoryHandlerData = {
+    body?: never;
+    path: {
+        /**
+         * ID of the schedule
+         */
+        id: string;
+    };
+    query?: {
+        limit?: number;
+    };
+    url: '/schedule/{id}/history';
+};
+
+export type RunHistoryHandlerErrors = {
+    /**
+     * Scheduled job not found
+     */
+    404: unknown;
+    /**
+     * Internal server error
+     */
+    500: unknown;
+    /**
+     * The scheduler in use does not keep run history
+     */
+    501: unknown;
+};
+
+export type RunHistoryHandlerResponses = {
+    /**
+     * The newest runs of the schedule, newest first
+     */
+    200: Array<JobRun>;
+};
+
+export type RunHistoryHandlerResponse = RunHistoryHandlerResponses[keyof RunHistoryHandlerResponses];
+
 export type InspectRunningJobData = {
     body?: never;
     path: {
@@ -1579,6 +2271,42 @@ export type UnpauseScheduleResponses = {
 
 export type UnpauseScheduleResponse = UnpauseScheduleResponses[keyof UnpauseScheduleResponses];
 
+export type WebhookHandlerData = {
+    body: string;
+    headers: {
+        /**
+         * Secret of the schedule's webhook trigger
+         */
+        'X-Goose-Webhook-Secret': string;
+    };
+    path: {
+        /**
+         * ID of the schedule to run
+         */
+        id: string;
+    };
+    query?: never;
+    url: '/schedule/{id}/webhook';
+};
+
+export type WebhookHandlerErrors = {
+    /**
+     * No schedule with a webhook trigger and this secret
+     */
+    404: unknown;
+    /**
+     * Internal server error
+     */
+    500: unknown;
+};
+
+export type WebhookHandlerResponses = {
+    /**
+     * The run was started
+     */
+    202: unknown;
+};
+
 export type ListSessionsData = {
     body?: never;
     path?: never;
@@ -1642,6 +2370,124 @@ export type GetSessionHistoryResponses = {
 
 export type GetSessionHistoryResponse = GetSessionHistoryResponses[keyof GetSessionHistoryResponses];
 
+export type ListSessionArtifactsData = {
+    body?: never;
+    path: {
+        /**
+         * Unique identifier for the session
+         */
+        session_id: string;
+    };
+    query?: never;
+    url: '/sessions/{session_id}/artifacts';
+};
+
+export type ListSessionArtifactsErrors = {
+    /**
+     * Bad request - Invalid session ID
+     */
+    400: unknown;
+    /**
+     * Unauthorized - Invalid or missing API key
+     */
+    401: unknown;
+    /**
+     * Session not found
+     */
+    404: unknown;
+};
+
+export type ListSessionArtifactsResponses = {
+    /**
+     * Artifacts collected for the session
+     */
+    200: SessionArtifactsResponse;
+};
+
+export type ListSessionArtifactsResponse = ListSessionArtifactsResponses[keyof ListSessionArtifactsResponses];
+
+export type GetSessionArtifactData = {
+    body?: never;
+    path: {
+        /**
+         * Unique identifier for the session
+         */
+        session_id: string;
+        /**
+         * Path of the artifact relative to the working directory of the run
+         */
+        path: string;
+    };
+    query?: never;
+    url: '/sessions/{session_id}/artifacts/{path}';
+};
+
+export type GetSessionArtifactErrors = {
+    /**
+     * Bad request - Invalid session ID
+     */
+    400: unknown;
+    /**
+     * Unauthorized - Invalid or missing API key
+     */
+    401: unknown;
+    /**
+     * Artifact not found
+     */
+    404: unknown;
+};
+
+export type GetSessionArtifactResponses = {
+    /**
+     * Content of the artifact
+     */
+    200: unknown;
+};
+
+export type SetMessagePinnedData = {
+    body: SetMessagePinnedRequest;
+    path: {
+        /**
+         * Unique identifier for the session
+         */
+        session_id: string;
+        /**
+         * Index of the message in the session history
+         */
+        message_index: number;
+    };
+    query?: never;
+    url: '/sessions/{session_id}/messages/{message_index}/pinned';
+};
+
+export type SetMessagePinnedErrors = {
+    /**
+     * Bad request - Invalid session ID
+     */
+    400: unknown;
+    /**
+     * Unauthorized - Invalid or missing API key
+     */
+    401: unknown;
+    /**
+     * Session or message not found
+     */
+    404: unknown;
+    /**
+     * Internal server error
+     */
+    500: unknown;
+};
+
+export type SetMessagePinnedResponses = {
+    /**
+     * Message pinned or unpinned successfully
+     */
+    200: Message;
+};
+
+export type SetMessagePinnedResponse = SetMessagePinnedResponses[keyof SetMessagePinnedResponses];
+
 export type ClientOptions = {
     baseUrl: `${string}://${string}` | (string & {});
 };
\ No newline at end of file
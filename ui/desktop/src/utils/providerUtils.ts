@@ -216,6 +216,8 @@ export const initializeSystem = async (
     const recipeConfig = window.appConfig?.get?.('recipe');
     const recipe_instructions = (recipeConfig as { instructions?: string })?.instructions;
     const responseConfig = (recipeConfig as { response?: { json_schema?: unknown } })?.response;
+    const settings = (recipeConfig as { settings?: { compaction_strategy?: string } })?.settings;
+    const compactionStrategy = settings?.compaction_strategy;
     const subRecipes = (recipeConfig as { sub_recipes?: SubRecipe[] })?.sub_recipes;
     const parameters = (recipeConfig as { parameters?: RecipeParameter[] })?.parameters;
     const hasParameters = parameters && parameters?.length > 0;
@@ -243,8 +245,8 @@ export const initializeSystem = async (
     if (!hasParameters && hasSubRecipes) {
       await addSubRecipesToAgent(subRecipes);
     }
-    // Configure session with response config if present
-    if (responseConfig?.json_schema) {
+    // Configure session with response config and compaction strategy if present
+    if (responseConfig?.json_schema || compactionStrategy) {
       const sessionConfigResponse = await fetch(getApiUrl('/agent/session_config'), {
         method: 'POST',
         headers: {
@@ -252,7 +254,8 @@ export const initializeSystem = async (
           'X-Secret-Key': await window.electron.getSecretKey(),
         },
         body: JSON.stringify({
-          response: responseConfig,
+          response: responseConfig?.json_schema ? responseConfig : undefined,
+          compaction_strategy: compactionStrategy,
         }),
       });
       if (!sessionConfigResponse.ok) {